@@ -0,0 +1,189 @@
+//! Selection of the most relevant validation error.
+use crate::error::{ValidationError, ValidationErrorKind};
+use std::mem;
+
+/// Pick the most relevant error out of the given ones.
+///
+/// Errors from `anyOf` / `oneOf` keywords are not very helpful on their own, therefore
+/// this function descends into errors of their branches and picks the most relevant error there.
+/// The following heuristics are used:
+///   - Errors located deeper in the instance are preferred;
+///   - A branch where a `const` or `enum` keyword on a direct property matched (a discriminator) is
+///     preferred over branches where it didn't;
+///   - A branch with fewer errors is preferred.
+///
+/// If several errors are equally relevant, then the first one wins.
+///
+/// ```rust
+/// use jsonschema::{best_match, JSONSchema};
+/// use serde_json::json;
+///
+/// let schema = json!({
+///     "anyOf": [
+///         {"properties": {"kind": {"const": "circle"}, "radius": {"type": "number"}}},
+///         {"properties": {"kind": {"const": "square"}, "side": {"type": "number"}}}
+///     ]
+/// });
+/// let compiled = JSONSchema::compile(&schema).expect("A valid schema");
+/// let instance = json!({"kind": "square", "side": "2"});
+/// let errors = compiled.validate(&instance).expect_err("Invalid instance");
+/// let error = best_match(errors).expect("There is at least one error");
+/// assert_eq!(error.instance_path.to_string(), "/side");
+/// ```
+pub fn best_match<'a, I>(errors: I) -> Option<ValidationError<'a>>
+where
+    I: IntoIterator<Item = ValidationError<'a>>,
+{
+    let mut best = select_error(errors)?;
+    loop {
+        let context = match &mut *best.kind {
+            ValidationErrorKind::AnyOf { context }
+            | ValidationErrorKind::OneOfNotValid { context }
+                if !context.is_empty() =>
+            {
+                mem::take(context)
+            }
+            _ => return Some(best),
+        };
+        let branch = select_branch(&best, context);
+        best = select_error(branch).expect("Failed branches always contain errors");
+    }
+}
+
+/// The first error among the deepest ones.
+fn select_error<'a, I>(errors: I) -> Option<ValidationError<'a>>
+where
+    I: IntoIterator<Item = ValidationError<'a>>,
+{
+    let mut best: Option<ValidationError<'a>> = None;
+    for error in errors {
+        match &best {
+            Some(current) if current.instance_path.len() >= error.instance_path.len() => {}
+            _ => best = Some(error),
+        }
+    }
+    best
+}
+
+fn select_branch(
+    parent: &ValidationError<'_>,
+    context: Vec<Vec<ValidationError<'static>>>,
+) -> Vec<ValidationError<'static>> {
+    let mut best: Option<((bool, usize, usize), Vec<ValidationError<'static>>)> = None;
+    for branch in context {
+        let relevance = branch_relevance(parent, &branch);
+        match &best {
+            Some((current, _)) if *current >= relevance => {}
+            _ => best = Some((relevance, branch)),
+        }
+    }
+    best.map(|(_, branch)| branch).unwrap_or_default()
+}
+
+/// Branch relevance. Bigger is better.
+fn branch_relevance(
+    parent: &ValidationError<'_>,
+    branch: &[ValidationError<'_>],
+) -> (bool, usize, usize) {
+    let discriminator_matched = parent.instance.is_object()
+        && !branch.iter().any(|error| {
+            error.instance_path.len() == parent.instance_path.len() + 1
+                && matches!(
+                    *error.kind,
+                    ValidationErrorKind::Constant { .. } | ValidationErrorKind::Enum { .. }
+                )
+        });
+    let depth = branch
+        .iter()
+        .map(|error| error.instance_path.len())
+        .max()
+        .unwrap_or(0);
+    (discriminator_matched, usize::MAX - branch.len(), depth)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::best_match;
+    use crate::{compilation::JSONSchema, error::ValidationErrorKind};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn best_match_path(schema: &Value, instance: &Value) -> (String, String) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let errors = compiled.validate(instance).expect_err("Should be invalid");
+        let error = best_match(errors).expect("Should have errors");
+        (error.instance_path.to_string(), error.to_string())
+    }
+
+    #[test_case(
+        json!({"properties": {"a": {"type": "string"}}, "minProperties": 3}),
+        json!({"a": 1}),
+        "/a";
+        "deepest error"
+    )]
+    #[test_case(
+        json!({"anyOf": [{"type": "string"}, {"properties": {"a": {"type": "string"}}}]}),
+        json!({"a": 1}),
+        "/a";
+        "deepest branch error"
+    )]
+    #[test_case(
+        json!({"oneOf": [
+            {"properties": {"kind": {"const": "a"}, "value": {"type": "string"}}, "required": ["x"]},
+            {"properties": {"kind": {"const": "b"}, "value": {"type": "number"}}}
+        ]}),
+        json!({"kind": "a", "value": 1}),
+        "/value";
+        "discriminator"
+    )]
+    #[test_case(
+        json!({"anyOf": [{"minimum": 5, "multipleOf": 2}, {"minimum": 3}]}),
+        json!(1),
+        "";
+        "fewest errors"
+    )]
+    fn selection(schema: Value, instance: Value, expected: &str) {
+        let (path, _) = best_match_path(&schema, &instance);
+        assert_eq!(path, expected)
+    }
+
+    #[test]
+    fn fewest_errors_message() {
+        let schema = json!({"anyOf": [{"minimum": 5, "multipleOf": 2}, {"minimum": 3}]});
+        let (_, message) = best_match_path(&schema, &json!(1));
+        assert_eq!(message, "1 is less than the minimum of 3")
+    }
+
+    #[test]
+    fn no_errors() {
+        assert!(best_match(Vec::new()).is_none())
+    }
+
+    #[test]
+    fn one_of_context() {
+        let schema = json!({"oneOf": [{"type": "string"}, {"minimum": 5}]});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let instance = json!(1);
+        let error = compiled.validate(&instance).unwrap_err().next().unwrap();
+        if let ValidationErrorKind::OneOfNotValid { context } = *error.kind {
+            assert_eq!(context.len(), 2);
+            assert_eq!(context[0].len(), 1);
+            assert_eq!(context[1].len(), 1);
+        } else {
+            panic!("Unexpected error kind: {:?}", error.kind)
+        }
+    }
+
+    #[test]
+    fn one_of_matched() {
+        let schema = json!({"oneOf": [{"type": "integer"}, {"type": "string"}, {"minimum": 0}]});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let instance = json!(1);
+        let error = compiled.validate(&instance).unwrap_err().next().unwrap();
+        if let ValidationErrorKind::OneOfMultipleValid { matched } = *error.kind {
+            assert_eq!(matched, vec![0, 2]);
+        } else {
+            panic!("Unexpected error kind: {:?}", error.kind)
+        }
+    }
+}
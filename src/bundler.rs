@@ -0,0 +1,480 @@
+//! Bundling of schemas into self-contained documents.
+//!
+//! Every external document that is referenced via `$ref` is embedded under `definitions` of the root schema
+//! and all references are rewritten into JSON pointers within the bundle. Since pointers are resolved against the
+//! closest `$id`, all identifiers except the one in the root schema are removed. The root identifier is resolved
+//! into an absolute URI, so the bundle doesn't depend on the location it was built from.
+use crate::{
+    compilation::{options::CompilationOptions, DEFAULT_SCOPE},
+    error::ValidationError,
+    resolver::Resolver,
+    schemas::{id_of, Draft},
+};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use serde_json::{Map, Value};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
+use url::Url;
+
+/// Characters that should be encoded in a URI fragment.
+const FRAGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'`');
+
+/// Keywords that contain instances rather than subschemas.
+pub(crate) const DATA_KEYWORDS: &[&str] = &["const", "default", "enum", "examples"];
+
+struct Bundler<'a> {
+    draft: Draft,
+    root: &'a Value,
+    resolver: Resolver,
+    // Canonical URLs of all known (sub)schemas and their locations within the bundle
+    locations: HashMap<String, String>,
+    // Embedded documents under their names in the container
+    documents: Vec<(String, Value)>,
+    // Documents that are embedded, but their references are not rewritten yet
+    pending: Vec<(usize, Url)>,
+    // Names that are already taken in the container
+    names: HashSet<String>,
+}
+
+impl<'a> Bundler<'a> {
+    fn new(
+        draft: Draft,
+        root: &'a Value,
+        location: &Url,
+        scope: &Url,
+    ) -> Result<Self, ValidationError<'static>> {
+        let resolver = Resolver::new(draft, scope, root)?;
+        let mut locations: HashMap<_, _> = resolver
+            .pointers()
+            .iter()
+            .map(|(id, pointer)| (id.clone(), pointer.clone()))
+            .collect();
+        // References to the root document by its location are local as well
+        locations.insert(without_fragment(location).to_string(), String::new());
+        locations.insert(without_fragment(scope).to_string(), String::new());
+        let names = match root.get(definitions_keyword(draft)) {
+            Some(Value::Object(definitions)) => definitions.keys().cloned().collect(),
+            _ => HashSet::new(),
+        };
+        Ok(Bundler {
+            draft,
+            root,
+            resolver,
+            locations,
+            documents: Vec::new(),
+            pending: Vec::new(),
+            names,
+        })
+    }
+
+    /// Location of the schema at the given URL within the bundle. Unknown documents are fetched and embedded.
+    fn locate(&mut self, url: &Url) -> Result<String, ValidationError<'static>> {
+        if let Some(pointer) = self.locations.get(url.as_str()) {
+            return Ok(pointer.clone());
+        }
+        let fragment = percent_decode_str(url.fragment().unwrap_or("")).decode_utf8()?;
+        if !fragment.is_empty() && !fragment.starts_with('/') {
+            // Plain-name fragments are identifiers and all of them are already known
+            return Err(ValidationError::invalid_reference(url.to_string()));
+        }
+        let document = without_fragment(url);
+        let prefix = match self.locations.get(document.as_str()) {
+            Some(prefix) => prefix.clone(),
+            None => self.embed(document)?,
+        };
+        Ok(format!("{}{}", prefix, fragment))
+    }
+
+    fn embed(&mut self, url: Url) -> Result<String, ValidationError<'static>> {
+        let document = self.fetch(&url)?;
+        let name = self.unique_name(&url);
+        let prefix = format!(
+            "/{}/{}",
+            definitions_keyword(self.draft),
+            name.replace('~', "~0").replace('/', "~1")
+        );
+        for (id, pointer) in Resolver::new(self.draft, &url, &document)?.pointers() {
+            self.locations
+                .entry(id.clone())
+                .or_insert_with(|| format!("{}{}", prefix, pointer));
+        }
+        self.locations.insert(url.to_string(), prefix.clone());
+        self.names.insert(name.clone());
+        self.pending.push((self.documents.len(), url));
+        self.documents.push((name, document));
+        Ok(prefix)
+    }
+
+    fn fetch(&self, url: &Url) -> Result<Value, ValidationError<'static>> {
+        if url.scheme() == "file" {
+            let path = url
+                .to_file_path()
+                .map_err(|_| ValidationError::invalid_reference(url.to_string()))?;
+            Ok(serde_json::from_slice(&fs::read(path)?)?)
+        } else {
+            self.resolver
+                .resolve_fragment(self.draft, url, self.root)
+                .map(|(_, document)| document.into_owned())
+                .map_err(ValidationError::into_owned)
+        }
+    }
+
+    /// A name for the document in the container, derived from the last segment of its URL.
+    fn unique_name(&self, url: &Url) -> String {
+        let stem = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .map(|segment| segment.split('.').next().unwrap_or(segment))
+            .filter(|stem| !stem.is_empty())
+            .unwrap_or("schema");
+        let stem = percent_decode_str(stem).decode_utf8_lossy().into_owned();
+        let mut name = stem.clone();
+        let mut suffix = 1;
+        while self.names.contains(&name) {
+            suffix += 1;
+            name = format!("{}_{}", stem, suffix);
+        }
+        name
+    }
+
+    /// Rewrite references in `schema` and remove identifiers from all subschemas except the root one.
+    fn rewrite(
+        &mut self,
+        schema: &mut Value,
+        base: &Url,
+        is_root: bool,
+    ) -> Result<(), ValidationError<'static>> {
+        let scope = match id_of(self.draft, schema) {
+            Some(id) => Some(base.join(id)?),
+            None => None,
+        };
+        match schema {
+            Value::Object(object) => {
+                match &scope {
+                    Some(scope) if is_root => {
+                        object.insert(
+                            id_keyword(self.draft).to_string(),
+                            Value::String(scope.to_string()),
+                        );
+                    }
+                    Some(_) => {
+                        object.remove(id_keyword(self.draft));
+                    }
+                    None => {}
+                }
+                let base = scope.as_ref().unwrap_or(base);
+                if let Some(Value::String(reference)) = object.get("$ref") {
+                    let pointer = self.locate(&base.join(reference)?)?;
+                    object.insert(
+                        "$ref".to_string(),
+                        Value::String(format!("#{}", utf8_percent_encode(&pointer, FRAGMENT))),
+                    );
+                }
+                for (keyword, value) in object.iter_mut() {
+                    if !DATA_KEYWORDS.contains(&keyword.as_str()) {
+                        self.rewrite(value, base, false)?;
+                    }
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.rewrite(item, base, false)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn bundle(mut self, base: &Url) -> Result<Value, ValidationError<'static>> {
+        let mut root = self.root.clone();
+        self.rewrite(&mut root, base, true)?;
+        // Embedded documents may reference other documents, that are embedded in turn
+        while let Some((idx, url)) = self.pending.pop() {
+            let mut document = std::mem::take(&mut self.documents[idx].1);
+            self.rewrite(&mut document, &url, false)?;
+            self.documents[idx].1 = document;
+        }
+        if self.documents.is_empty() {
+            return Ok(root);
+        }
+        // Only object schemas could have references, but the root might be not a valid schema
+        let object = root.as_object_mut().ok_or_else(ValidationError::schema)?;
+        let definitions = object
+            .entry(definitions_keyword(self.draft))
+            .or_insert_with(|| Value::Object(Map::new()));
+        if !definitions.is_object() {
+            *definitions = Value::Object(Map::new());
+        }
+        if let Value::Object(definitions) = definitions {
+            definitions.extend(self.documents);
+        }
+        Ok(root)
+    }
+}
+
+fn without_fragment(url: &Url) -> Url {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url
+}
+
+const fn id_keyword(draft: Draft) -> &'static str {
+    match draft {
+        Draft::Draft4 => "id",
+        Draft::Draft6 | Draft::Draft7 | Draft::OpenAPI30 => "$id",
+    }
+}
+
+/// `$defs` appeared only in Draft 2019-09, earlier drafts use `definitions`.
+const fn definitions_keyword(draft: Draft) -> &'static str {
+    match draft {
+        Draft::Draft4 | Draft::Draft6 | Draft::Draft7 | Draft::OpenAPI30 => "definitions",
+    }
+}
+
+impl CompilationOptions {
+    /// Inline all external documents referenced via `$ref` into `schema`, so it can be distributed
+    /// as a single self-contained document.
+    ///
+    /// External documents are embedded under `definitions` and all references are rewritten to point there.
+    /// Relative references are resolved against the root `$id`, see `CompilationOptions::bundle_with_base`
+    /// to bundle schemas that reference files next to them. A relative root `$id` is resolved into an absolute URI.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({"properties": {"id": {"$ref": "#/definitions/id"}}, "definitions": {"id": {"type": "integer"}}});
+    /// // References within the document are kept as is
+    /// let bundled = JSONSchema::options().bundle(&schema).expect("References are valid");
+    /// assert_eq!(bundled, schema);
+    /// ```
+    pub fn bundle(&self, schema: &Value) -> Result<Value, ValidationError<'static>> {
+        self.bundle_with_base(schema, &DEFAULT_SCOPE)
+    }
+
+    /// Same as `CompilationOptions::bundle`, but relative references and `$id` in the root schema are resolved
+    /// against `base`, e.g. a `file://` URL of the schema document.
+    /// Documents with the `file` scheme are read from the local file system.
+    pub fn bundle_with_base(
+        &self,
+        schema: &Value,
+        base: &Url,
+    ) -> Result<Value, ValidationError<'static>> {
+        let draft = self.detect_draft(schema);
+        let scope = match id_of(draft, schema) {
+            Some(id) => base.join(id)?,
+            None => base.clone(),
+        };
+        Bundler::new(draft, schema, base, &scope)?.bundle(&scope)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compilation::JSONSchema;
+    use serde_json::{json, Value};
+    use std::{
+        fs,
+        ops::Deref,
+        path::{Path, PathBuf},
+        process,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+    use test_case::test_case;
+    use url::Url;
+
+    static DIRECTORIES: AtomicUsize = AtomicUsize::new(0);
+
+    /// A unique temporary directory that is removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl Deref for TempDir {
+        type Target = Path;
+
+        fn deref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn setup(files: &[(&str, Value)]) -> TempDir {
+        let directory = TempDir(std::env::temp_dir().join(format!(
+            "jsonschema-bundler-{}-{}",
+            process::id(),
+            DIRECTORIES.fetch_add(1, Ordering::SeqCst)
+        )));
+        fs::create_dir_all(&*directory).unwrap();
+        for (path, document) in files {
+            let path = directory.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, document.to_string()).unwrap();
+        }
+        directory
+    }
+
+    fn bundle(directory: &Path, schema: &Value) -> Value {
+        let base = Url::from_file_path(directory.join("root.json")).unwrap();
+        JSONSchema::options()
+            .bundle_with_base(schema, &base)
+            .unwrap()
+    }
+
+    #[test]
+    fn external_references() {
+        let directory = setup(&[
+            (
+                "person.json",
+                json!({"properties": {"name": {"$ref": "#/definitions/name"}}, "definitions": {"name": {"type": "string"}}}),
+            ),
+            ("address.json", json!({"required": ["city"]})),
+        ]);
+        let schema = json!({
+            "properties": {
+                "owner": {"$ref": "person.json"},
+                "name": {"$ref": "person.json#/definitions/name"},
+                "address": {"$ref": "address.json"}
+            }
+        });
+        let bundled = bundle(&directory, &schema);
+        assert_eq!(
+            bundled,
+            json!({
+                "properties": {
+                    "owner": {"$ref": "#/definitions/person"},
+                    "name": {"$ref": "#/definitions/person/definitions/name"},
+                    "address": {"$ref": "#/definitions/address"}
+                },
+                "definitions": {
+                    "person": {
+                        "properties": {"name": {"$ref": "#/definitions/person/definitions/name"}},
+                        "definitions": {"name": {"type": "string"}}
+                    },
+                    "address": {"required": ["city"]}
+                }
+            })
+        );
+        let compiled = JSONSchema::compile(&bundled).unwrap();
+        assert!(compiled.is_valid(&json!({"owner": {"name": "a"}, "address": {"city": "b"}})));
+        assert!(!compiled.is_valid(&json!({"owner": {"name": 1}})));
+        assert!(!compiled.is_valid(&json!({"address": {}})));
+    }
+
+    #[test]
+    fn cycles() {
+        let directory = setup(&[(
+            "node.json",
+            json!({"properties": {"next": {"$ref": "node.json"}, "tree": {"$ref": "root.json"}}}),
+        )]);
+        let schema =
+            json!({"properties": {"node": {"$ref": "node.json"}}, "additionalProperties": false});
+        let bundled = bundle(&directory, &schema);
+        assert_eq!(
+            bundled["definitions"]["node"],
+            json!({"properties": {"next": {"$ref": "#/definitions/node"}, "tree": {"$ref": "#"}}})
+        );
+        let compiled = JSONSchema::compile(&bundled).unwrap();
+        assert!(compiled.is_valid(&json!({"node": {"next": {"tree": {"node": {}}}}})));
+        assert!(!compiled.is_valid(&json!({"node": {"next": {"tree": {"other": 1}}}})));
+    }
+
+    #[test]
+    fn scope_changes() {
+        let directory = setup(&[
+            (
+                "nested/item.json",
+                json!({"$id": "item.json", "items": {"$ref": "#/definitions/a"}, "definitions": {"a": {"$ref": "../leaf.json"}}}),
+            ),
+            ("leaf.json", json!({"type": "integer"})),
+        ]);
+        let schema = json!({
+            "$id": "root.json",
+            "definitions": {"item": {"type": "string"}},
+            "properties": {
+                "a": {"$id": "nested/", "properties": {"b": {"$ref": "item.json"}}},
+                "c": {"$ref": "#/definitions/item"}
+            }
+        });
+        let bundled = bundle(&directory, &schema);
+        let id = Url::from_file_path(directory.join("root.json")).unwrap();
+        assert_eq!(
+            bundled,
+            json!({
+                "$id": id.as_str(),
+                "definitions": {
+                    "item": {"type": "string"},
+                    "item_2": {
+                        "items": {"$ref": "#/definitions/item_2/definitions/a"},
+                        "definitions": {"a": {"$ref": "#/definitions/leaf"}}
+                    },
+                    "leaf": {"type": "integer"}
+                },
+                "properties": {
+                    "a": {"properties": {"b": {"$ref": "#/definitions/item_2"}}},
+                    "c": {"$ref": "#/definitions/item"}
+                }
+            })
+        );
+        let compiled = JSONSchema::options()
+            .with_strict_mode()
+            .compile(&bundled)
+            .unwrap();
+        assert!(compiled.is_valid(&json!({"a": {"b": [1]}, "c": "d"})));
+        assert!(!compiled.is_valid(&json!({"a": {"b": ["1"]}})));
+    }
+
+    #[test]
+    fn defs() {
+        // `$defs` is not known before Draft 2019-09, documents are embedded under `definitions`
+        let directory = setup(&[("item.json", json!({"type": "string"}))]);
+        let schema = json!({
+            "$defs": {"item": {"type": "integer"}},
+            "items": [{"$ref": "#/$defs/item"}, {"$ref": "item.json"}]
+        });
+        let bundled = bundle(&directory, &schema);
+        assert_eq!(
+            bundled,
+            json!({
+                "$defs": {"item": {"type": "integer"}},
+                "definitions": {"item": {"type": "string"}},
+                "items": [{"$ref": "#/$defs/item"}, {"$ref": "#/definitions/item"}]
+            })
+        );
+        let compiled = JSONSchema::compile(&bundled).unwrap();
+        assert!(compiled.is_valid(&json!([1, "a"])));
+        assert!(!compiled.is_valid(&json!([1, 2])));
+    }
+
+    #[test_case(json!(true), Some(json!(true)); "boolean")]
+    #[test_case(json!([{"$ref": "item.json"}]), None; "array")]
+    fn not_object(schema: Value, expected: Option<Value>) {
+        let directory = setup(&[("item.json", json!({"type": "string"}))]);
+        let base = Url::from_file_path(directory.join("root.json")).unwrap();
+        let bundled = JSONSchema::options().bundle_with_base(&schema, &base);
+        assert_eq!(bundled.ok(), expected);
+    }
+
+    #[test]
+    fn missing_document() {
+        let directory = setup(&[]);
+        let base = Url::from_file_path(directory.join("root.json")).unwrap();
+        let schema = json!({"$ref": "unknown.json"});
+        assert!(JSONSchema::options()
+            .bundle_with_base(&schema, &base)
+            .is_err());
+    }
+}
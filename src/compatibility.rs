@@ -0,0 +1,1117 @@
+//! Compatibility analysis between two versions of a schema.
+//!
+//! Both schemas are walked side by side, references are resolved on the fly. Every keyword that differs is
+//! classified by the compatibility it preserves:
+//!   - Widening changes (e.g. a raised `maximum`) accept more instances - old instances are still valid;
+//!   - Narrowing changes (e.g. a new required property) accept fewer instances - new instances are valid under
+//!     the old schema;
+//!   - Other changes (e.g. a changed `pattern`) are conservatively treated as incompatible in both directions.
+use crate::{
+    compilation::{options::CompilationOptions, DEFAULT_SCOPE},
+    error::ValidationError,
+    paths::JSONPointer,
+    resolver::Resolver,
+    schemas::{id_of, Draft},
+};
+use serde_json::Value;
+use std::{borrow::Cow, collections::HashSet, fmt};
+use url::Url;
+
+/// How many references could be resolved in a row before giving up.
+const MAX_REFERENCES: usize = 64;
+
+const LOWER_LIMITS: &[&str] = &["minimum", "minLength", "minItems", "minProperties"];
+const UPPER_LIMITS: &[&str] = &["maximum", "maxLength", "maxItems", "maxProperties"];
+/// Keywords that restrict instances, their changes are narrowing if added and widening if removed.
+const RESTRICTIONS: &[&str] = &["pattern", "format", "contentMediaType", "contentEncoding"];
+
+/// Which kind of compatibility a change preserves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compatibility {
+    /// Every instance valid under the old schema is valid under the new one.
+    Backward,
+    /// Every instance valid under the new schema is valid under the old one.
+    Forward,
+    /// Neither of the above is guaranteed.
+    Incompatible,
+}
+
+impl Compatibility {
+    const fn flip(self) -> Self {
+        match self {
+            Compatibility::Backward => Compatibility::Forward,
+            Compatibility::Forward => Compatibility::Backward,
+            Compatibility::Incompatible => Compatibility::Incompatible,
+        }
+    }
+}
+
+/// What happened to a keyword.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(missing_docs)]
+pub enum ChangeKind {
+    /// The keyword is added.
+    Added { value: Value },
+    /// The keyword is removed.
+    Removed { value: Value },
+    /// The keyword accepts fewer instances.
+    Tightened { old: Value, new: Value },
+    /// The keyword accepts more instances.
+    Loosened { old: Value, new: Value },
+    /// The keyword value is changed in another way.
+    Changed { old: Value, new: Value },
+    /// A value is added to a list, e.g. to `enum` or `required`.
+    ValueAdded { value: Value },
+    /// A value is removed from a list, e.g. from `enum` or `required`.
+    ValueRemoved { value: Value },
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::Added { value } => write!(f, "Added {}", value),
+            ChangeKind::Removed { value } => write!(f, "Removed {}", value),
+            ChangeKind::Tightened { old, new } => write!(f, "Tightened from {} to {}", old, new),
+            ChangeKind::Loosened { old, new } => write!(f, "Loosened from {} to {}", old, new),
+            ChangeKind::Changed { old, new } => write!(f, "Changed from {} to {}", old, new),
+            ChangeKind::ValueAdded { value } => write!(f, "Added value {}", value),
+            ChangeKind::ValueRemoved { value } => write!(f, "Removed value {}", value),
+        }
+    }
+}
+
+/// A single difference between two schemas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaChange {
+    /// Location of the changed keyword. References are followed, so it is the same as the schema path
+    /// reported in validation errors.
+    pub schema_path: JSONPointer,
+    /// What happened to the keyword.
+    pub kind: ChangeKind,
+    /// Which compatibility is preserved by this change.
+    pub compatibility: Compatibility,
+}
+
+impl SchemaChange {
+    /// Whether some instances valid under the old schema are invalid under the new one.
+    #[must_use]
+    pub fn breaks_backward(&self) -> bool {
+        self.compatibility != Compatibility::Backward
+    }
+
+    /// Whether some instances valid under the new schema are invalid under the old one.
+    #[must_use]
+    pub fn breaks_forward(&self) -> bool {
+        self.compatibility != Compatibility::Forward
+    }
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at '{}'", self.kind, self.schema_path)
+    }
+}
+
+/// All differences between two schemas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompatibilityReport {
+    /// Changes in the order of their appearance in the schemas.
+    pub changes: Vec<SchemaChange>,
+}
+
+impl CompatibilityReport {
+    /// Whether every instance valid under the old schema is valid under the new one.
+    #[must_use]
+    pub fn is_backward_compatible(&self) -> bool {
+        !self.changes.iter().any(SchemaChange::breaks_backward)
+    }
+
+    /// Whether every instance valid under the new schema is valid under the old one.
+    #[must_use]
+    pub fn is_forward_compatible(&self) -> bool {
+        !self.changes.iter().any(SchemaChange::breaks_forward)
+    }
+
+    /// Changes that break backward compatibility.
+    pub fn backward_breaking(&self) -> impl Iterator<Item = &SchemaChange> {
+        self.changes
+            .iter()
+            .filter(|change| change.breaks_backward())
+    }
+
+    /// Changes that break forward compatibility.
+    pub fn forward_breaking(&self) -> impl Iterator<Item = &SchemaChange> {
+        self.changes.iter().filter(|change| change.breaks_forward())
+    }
+}
+
+impl fmt::Display for CompatibilityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, change) in self.changes.iter().enumerate() {
+            if idx > 0 {
+                f.write_str("\n")?;
+            }
+            change.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// A schema document together with its resolver.
+pub(crate) struct Document<'a> {
+    pub(crate) draft: Draft,
+    pub(crate) root: &'a Value,
+    pub(crate) resolver: Resolver,
+}
+
+impl<'a> Document<'a> {
+    pub(crate) fn new(
+        options: &CompilationOptions,
+        root: &'a Value,
+    ) -> Result<Self, ValidationError<'static>> {
+        let draft = options.detect_draft(root);
+        let scope = scope_of(draft, root)?;
+        Ok(Document {
+            draft,
+            root,
+            resolver: Resolver::new(draft, &scope, root)?,
+        })
+    }
+
+    /// The root schema of the document.
+    pub(crate) fn root_node(&self) -> Result<Node<'a>, ValidationError<'static>> {
+        Ok(Node {
+            schema: Cow::Borrowed(self.root),
+            scope: scope_of(self.draft, self.root)?,
+            location: "#".to_string(),
+        })
+    }
+
+    /// Follow `$id` and `$ref` until a subschema without a reference.
+    pub(crate) fn resolve(&self, mut node: Node<'a>) -> Result<Node<'a>, ValidationError<'static>> {
+        for _ in 0..MAX_REFERENCES {
+            if let Some(id) = id_of(self.draft, &node.schema) {
+                node.scope = node.scope.join(id)?;
+            }
+            let url = match node.schema.get("$ref") {
+                Some(Value::String(reference)) => node.scope.join(reference)?,
+                _ => return Ok(node),
+            };
+            let (scope, schema) = self
+                .resolver
+                .resolve_fragment(self.draft, &url, self.root)
+                .map_err(ValidationError::into_owned)?;
+            node = Node {
+                schema,
+                scope,
+                location: url.to_string(),
+            };
+        }
+        Ok(node)
+    }
+}
+
+/// A subschema within one of the documents.
+#[derive(Clone)]
+pub(crate) struct Node<'a> {
+    pub(crate) schema: Cow<'a, Value>,
+    pub(crate) scope: Url,
+    /// Canonical location of the subschema, used to detect recursion.
+    pub(crate) location: String,
+}
+
+impl<'a> Node<'a> {
+    pub(crate) fn child(&self, schema: Cow<'a, Value>, segment: &str) -> Node<'a> {
+        Node {
+            schema,
+            scope: self.scope.clone(),
+            location: format!("{}/{}", self.location, segment),
+        }
+    }
+
+    /// A subschema under `keyword`, `true` if it is absent.
+    pub(crate) fn keyword(&self, keyword: &str) -> Node<'a> {
+        let schema = match &self.schema {
+            Cow::Borrowed(schema) => schema.get(keyword).map(Cow::Borrowed),
+            Cow::Owned(schema) => schema.get(keyword).cloned().map(Cow::Owned),
+        };
+        match schema {
+            Some(schema) => self.child(schema, keyword),
+            None => self.absent(),
+        }
+    }
+
+    /// A subschema under `keyword` and `name`, `default` if it is absent.
+    pub(crate) fn entry(&self, keyword: &str, name: &str, default: &Node<'a>) -> Node<'a> {
+        let schema = match &self.schema {
+            Cow::Borrowed(schema) => schema
+                .get(keyword)
+                .and_then(|value| value.get(name))
+                .map(Cow::Borrowed),
+            Cow::Owned(schema) => schema
+                .get(keyword)
+                .and_then(|value| value.get(name))
+                .cloned()
+                .map(Cow::Owned),
+        };
+        match schema {
+            Some(schema) => self.child(schema, &format!("{}/{}", keyword, name)),
+            None => default.clone(),
+        }
+    }
+
+    /// A subschema under `keyword` and `idx`, `true` if it is absent.
+    pub(crate) fn element(&self, keyword: &str, idx: usize) -> Node<'a> {
+        let schema = match &self.schema {
+            Cow::Borrowed(schema) => schema
+                .get(keyword)
+                .and_then(|value| value.get(idx))
+                .map(Cow::Borrowed),
+            Cow::Owned(schema) => schema
+                .get(keyword)
+                .and_then(|value| value.get(idx))
+                .cloned()
+                .map(Cow::Owned),
+        };
+        match schema {
+            Some(schema) => self.child(schema, &format!("{}/{}", keyword, idx)),
+            None => self.absent(),
+        }
+    }
+
+    /// An absent subschema. All of them share the same location, otherwise comparing a recursive schema
+    /// against an absent one would never revisit a pair of locations.
+    fn absent(&self) -> Node<'a> {
+        Node {
+            schema: Cow::Owned(Value::Bool(true)),
+            scope: self.scope.clone(),
+            location: "true".to_string(),
+        }
+    }
+
+    /// A positional subschema under `items`, `additionalItems` if there is no subschema at `idx`.
+    pub(crate) fn item(&self, idx: usize) -> Node<'a> {
+        match self.schema.get("items") {
+            Some(Value::Array(items)) if idx < items.len() => self.element("items", idx),
+            _ => self.keyword("additionalItems"),
+        }
+    }
+}
+
+struct Comparer<'a> {
+    old: Document<'a>,
+    new: Document<'a>,
+    // Pairs of compared locations, a pair is compared only once, which stops recursion
+    visited: HashSet<(String, String)>,
+    changes: Vec<SchemaChange>,
+}
+
+impl<'a> Comparer<'a> {
+    fn report(&mut self, path: &JSONPointer, kind: ChangeKind, compatibility: Compatibility) {
+        self.changes.push(SchemaChange {
+            schema_path: path.clone(),
+            kind,
+            compatibility,
+        })
+    }
+
+    fn compare(
+        &mut self,
+        old: Node<'a>,
+        new: Node<'a>,
+        path: &JSONPointer,
+        flipped: bool,
+    ) -> Result<(), ValidationError<'static>> {
+        let old = self.old.resolve(old)?;
+        let new = self.new.resolve(new)?;
+        if old.schema == new.schema
+            || !self
+                .visited
+                .insert((old.location.clone(), new.location.clone()))
+        {
+            return Ok(());
+        }
+        let direction = |compatibility: Compatibility| {
+            if flipped {
+                compatibility.flip()
+            } else {
+                compatibility
+            }
+        };
+        match (old.schema.as_ref(), new.schema.as_ref()) {
+            (Value::Bool(false), _) => {
+                let kind = ChangeKind::Loosened {
+                    old: Value::Bool(false),
+                    new: new.schema.into_owned(),
+                };
+                self.report(path, kind, direction(Compatibility::Backward));
+                return Ok(());
+            }
+            (_, Value::Bool(false)) => {
+                let kind = ChangeKind::Tightened {
+                    old: old.schema.into_owned(),
+                    new: Value::Bool(false),
+                };
+                self.report(path, kind, direction(Compatibility::Forward));
+                return Ok(());
+            }
+            _ => {}
+        }
+        let empty = serde_json::Map::new();
+        let old_keywords = old.schema.as_object().unwrap_or(&empty);
+        let new_keywords = new.schema.as_object().unwrap_or(&empty);
+        let mut keywords: Vec<&String> = old_keywords.keys().collect();
+        keywords.extend(
+            new_keywords
+                .keys()
+                .filter(|keyword| !old_keywords.contains_key(*keyword)),
+        );
+        for keyword in keywords {
+            let (old_value, new_value) = (old_keywords.get(keyword), new_keywords.get(keyword));
+            if old_value == new_value {
+                continue;
+            }
+            let keyword_path = path.join(keyword.as_str());
+            let keyword = keyword.as_str();
+            match keyword {
+                "type" => {
+                    let compatibility = compare_types(old_value, new_value);
+                    self.report(
+                        &keyword_path,
+                        change(old_value, new_value),
+                        direction(compatibility),
+                    );
+                }
+                "enum" | "required" => {
+                    let (old_values, new_values) = match (old_value, new_value) {
+                        (Some(Value::Array(old_values)), Some(Value::Array(new_values))) => {
+                            (old_values, new_values)
+                        }
+                        // A new `enum` / `required` restricts instances, a removed one relaxes them
+                        _ => {
+                            let compatibility = if old_value.is_none() {
+                                Compatibility::Forward
+                            } else {
+                                Compatibility::Backward
+                            };
+                            self.report(
+                                &keyword_path,
+                                change(old_value, new_value),
+                                direction(compatibility),
+                            );
+                            continue;
+                        }
+                    };
+                    // More values in `enum` accept more instances, in `required` - fewer
+                    let (added, removed) = if keyword == "enum" {
+                        (Compatibility::Backward, Compatibility::Forward)
+                    } else {
+                        (Compatibility::Forward, Compatibility::Backward)
+                    };
+                    for value in old_values {
+                        if !new_values.contains(value) {
+                            let kind = ChangeKind::ValueRemoved {
+                                value: value.clone(),
+                            };
+                            self.report(&keyword_path, kind, direction(removed));
+                        }
+                    }
+                    for value in new_values {
+                        if !old_values.contains(value) {
+                            let kind = ChangeKind::ValueAdded {
+                                value: value.clone(),
+                            };
+                            self.report(&keyword_path, kind, direction(added));
+                        }
+                    }
+                }
+                "const" => {
+                    let compatibility = match (old_value, new_value) {
+                        (None, _) => Compatibility::Forward,
+                        (_, None) => Compatibility::Backward,
+                        _ => Compatibility::Incompatible,
+                    };
+                    self.report(
+                        &keyword_path,
+                        change(old_value, new_value),
+                        direction(compatibility),
+                    );
+                }
+                "exclusiveMinimum" | "exclusiveMaximum"
+                    if is_legacy(self.old.draft) && is_legacy(self.new.draft) =>
+                {
+                    self.compare_flags(&keyword_path, old_value, new_value, flipped)
+                }
+                "uniqueItems" => self.compare_flags(&keyword_path, old_value, new_value, flipped),
+                "nullable" => {
+                    // Unlike other flags, `nullable` accepts more instances
+                    self.compare_flags(&keyword_path, old_value, new_value, !flipped)
+                }
+                "exclusiveMinimum" | "exclusiveMaximum" => {
+                    // Each side is interpreted with its own draft
+                    let lower = keyword == "exclusiveMinimum";
+                    let old_limit = exclusive_limit(self.old.draft, old_keywords, keyword);
+                    let new_limit = exclusive_limit(self.new.draft, new_keywords, keyword);
+                    self.compare_limits(
+                        &keyword_path,
+                        old_limit.as_ref(),
+                        new_limit.as_ref(),
+                        lower,
+                        flipped,
+                    )
+                }
+                _ if LOWER_LIMITS.contains(&keyword) || UPPER_LIMITS.contains(&keyword) => {
+                    let lower = LOWER_LIMITS.contains(&keyword);
+                    self.compare_limits(&keyword_path, old_value, new_value, lower, flipped)
+                }
+                "multipleOf" => {
+                    let compatibility = match (
+                        old_value.and_then(Value::as_f64),
+                        new_value.and_then(Value::as_f64),
+                    ) {
+                        (None, _) => Compatibility::Forward,
+                        (_, None) => Compatibility::Backward,
+                        (Some(old), Some(new)) if new % old == 0.0 => Compatibility::Forward,
+                        (Some(old), Some(new)) if old % new == 0.0 => Compatibility::Backward,
+                        _ => Compatibility::Incompatible,
+                    };
+                    let kind = match (compatibility, old_value, new_value) {
+                        (Compatibility::Forward, Some(old), Some(new)) => ChangeKind::Tightened {
+                            old: old.clone(),
+                            new: new.clone(),
+                        },
+                        (Compatibility::Backward, Some(old), Some(new)) => ChangeKind::Loosened {
+                            old: old.clone(),
+                            new: new.clone(),
+                        },
+                        _ => change(old_value, new_value),
+                    };
+                    self.report(&keyword_path, kind, direction(compatibility));
+                }
+                _ if RESTRICTIONS.contains(&keyword) => {
+                    let compatibility = match (old_value, new_value) {
+                        (None, _) => Compatibility::Forward,
+                        (_, None) => Compatibility::Backward,
+                        _ => Compatibility::Incompatible,
+                    };
+                    self.report(
+                        &keyword_path,
+                        change(old_value, new_value),
+                        direction(compatibility),
+                    );
+                }
+                "properties" => {
+                    let old_default = old.keyword("additionalProperties");
+                    let new_default = new.keyword("additionalProperties");
+                    for name in names(old_value, new_value) {
+                        self.compare(
+                            old.entry("properties", &name, &old_default),
+                            new.entry("properties", &name, &new_default),
+                            &keyword_path.join(name.as_str()),
+                            flipped,
+                        )?;
+                    }
+                }
+                "patternProperties" => {
+                    let default = old.child(Cow::Owned(Value::Bool(true)), "");
+                    for pattern in names(old_value, new_value) {
+                        self.compare(
+                            old.entry(keyword, &pattern, &default),
+                            new.entry(keyword, &pattern, &default),
+                            &keyword_path.join(pattern.as_str()),
+                            flipped,
+                        )?;
+                    }
+                }
+                "dependencies" => {
+                    for name in names(old_value, new_value) {
+                        let dependency_path = keyword_path.join(name.as_str());
+                        let (old_dependency, new_dependency) = (
+                            old_value.and_then(|value| value.get(&name)),
+                            new_value.and_then(|value| value.get(&name)),
+                        );
+                        match (old_dependency, new_dependency) {
+                            (Some(Value::Array(_)), Some(Value::Array(_)))
+                            | (Some(Value::Array(_)), None)
+                            | (None, Some(Value::Array(_))) => {
+                                let old_names = old_dependency
+                                    .and_then(Value::as_array)
+                                    .cloned()
+                                    .unwrap_or_default();
+                                let new_names = new_dependency
+                                    .and_then(Value::as_array)
+                                    .cloned()
+                                    .unwrap_or_default();
+                                for value in &old_names {
+                                    if !new_names.contains(value) {
+                                        let kind = ChangeKind::ValueRemoved {
+                                            value: value.clone(),
+                                        };
+                                        self.report(
+                                            &dependency_path,
+                                            kind,
+                                            direction(Compatibility::Backward),
+                                        );
+                                    }
+                                }
+                                for value in &new_names {
+                                    if !old_names.contains(value) {
+                                        let kind = ChangeKind::ValueAdded {
+                                            value: value.clone(),
+                                        };
+                                        self.report(
+                                            &dependency_path,
+                                            kind,
+                                            direction(Compatibility::Forward),
+                                        );
+                                    }
+                                }
+                            }
+                            (Some(Value::Array(_)), _) | (_, Some(Value::Array(_))) => {
+                                self.report(
+                                    &dependency_path,
+                                    change(old_dependency, new_dependency),
+                                    Compatibility::Incompatible,
+                                );
+                            }
+                            _ => {
+                                let default = old.child(Cow::Owned(Value::Bool(true)), "");
+                                self.compare(
+                                    old.entry(keyword, &name, &default),
+                                    new.entry(keyword, &name, &default),
+                                    &dependency_path,
+                                    flipped,
+                                )?;
+                            }
+                        }
+                    }
+                }
+                "items" => match (old_value, new_value) {
+                    (Some(Value::Array(old_items)), Some(Value::Array(new_items))) => {
+                        for idx in 0..old_items.len().max(new_items.len()) {
+                            self.compare(
+                                old.item(idx),
+                                new.item(idx),
+                                &keyword_path.join(idx),
+                                flipped,
+                            )?;
+                        }
+                    }
+                    (Some(Value::Array(_)), _) | (_, Some(Value::Array(_))) => {
+                        self.report(
+                            &keyword_path,
+                            change(old_value, new_value),
+                            Compatibility::Incompatible,
+                        );
+                    }
+                    _ => self.compare(
+                        old.keyword(keyword),
+                        new.keyword(keyword),
+                        &keyword_path,
+                        flipped,
+                    )?,
+                },
+                "additionalProperties" | "additionalItems" | "contains" | "propertyNames" => self
+                    .compare(
+                    old.keyword(keyword),
+                    new.keyword(keyword),
+                    &keyword_path,
+                    flipped,
+                )?,
+                // A missing `not`, `allOf` or `anyOf` accepts everything, so adding one is narrowing
+                "not" | "allOf" | "anyOf" if old_value.is_none() || new_value.is_none() => {
+                    let compatibility = if old_value.is_none() {
+                        Compatibility::Forward
+                    } else {
+                        Compatibility::Backward
+                    };
+                    self.report(
+                        &keyword_path,
+                        change(old_value, new_value),
+                        direction(compatibility),
+                    );
+                }
+                "not" => self.compare(
+                    old.keyword(keyword),
+                    new.keyword(keyword),
+                    &keyword_path,
+                    !flipped,
+                )?,
+                "allOf" | "anyOf" => {
+                    let (old_members, new_members) = (members(old_value), members(new_value));
+                    if old_members.len() == new_members.len() {
+                        // Both keywords are monotonic, so members are compared one by one
+                        for idx in 0..old_members.len() {
+                            self.compare(
+                                old.element(keyword, idx),
+                                new.element(keyword, idx),
+                                &keyword_path.join(idx),
+                                flipped,
+                            )?;
+                        }
+                        continue;
+                    }
+                    // More members in `allOf` accept fewer instances, in `anyOf` - more
+                    let (added, removed) = if keyword == "allOf" {
+                        (Compatibility::Forward, Compatibility::Backward)
+                    } else {
+                        (Compatibility::Backward, Compatibility::Forward)
+                    };
+                    for member in old_members {
+                        if !new_members.contains(member) {
+                            let kind = ChangeKind::ValueRemoved {
+                                value: member.clone(),
+                            };
+                            self.report(&keyword_path, kind, direction(removed));
+                        }
+                    }
+                    for member in new_members {
+                        if !old_members.contains(member) {
+                            let kind = ChangeKind::ValueAdded {
+                                value: member.clone(),
+                            };
+                            self.report(&keyword_path, kind, direction(added));
+                        }
+                    }
+                }
+                // Narrowing a `oneOf` member may make an instance that matched several members valid
+                "oneOf" | "if" | "then" | "else" => self.report(
+                    &keyword_path,
+                    change(old_value, new_value),
+                    Compatibility::Incompatible,
+                ),
+                // Other keywords are annotations or data
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Compare boolean keywords that accept fewer instances when they are `true`.
+    fn compare_flags(
+        &mut self,
+        path: &JSONPointer,
+        old: Option<&Value>,
+        new: Option<&Value>,
+        flipped: bool,
+    ) {
+        let is_set = |value: Option<&Value>| matches!(value, Some(Value::Bool(true)));
+        let compatibility = match (is_set(old), is_set(new)) {
+            (false, true) => Compatibility::Forward,
+            (true, false) => Compatibility::Backward,
+            _ => return,
+        };
+        let compatibility = if flipped {
+            compatibility.flip()
+        } else {
+            compatibility
+        };
+        self.report(path, change(old, new), compatibility);
+    }
+
+    /// Compare numeric limits, `lower` is `true` for limits like `minimum`.
+    fn compare_limits(
+        &mut self,
+        path: &JSONPointer,
+        old: Option<&Value>,
+        new: Option<&Value>,
+        lower: bool,
+        flipped: bool,
+    ) {
+        let (old_limit, new_limit) = (old.and_then(Value::as_f64), new.and_then(Value::as_f64));
+        let (compatibility, kind) = match (old_limit, new_limit, old, new) {
+            (Some(old_limit), Some(new_limit), Some(old), Some(new)) => {
+                let tightened = if lower {
+                    new_limit > old_limit
+                } else {
+                    new_limit < old_limit
+                };
+                if tightened {
+                    (
+                        Compatibility::Forward,
+                        ChangeKind::Tightened {
+                            old: old.clone(),
+                            new: new.clone(),
+                        },
+                    )
+                } else if new_limit == old_limit {
+                    // E.g. `1` and `1.0`
+                    return;
+                } else {
+                    (
+                        Compatibility::Backward,
+                        ChangeKind::Loosened {
+                            old: old.clone(),
+                            new: new.clone(),
+                        },
+                    )
+                }
+            }
+            (None, Some(_), _, _) => (Compatibility::Forward, change(old, new)),
+            (Some(_), None, _, _) => (Compatibility::Backward, change(old, new)),
+            _ => return,
+        };
+        let compatibility = if flipped {
+            compatibility.flip()
+        } else {
+            compatibility
+        };
+        self.report(path, kind, compatibility);
+    }
+}
+
+/// Whether the new `type` accepts more or fewer instances than the old one.
+fn compare_types(old: Option<&Value>, new: Option<&Value>) -> Compatibility {
+    let (old, new) = (types(old), types(new));
+    let is_subset = |left: &[&str], right: &[&str]| {
+        left.iter()
+            .all(|name| right.contains(name) || (*name == "integer" && right.contains(&"number")))
+    };
+    if is_subset(&old, &new) {
+        Compatibility::Backward
+    } else if is_subset(&new, &old) {
+        Compatibility::Forward
+    } else {
+        Compatibility::Incompatible
+    }
+}
+
+/// Type names allowed by the `type` keyword.
+fn types(value: Option<&Value>) -> Vec<&str> {
+    match value {
+        Some(Value::String(name)) => vec![name.as_str()],
+        Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+        _ => vec!["null", "boolean", "number", "string", "array", "object"],
+    }
+}
+
+fn change(old: Option<&Value>, new: Option<&Value>) -> ChangeKind {
+    match (old, new) {
+        (None, Some(value)) => ChangeKind::Added {
+            value: value.clone(),
+        },
+        (Some(value), None) => ChangeKind::Removed {
+            value: value.clone(),
+        },
+        (Some(old), Some(new)) => ChangeKind::Changed {
+            old: old.clone(),
+            new: new.clone(),
+        },
+        (None, None) => ChangeKind::Changed {
+            old: Value::Null,
+            new: Value::Null,
+        },
+    }
+}
+
+/// Keys of both objects, the old ones go first.
+fn names(old: Option<&Value>, new: Option<&Value>) -> Vec<String> {
+    let mut names: Vec<String> = old
+        .and_then(Value::as_object)
+        .map(|object| object.keys().cloned().collect())
+        .unwrap_or_default();
+    if let Some(object) = new.and_then(Value::as_object) {
+        for name in object.keys() {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+    names
+}
+
+fn members(value: Option<&Value>) -> &[Value] {
+    match value {
+        Some(Value::Array(members)) => members,
+        _ => &[],
+    }
+}
+
+const fn is_legacy(draft: Draft) -> bool {
+    matches!(draft, Draft::Draft4 | Draft::OpenAPI30)
+}
+
+/// The value of `exclusiveMinimum` / `exclusiveMaximum` as a number. In Draft 4 and `OpenAPI` 3.0 these keywords
+/// are flags that make `minimum` / `maximum` exclusive.
+fn exclusive_limit(
+    draft: Draft,
+    keywords: &serde_json::Map<String, Value>,
+    keyword: &str,
+) -> Option<Value> {
+    if is_legacy(draft) {
+        let limit = if keyword == "exclusiveMinimum" {
+            "minimum"
+        } else {
+            "maximum"
+        };
+        match keywords.get(keyword) {
+            Some(Value::Bool(true)) => keywords.get(limit).cloned(),
+            _ => None,
+        }
+    } else {
+        keywords.get(keyword).cloned()
+    }
+}
+
+pub(crate) fn scope_of(draft: Draft, schema: &Value) -> Result<Url, ValidationError<'static>> {
+    match id_of(draft, schema) {
+        Some(id) => Ok(Url::parse(id)?),
+        None => Ok(DEFAULT_SCOPE.clone()),
+    }
+}
+
+impl CompilationOptions {
+    /// Compare two versions of a schema and report all changes that affect validation, together with
+    /// the compatibility they preserve. Annotations like `title` or `description` are not compared.
+    ///
+    /// A change is backward compatible if every instance valid under the `old` schema is valid under
+    /// the `new` one and forward compatible if it is the other way around. Changes that can't be
+    /// classified precisely, e.g. a changed `pattern`, are reported as incompatible.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let old = json!({
+    ///     "properties": {"id": {"type": "integer", "maximum": 100}},
+    ///     "required": ["id"]
+    /// });
+    /// let new = json!({
+    ///     "properties": {"id": {"type": "integer", "maximum": 10}, "name": {"type": "string"}},
+    ///     "required": ["id", "name"]
+    /// });
+    /// let report = JSONSchema::options().compatibility(&old, &new).expect("Valid references");
+    /// assert!(!report.is_backward_compatible());
+    /// assert!(report.is_forward_compatible());
+    /// let breaking: Vec<_> = report.backward_breaking().map(ToString::to_string).collect();
+    /// assert_eq!(
+    ///     breaking,
+    ///     vec![
+    ///         "Tightened from 100 to 10 at '/properties/id/maximum'",
+    ///         "Added \"string\" at '/properties/name/type'",
+    ///         "Added value \"name\" at '/required'"
+    ///     ]
+    /// );
+    /// ```
+    pub fn compatibility(
+        &self,
+        old: &Value,
+        new: &Value,
+    ) -> Result<CompatibilityReport, ValidationError<'static>> {
+        let (old_document, new_document) = (Document::new(self, old)?, Document::new(self, new)?);
+        let (old_node, new_node) = (old_document.root_node()?, new_document.root_node()?);
+        let mut comparer = Comparer {
+            old: old_document,
+            new: new_document,
+            visited: HashSet::new(),
+            changes: Vec::new(),
+        };
+        comparer.compare(old_node, new_node, &JSONPointer::default(), false)?;
+        Ok(CompatibilityReport {
+            changes: comparer.changes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Compatibility;
+    use crate::compilation::JSONSchema;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn changes(old: &Value, new: &Value) -> Vec<(String, Compatibility)> {
+        JSONSchema::options()
+            .compatibility(old, new)
+            .unwrap()
+            .changes
+            .iter()
+            .map(|change| (change.to_string(), change.compatibility))
+            .collect()
+    }
+
+    #[test_case(
+        json!({"required": ["a"]}), json!({"required": ["a", "b"]}),
+        "Added value \"b\" at '/required'", Compatibility::Forward; "required added"
+    )]
+    #[test_case(
+        json!({"required": ["a"]}), json!({}),
+        "Removed [\"a\"] at '/required'", Compatibility::Backward; "required removed"
+    )]
+    #[test_case(
+        json!({"maximum": 10}), json!({"maximum": 5}),
+        "Tightened from 10 to 5 at '/maximum'", Compatibility::Forward; "maximum tightened"
+    )]
+    #[test_case(
+        json!({"minLength": 3}), json!({"minLength": 1}),
+        "Loosened from 3 to 1 at '/minLength'", Compatibility::Backward; "min length loosened"
+    )]
+    #[test_case(
+        json!({"exclusiveMinimum": 1}), json!({}),
+        "Removed 1 at '/exclusiveMinimum'", Compatibility::Backward; "exclusive minimum removed"
+    )]
+    #[test_case(
+        json!({"enum": ["a", "b"]}), json!({"enum": ["a"]}),
+        "Removed value \"b\" at '/enum'", Compatibility::Forward; "enum value removed"
+    )]
+    #[test_case(
+        json!({"enum": ["a"]}), json!({"enum": ["a", "c"]}),
+        "Added value \"c\" at '/enum'", Compatibility::Backward; "enum value added"
+    )]
+    #[test_case(
+        json!({"type": "string"}), json!({"type": "integer"}),
+        "Changed from \"string\" to \"integer\" at '/type'", Compatibility::Incompatible; "type changed"
+    )]
+    #[test_case(
+        json!({"type": "integer"}), json!({"type": "number"}),
+        "Changed from \"integer\" to \"number\" at '/type'", Compatibility::Backward; "type widened"
+    )]
+    #[test_case(
+        json!({"type": ["string", "null"]}), json!({"type": "string"}),
+        "Changed from [\"string\",\"null\"] to \"string\" at '/type'", Compatibility::Forward; "type narrowed"
+    )]
+    #[test_case(
+        json!({"multipleOf": 2}), json!({"multipleOf": 4}),
+        "Tightened from 2 to 4 at '/multipleOf'", Compatibility::Forward; "multiple of"
+    )]
+    #[test_case(
+        json!({"pattern": "^a"}), json!({"pattern": "^b"}),
+        "Changed from \"^a\" to \"^b\" at '/pattern'", Compatibility::Incompatible; "pattern"
+    )]
+    #[test_case(
+        json!({"properties": {"a": {"additionalProperties": false}}}), json!({"properties": {"a": {}}}),
+        "Loosened from false to true at '/properties/a/additionalProperties'", Compatibility::Backward; "nested"
+    )]
+    #[test_case(
+        json!({"additionalProperties": false}), json!({"properties": {"a": {}}, "additionalProperties": false}),
+        "Loosened from false to {} at '/properties/a'", Compatibility::Backward; "property allowed"
+    )]
+    #[test_case(
+        json!({"items": {"maxItems": 2}}), json!({"items": {"maxItems": 3}}),
+        "Loosened from 2 to 3 at '/items/maxItems'", Compatibility::Backward; "items"
+    )]
+    #[test_case(
+        json!({"not": {"maximum": 2}}), json!({"not": {"maximum": 3}}),
+        "Loosened from 2 to 3 at '/not/maximum'", Compatibility::Forward; "negation"
+    )]
+    #[test_case(
+        json!({"anyOf": [{"type": "string"}]}), json!({"anyOf": [{"type": "string"}, {"type": "null"}]}),
+        "Added value {\"type\":\"null\"} at '/anyOf'", Compatibility::Backward; "any of"
+    )]
+    #[test_case(
+        json!({}), json!({"not": {"type": "string"}}),
+        "Added {\"type\":\"string\"} at '/not'", Compatibility::Forward; "negation added"
+    )]
+    #[test_case(
+        json!({"not": {"type": "string"}}), json!({}),
+        "Removed {\"type\":\"string\"} at '/not'", Compatibility::Backward; "negation removed"
+    )]
+    #[test_case(
+        json!({}), json!({"anyOf": [{"const": 1}]}),
+        "Added [{\"const\":1}] at '/anyOf'", Compatibility::Forward; "any of added"
+    )]
+    #[test_case(
+        json!({"anyOf": [{"const": 1}]}), json!({}),
+        "Removed [{\"const\":1}] at '/anyOf'", Compatibility::Backward; "any of removed"
+    )]
+    #[test_case(
+        json!({"oneOf": [{"type": "string"}]}), json!({"oneOf": [{"type": "string"}, {"type": "null"}]}),
+        "Changed from [{\"type\":\"string\"}] to [{\"type\":\"string\"},{\"type\":\"null\"}] at '/oneOf'",
+        Compatibility::Incompatible; "one of"
+    )]
+    #[test_case(
+        json!({"dependencies": {"a": ["b"]}}), json!({"dependencies": {"a": ["b", "c"]}}),
+        "Added value \"c\" at '/dependencies/a'", Compatibility::Forward; "dependencies"
+    )]
+    #[test_case(
+        json!({"properties": {"a": {"$ref": "#/definitions/a"}}, "definitions": {"a": {"maximum": 1}}}),
+        json!({"properties": {"a": {"maximum": 2}}}),
+        "Loosened from 1 to 2 at '/properties/a/maximum'", Compatibility::Backward; "reference"
+    )]
+    #[test_case(
+        json!({"items": [{}], "additionalItems": {"type": "string"}}),
+        json!({"items": [{}, {"type": "string", "maxLength": 3}], "additionalItems": {"type": "string"}}),
+        "Added 3 at '/items/1/maxLength'", Compatibility::Forward; "additional items"
+    )]
+    fn change(old: Value, new: Value, expected: &str, compatibility: Compatibility) {
+        assert_eq!(
+            changes(&old, &new),
+            vec![(expected.to_string(), compatibility)]
+        );
+    }
+
+    #[test_case(json!({"title": "Old", "type": "string"}), json!({"title": "New", "type": "string"}); "annotations")]
+    #[test_case(json!({"maximum": 1}), json!({"maximum": 1.0}); "same number")]
+    #[test_case(
+        json!({"properties": {"next": {"$ref": "#"}}, "maximum": 1}),
+        json!({"properties": {"next": {"$ref": "#/definitions/node"}}, "maximum": 1, "definitions": {"node": {"$ref": "#"}}});
+        "recursion"
+    )]
+    #[test_case(json!({"properties": {"next": {"$ref": "#"}}}), json!({}); "recursion removed")]
+    #[test_case(json!({}), json!({"properties": {"next": {"$ref": "#"}}}); "recursion added")]
+    #[test_case(
+        json!({"properties": {"next": {"$ref": "#"}}}),
+        json!({
+            "properties": {"next": {"$ref": "#/definitions/a"}},
+            "definitions": {"a": {"properties": {"next": {"$ref": "#/definitions/b"}}}, "b": {"$ref": "#"}}
+        });
+        "mutual recursion"
+    )]
+    fn unchanged(old: Value, new: Value) {
+        assert_eq!(changes(&old, &new), vec![]);
+    }
+
+    #[test_case(
+        json!({"$schema": "http://json-schema.org/draft-04/schema#", "minimum": 5, "exclusiveMinimum": true}),
+        json!({"$schema": "http://json-schema.org/draft-07/schema#", "minimum": 5, "exclusiveMinimum": 6}),
+        &[("Tightened from 5 to 6 at '/exclusiveMinimum'", Compatibility::Forward)];
+        "to number"
+    )]
+    #[test_case(
+        json!({"$schema": "http://json-schema.org/draft-07/schema#", "exclusiveMaximum": 5}),
+        json!({"$schema": "http://json-schema.org/draft-04/schema#", "maximum": 5, "exclusiveMaximum": true}),
+        &[("Added 5 at '/maximum'", Compatibility::Forward)];
+        "to flag"
+    )]
+    #[test_case(
+        json!({"$schema": "http://json-schema.org/draft-04/schema#", "maximum": 5, "exclusiveMaximum": true}),
+        json!({"$schema": "http://json-schema.org/draft-07/schema#", "maximum": 5}),
+        &[("Removed 5 at '/exclusiveMaximum'", Compatibility::Backward)];
+        "flag removed"
+    )]
+    fn drafts(old: Value, new: Value, expected: &[(&str, Compatibility)]) {
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|(change, compatibility)| (change.to_string(), *compatibility))
+            .collect();
+        assert_eq!(changes(&old, &new), expected);
+    }
+
+    #[test]
+    fn any_of_replaces_type() {
+        // Both changes are reported, so neither direction is claimed to be compatible
+        let report = JSONSchema::options()
+            .compatibility(
+                &json!({"type": "integer"}),
+                &json!({"anyOf": [{"const": 1}]}),
+            )
+            .unwrap();
+        assert!(!report.is_backward_compatible());
+        assert!(!report.is_forward_compatible());
+    }
+
+    #[test]
+    fn report() {
+        let old = json!({"properties": {"a": {"maximum": 5}, "b": {"minimum": 1}}});
+        let new = json!({"properties": {"a": {"maximum": 10}, "b": {"minimum": 2}}});
+        let report = JSONSchema::options().compatibility(&old, &new).unwrap();
+        assert!(!report.is_backward_compatible());
+        assert!(!report.is_forward_compatible());
+        assert_eq!(report.backward_breaking().count(), 1);
+        assert_eq!(report.forward_breaking().count(), 1);
+        assert_eq!(
+            report.to_string(),
+            "Loosened from 5 to 10 at '/properties/a/maximum'\n\
+             Tightened from 1 to 2 at '/properties/b/minimum'"
+        );
+    }
+}
@@ -0,0 +1,592 @@
+//! A thread-safe cache of compiled schemas.
+use super::DEFAULT_SCOPE;
+use super::{options::CompilationOptions, JSONSchema};
+use crate::{error::CompilationError, schemas};
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, HashMap, HashSet},
+    fmt,
+    hash::{Hash, Hasher},
+    sync::{Arc, Weak},
+};
+use url::Url;
+
+/// Cached schemas are identified by their `$id` if it is present and by a hash of their content otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum CacheKey {
+    Id(String),
+    Content(u64),
+}
+
+/// A link from a schema compiled via `SchemaCache` back to the cache.
+/// It is stored in `CompilationOptions` and used to share compiled `$ref` targets.
+#[derive(Debug, Clone)]
+pub(crate) struct CacheLink {
+    cache: Weak<CacheInner>,
+    key: CacheKey,
+}
+
+impl CacheLink {
+    /// Find a cached schema with the given `$id`.
+    pub(crate) fn lookup(&self, id: &str) -> Option<Arc<JSONSchema<'static>>> {
+        let cache = self.cache.upgrade()?;
+        let target = CacheKey::Id(id.to_string());
+        if target == self.key {
+            return None;
+        }
+        let mut state = cache.state.lock();
+        state.touch(&target)
+    }
+}
+
+/// A thread-safe cache of compiled schemas.
+///
+/// Compiling the same schema over and over again is wasteful, therefore `SchemaCache` stores compiled schemas
+/// and returns them for equal inputs. Schemas are deduplicated by `$id` (`id` in Draft 4) or by content if they have no ID.
+/// If a schema with a known `$id` but with a different content is passed, then the cached one is replaced.
+///
+/// Schemas in the cache share compiled `$ref` targets - if a cached schema references another cached schema by its `$id`,
+/// then the validators compiled for the latter one are reused and no remote document is fetched.
+/// Shared targets are not kept alive by schemas that refer to them - once a target is evicted and dropped,
+/// the reference is resolved again.
+/// Invalidating a schema also invalidates all cached schemas that refer to it.
+///
+/// The cache could be bounded - the least recently used schemas are evicted when the capacity is exceeded.
+///
+/// ```rust
+/// # use jsonschema::{JSONSchema, SchemaCache};
+/// # use serde_json::json;
+/// let cache = SchemaCache::with_capacity(JSONSchema::options(), 100);
+/// let schema = json!({"$id": "http://example.com/item.json", "type": "string"});
+/// let compiled = cache.get_or_compile(&schema).expect("A valid schema");
+/// assert!(compiled.is_valid(&json!("foo")));
+/// assert!(cache.get("http://example.com/item.json").is_some());
+/// ```
+pub struct SchemaCache {
+    inner: Arc<CacheInner>,
+}
+
+struct CacheInner {
+    options: CompilationOptions,
+    capacity: Option<usize>,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<CacheKey, CacheEntry>,
+    // Keys of entries ordered from the least recently used one
+    recency: BTreeMap<u64, CacheKey>,
+    // Schemas that refer to the key schema via `$ref`
+    dependents: HashMap<CacheKey, HashSet<CacheKey>>,
+    clock: u64,
+}
+
+struct CacheEntry {
+    schema: Arc<JSONSchema<'static>>,
+    last_used: u64,
+    references: HashSet<CacheKey>,
+}
+
+impl CacheState {
+    fn touch(&mut self, key: &CacheKey) -> Option<Arc<JSONSchema<'static>>> {
+        let entry = self.entries.get_mut(key)?;
+        self.clock += 1;
+        self.recency.remove(&entry.last_used);
+        self.recency.insert(self.clock, key.clone());
+        entry.last_used = self.clock;
+        Some(Arc::clone(&entry.schema))
+    }
+
+    fn insert(
+        &mut self,
+        key: CacheKey,
+        schema: Arc<JSONSchema<'static>>,
+        references: HashSet<CacheKey>,
+        capacity: Option<usize>,
+    ) {
+        // The same schema could be compiled concurrently by multiple threads
+        self.remove(&key);
+        if let Some(capacity) = capacity {
+            if capacity == 0 {
+                return;
+            }
+            while self.entries.len() >= capacity {
+                self.evict();
+            }
+        }
+        self.clock += 1;
+        self.recency.insert(self.clock, key.clone());
+        for reference in &references {
+            self.dependents
+                .entry(reference.clone())
+                .or_default()
+                .insert(key.clone());
+        }
+        let entry = CacheEntry {
+            schema,
+            last_used: self.clock,
+            references,
+        };
+        self.entries.insert(key, entry);
+    }
+
+    /// Remove the least recently used schema.
+    /// Evicted schemas are still valid, therefore schemas that refer to them are kept.
+    fn evict(&mut self) {
+        let lru = self.recency.values().next().cloned();
+        if let Some(key) = lru {
+            self.remove(&key);
+        }
+    }
+
+    fn remove(&mut self, key: &CacheKey) -> bool {
+        match self.entries.remove(key) {
+            Some(entry) => {
+                self.recency.remove(&entry.last_used);
+                for reference in &entry.references {
+                    if let Some(dependents) = self.dependents.get_mut(reference) {
+                        dependents.remove(key);
+                        if dependents.is_empty() {
+                            self.dependents.remove(reference);
+                        }
+                    }
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn invalidate(&mut self, key: &CacheKey) -> bool {
+        let removed = self.remove(key);
+        if let Some(dependents) = self.dependents.remove(key) {
+            for dependent in &dependents {
+                self.invalidate(dependent);
+            }
+        }
+        removed
+    }
+}
+
+impl SchemaCache {
+    /// Create an unbounded cache. All schemas are compiled with the given options.
+    #[must_use]
+    pub fn new(options: CompilationOptions) -> Self {
+        SchemaCache::build(options, None)
+    }
+
+    /// Create a cache that holds at most `capacity` schemas.
+    /// All schemas are compiled with the given options.
+    #[must_use]
+    pub fn with_capacity(options: CompilationOptions, capacity: usize) -> Self {
+        SchemaCache::build(options, Some(capacity))
+    }
+
+    fn build(options: CompilationOptions, capacity: Option<usize>) -> Self {
+        SchemaCache {
+            inner: Arc::new(CacheInner {
+                options,
+                capacity,
+                state: Mutex::new(CacheState::default()),
+            }),
+        }
+    }
+
+    /// Return a compiled schema from the cache or compile it and store in the cache.
+    pub fn get_or_compile(
+        &self,
+        schema: &Value,
+    ) -> Result<Arc<JSONSchema<'static>>, CompilationError> {
+        let key = self.key_of(schema)?;
+        {
+            let mut state = self.inner.state.lock();
+            if let Some(cached) = state.touch(&key) {
+                if *cached.schema == *schema {
+                    return Ok(cached);
+                }
+                // The same `$id` with a different content or a hash collision
+                state.invalidate(&key);
+            }
+        }
+        // Compile without holding the lock, so other threads are not blocked
+        let mut options = self.inner.options.clone();
+        options.with_cache_link(CacheLink {
+            cache: Arc::downgrade(&self.inner),
+            key: key.clone(),
+        });
+        let compiled = Arc::new(options.compile_owned(schema.clone())?);
+        let mut references = HashSet::new();
+        let draft = self.inner.options.detect_draft(schema);
+        collect_references(draft, schema, &DEFAULT_SCOPE, &mut references);
+        references.remove(&key);
+        self.inner
+            .state
+            .lock()
+            .insert(key, Arc::clone(&compiled), references, self.inner.capacity);
+        Ok(compiled)
+    }
+
+    /// Get a cached schema by its `$id`.
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<Arc<JSONSchema<'static>>> {
+        let key = CacheKey::Id(normalize_id(id).ok()?);
+        self.inner.state.lock().touch(&key)
+    }
+
+    /// Remove a schema with the given `$id` from the cache, together with all schemas that refer to it.
+    /// Returns `true` if the schema was cached.
+    pub fn invalidate(&self, id: &str) -> bool {
+        match normalize_id(id) {
+            Ok(id) => self.inner.state.lock().invalidate(&CacheKey::Id(id)),
+            Err(_) => false,
+        }
+    }
+
+    /// Remove the given schema from the cache, together with all schemas that refer to it.
+    /// Returns `true` if the schema was cached.
+    pub fn invalidate_schema(&self, schema: &Value) -> bool {
+        match self.key_of(schema) {
+            Ok(key) => self.inner.state.lock().invalidate(&key),
+            Err(_) => false,
+        }
+    }
+
+    /// Remove all schemas from the cache.
+    pub fn clear(&self) {
+        let mut state = self.inner.state.lock();
+        state.entries.clear();
+        state.recency.clear();
+        state.dependents.clear();
+    }
+
+    /// The number of cached schemas.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.state.lock().entries.len()
+    }
+
+    /// Whether the cache is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.state.lock().entries.is_empty()
+    }
+
+    fn key_of(&self, schema: &Value) -> Result<CacheKey, CompilationError> {
+        let draft = self.inner.options.detect_draft(schema);
+        match schemas::id_of(draft, schema) {
+            Some(id) => Ok(CacheKey::Id(normalize_id(id)?)),
+            None => {
+                let mut hasher = DefaultHasher::new();
+                hash_value(schema, &mut hasher);
+                Ok(CacheKey::Content(hasher.finish()))
+            }
+        }
+    }
+}
+
+impl fmt::Debug for SchemaCache {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("SchemaCache")
+            .field("options", &self.inner.options)
+            .field("capacity", &self.inner.capacity)
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// IDs are compared in the same form as they are resolved by `$ref`.
+/// Relative IDs are resolved against the default base URI, like the resolver does.
+fn normalize_id(id: &str) -> Result<String, url::ParseError> {
+    let mut url = DEFAULT_SCOPE.join(id)?;
+    url.set_fragment(None);
+    Ok(url.to_string())
+}
+
+/// Collect IDs of documents that `schema` refers to via `$ref`, so dependents are known before references are resolved.
+/// Values that only look like schemas, e.g. inside `enum`, are collected too, which leads only to extra invalidations.
+fn collect_references(
+    draft: schemas::Draft,
+    schema: &Value,
+    scope: &Url,
+    references: &mut HashSet<CacheKey>,
+) {
+    match schema {
+        Value::Object(object) => {
+            let scope = match schemas::id_of(draft, schema).map(|id| scope.join(id)) {
+                Some(Ok(scope)) => scope,
+                _ => scope.clone(),
+            };
+            if let Some(Value::String(reference)) = object.get("$ref") {
+                if let Ok(mut url) = scope.join(reference) {
+                    url.set_fragment(None);
+                    references.insert(CacheKey::Id(url.to_string()));
+                }
+            }
+            for value in object.values() {
+                collect_references(draft, value, &scope, references);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_references(draft, item, scope, references);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn hash_value<H: Hasher>(value: &Value, state: &mut H) {
+    match value {
+        Value::Null => 0_u8.hash(state),
+        Value::Bool(item) => {
+            1_u8.hash(state);
+            item.hash(state);
+        }
+        Value::Number(item) => {
+            2_u8.hash(state);
+            if let Some(number) = item.as_u64() {
+                number.hash(state);
+            } else if let Some(number) = item.as_i64() {
+                number.hash(state);
+            } else if let Some(number) = item.as_f64() {
+                number.to_bits().hash(state);
+            }
+        }
+        Value::String(item) => {
+            3_u8.hash(state);
+            item.hash(state);
+        }
+        Value::Array(items) => {
+            4_u8.hash(state);
+            items.len().hash(state);
+            for item in items {
+                hash_value(item, state);
+            }
+        }
+        Value::Object(items) => {
+            5_u8.hash(state);
+            items.len().hash(state);
+            for (key, item) in items {
+                key.hash(state);
+                hash_value(item, state);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SchemaCache;
+    use crate::compilation::JSONSchema;
+    use serde_json::{json, Value};
+    use std::{sync::Arc, thread};
+
+    fn item() -> Value {
+        json!({
+            "$id": "http://example.com/item.json",
+            "definitions": {"positive": {"minimum": 0}},
+            "type": "integer",
+            "allOf": [{"$ref": "#/definitions/positive"}]
+        })
+    }
+
+    fn order() -> Value {
+        json!({
+            "$id": "http://example.com/order.json",
+            "type": "array",
+            "items": {"$ref": "item.json"}
+        })
+    }
+
+    #[test]
+    fn deduplicated_by_content() {
+        let cache = SchemaCache::new(JSONSchema::options());
+        let first = cache.get_or_compile(&json!({"type": "string"})).unwrap();
+        let second = cache.get_or_compile(&json!({"type": "string"})).unwrap();
+        let other = cache.get_or_compile(&json!({"type": "integer"})).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(!Arc::ptr_eq(&first, &other));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn deduplicated_by_id() {
+        let cache = SchemaCache::new(JSONSchema::options());
+        let first = cache.get_or_compile(&item()).unwrap();
+        let second = cache.get("http://example.com/item.json").unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn replaced_on_change() {
+        let cache = SchemaCache::new(JSONSchema::options());
+        let first = cache.get_or_compile(&item()).unwrap();
+        let changed = json!({"$id": "http://example.com/item.json", "type": "string"});
+        let second = cache.get_or_compile(&changed).unwrap();
+        assert!(!Arc::ptr_eq(&first, &second));
+        assert!(second.is_valid(&json!("a")));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn lru_eviction() {
+        let cache = SchemaCache::with_capacity(JSONSchema::options(), 2);
+        let a = json!({"type": "string"});
+        let b = json!({"type": "integer"});
+        let c = json!({"type": "null"});
+        let first = cache.get_or_compile(&a).unwrap();
+        cache.get_or_compile(&b).unwrap();
+        // `a` becomes the most recently used one, therefore `b` is evicted
+        cache.get_or_compile(&a).unwrap();
+        cache.get_or_compile(&c).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert!(Arc::ptr_eq(&first, &cache.get_or_compile(&a).unwrap()));
+        assert!(cache.invalidate_schema(&c));
+        assert!(!cache.invalidate_schema(&b));
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let cache = SchemaCache::with_capacity(JSONSchema::options(), 0);
+        let compiled = cache.get_or_compile(&item()).unwrap();
+        assert!(compiled.is_valid(&json!(1)));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn shared_references() {
+        let cache = SchemaCache::new(JSONSchema::options());
+        let item = cache.get_or_compile(&item()).unwrap();
+        let order = cache.get_or_compile(&order()).unwrap();
+        // The reference is resolved via the cache, not via fetching a remote document
+        assert!(order.is_valid(&json!([1, 2])));
+        assert!(!order.is_valid(&json!([1, -2])));
+        assert!(!order.is_valid(&json!(["a"])));
+        assert!(item
+            .subschemas
+            .read()
+            .contains_key("http://example.com/item.json"));
+    }
+
+    #[test]
+    fn invalidate_dependents() {
+        let cache = SchemaCache::new(JSONSchema::options());
+        cache.get_or_compile(&item()).unwrap();
+        let order = cache.get_or_compile(&order()).unwrap();
+        assert!(order.is_valid(&json!([1])));
+        assert!(cache.invalidate("http://example.com/item.json"));
+        assert!(cache.get("http://example.com/order.json").is_none());
+        assert!(cache.is_empty());
+        assert!(!cache.invalidate("http://example.com/item.json"));
+    }
+
+    #[test]
+    fn invalidate_unresolved_dependents() {
+        let cache = SchemaCache::new(JSONSchema::options());
+        // Dependents are known before their references are resolved, even if they are compiled first
+        cache.get_or_compile(&order()).unwrap();
+        cache.get_or_compile(&item()).unwrap();
+        assert!(cache.invalidate("http://example.com/item.json"));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn dropped_target() {
+        let cache = SchemaCache::new(JSONSchema::options());
+        let item_schema = cache.get_or_compile(&item()).unwrap();
+        let order = cache.get_or_compile(&order()).unwrap();
+        assert!(order.is_valid(&json!([1])));
+        cache.clear();
+        drop(item_schema);
+        // The reference is resolved again via the cache
+        cache.get_or_compile(&item()).unwrap();
+        assert!(order.is_valid(&json!([1, 2])));
+        assert!(!order.is_valid(&json!([1, -2])));
+        assert_eq!(order.validate(&json!([-1])).unwrap_err().count(), 1);
+    }
+
+    #[test]
+    fn mutual_references_are_freed() {
+        let cache = SchemaCache::new(JSONSchema::options());
+        let a = cache
+            .get_or_compile(&json!({
+                "$id": "http://example.com/a.json",
+                "properties": {"b": {"$ref": "b.json"}}
+            }))
+            .unwrap();
+        let b = cache
+            .get_or_compile(&json!({
+                "$id": "http://example.com/b.json",
+                "properties": {"a": {"$ref": "a.json"}},
+                "type": "object"
+            }))
+            .unwrap();
+        assert!(a.is_valid(&json!({"b": {"a": {}}})));
+        assert!(!a.is_valid(&json!({"b": 1})));
+        assert!(b.is_valid(&json!({"a": {"b": {}}})));
+        let weak_a = Arc::downgrade(&a);
+        let weak_b = Arc::downgrade(&b);
+        drop((a, b));
+        cache.clear();
+        assert!(weak_a.upgrade().is_none());
+        assert!(weak_b.upgrade().is_none());
+    }
+
+    #[test]
+    fn relative_ids() {
+        let cache = SchemaCache::new(JSONSchema::options());
+        cache
+            .get_or_compile(&json!({"$id": "item.json", "type": "integer"}))
+            .unwrap();
+        let order = cache
+            .get_or_compile(&json!({"$id": "order.json", "items": {"$ref": "item.json"}}))
+            .unwrap();
+        assert!(order.is_valid(&json!([1])));
+        assert!(!order.is_valid(&json!(["a"])));
+        assert!(cache.get("item.json").is_some());
+        assert!(cache.invalidate("item.json"));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn shared_errors_outlive_target() {
+        let cache = SchemaCache::new(JSONSchema::options());
+        let item_schema = cache.get_or_compile(&item()).unwrap();
+        let order = cache.get_or_compile(&order()).unwrap();
+        let instance = json!([-1, "a", -2]);
+        let errors = order.validate(&instance).unwrap_err();
+        // Errors outlive the shared schema
+        drop(item_schema);
+        cache.clear();
+        assert_eq!(errors.count(), 3);
+    }
+
+    #[test]
+    fn clear() {
+        let cache = SchemaCache::new(JSONSchema::options());
+        cache.get_or_compile(&item()).unwrap();
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn concurrent_access() {
+        let cache = Arc::new(SchemaCache::with_capacity(JSONSchema::options(), 4));
+        let handles: Vec<_> = (0..8)
+            .map(|idx| {
+                let cache = Arc::clone(&cache);
+                thread::spawn(move || {
+                    let schema = json!({"maximum": idx % 4});
+                    let compiled = cache.get_or_compile(&schema).unwrap();
+                    assert!(compiled.is_valid(&json!(0)));
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(cache.len(), 4);
+    }
+}
@@ -1,14 +1,26 @@
 use super::options::CompilationOptions;
-use crate::schemas;
+use crate::{
+    error::CompilationError,
+    paths::{JSONPointer, PathChunk, SchemaPath},
+    schemas,
+};
+use parking_lot::Mutex;
 use serde_json::Value;
 use std::borrow::Cow;
 use url::{ParseError, Url};
 
-/// Context holds information about used draft and current scope.
+/// Compilation problems with their locations and messages.
+pub(crate) type Problems = Mutex<Vec<(JSONPointer, String)>>;
+
+/// Context holds information about used draft, current scope and the location within the schema.
 #[derive(Debug)]
 pub(crate) struct CompilationContext<'a> {
     pub(crate) scope: Cow<'a, Url>,
     pub(crate) config: Cow<'a, CompilationOptions>,
+    /// Location of the currently compiled schema or keyword within the root schema.
+    pub(crate) schema_path: SchemaPath,
+    /// Collects compilation problems if set, instead of failing on the first one.
+    pub(crate) problems: Option<&'a Problems>,
 }
 
 impl<'a> CompilationContext<'a> {
@@ -16,6 +28,73 @@ impl<'a> CompilationContext<'a> {
         CompilationContext {
             scope: Cow::Owned(scope),
             config,
+            schema_path: SchemaPath::new(),
+            problems: None,
+        }
+    }
+
+    /// Set the location of the root schema. Used when validators are compiled for a subschema.
+    #[inline]
+    pub(crate) fn with_schema_path(mut self, schema_path: SchemaPath) -> Self {
+        self.schema_path = schema_path;
+        self
+    }
+
+    /// Location of the currently compiled schema or keyword as a JSON Pointer.
+    #[inline]
+    pub(crate) fn location(&self) -> JSONPointer {
+        JSONPointer::from(&self.schema_path)
+    }
+
+    /// Collect compilation problems into `problems`, so the compilation continues after them and finds them all.
+    #[inline]
+    pub(crate) fn with_problems(mut self, problems: &'a Problems) -> Self {
+        self.problems = Some(problems);
+        self
+    }
+
+    /// Record a problem at `location` if problems are collected, otherwise return the error.
+    #[inline]
+    pub(crate) fn problem(
+        &self,
+        location: JSONPointer,
+        error: CompilationError,
+        message: impl FnOnce(&CompilationError) -> String,
+    ) -> Result<(), CompilationError> {
+        match self.problems {
+            Some(problems) => {
+                problems.lock().push((location, message(&error)));
+                Ok(())
+            }
+            None => Err(error),
+        }
+    }
+
+    /// The number of collected problems.
+    #[inline]
+    pub(crate) fn problem_count(&self) -> usize {
+        self.problems.map_or(0, |problems| problems.lock().len())
+    }
+
+    /// Create a context for a nested location, e.g. a keyword or a property inside `properties`.
+    #[inline]
+    pub(crate) fn with_path(&'a self, chunk: impl Into<PathChunk>) -> Self {
+        CompilationContext {
+            scope: Cow::Borrowed(self.scope.as_ref()),
+            config: Cow::Borrowed(&self.config),
+            schema_path: self.schema_path.push(chunk),
+            problems: self.problems,
+        }
+    }
+
+    /// Create a context for a sibling keyword, e.g. `then` for `if`.
+    #[inline]
+    pub(crate) fn with_sibling(&'a self, keyword: &str) -> Self {
+        CompilationContext {
+            scope: Cow::Borrowed(self.scope.as_ref()),
+            config: Cow::Borrowed(&self.config),
+            schema_path: self.schema_path.parent().push(keyword),
+            problems: self.problems,
         }
     }
 
@@ -36,11 +115,15 @@ impl<'a> CompilationContext<'a> {
             Ok(CompilationContext {
                 scope: Cow::Owned(scope),
                 config: Cow::Borrowed(&self.config),
+                schema_path: self.schema_path.clone(),
+                problems: self.problems,
             })
         } else {
             Ok(CompilationContext {
                 scope: Cow::Borrowed(self.scope.as_ref()),
                 config: Cow::Borrowed(&self.config),
+                schema_path: self.schema_path.clone(),
+                problems: self.problems,
             })
         }
     }
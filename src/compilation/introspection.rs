@@ -0,0 +1,246 @@
+//! Read-only view over the structure of a compiled schema.
+use super::JSONSchema;
+use crate::{
+    location::KeywordNode as Validator,
+    paths::{JSONPointer, PathChunk},
+};
+use serde_json::Value;
+
+/// A (sub)schema that is compiled into validators.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaNode {
+    /// Location of the schema within the root schema.
+    pub location: JSONPointer,
+    /// The value of a boolean schema, `None` for object schemas.
+    pub boolean: Option<bool>,
+    /// Keywords compiled into validators. Keywords that have no effect in the used draft,
+    /// or don't affect validation at all (e.g. `definitions` or `additionalProperties: true`) are not present.
+    pub keywords: Vec<KeywordNode>,
+}
+
+/// A keyword compiled into a validator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeywordNode {
+    /// Keyword name, e.g. `properties`.
+    pub keyword: String,
+    /// Keyword value from the schema.
+    pub value: Value,
+    /// Location of the keyword within the root schema, e.g. `/properties/foo/type`.
+    pub location: JSONPointer,
+    /// Subschemas compiled as a part of this keyword.
+    /// Targets of `$ref` are compiled on demand during validation and they are not included.
+    pub subschemas: Vec<SchemaNode>,
+}
+
+impl SchemaNode {
+    /// Iterate over all keywords in this schema and its subschemas in the depth-first order.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({
+    ///     "properties": {
+    ///         "name": {"pattern": "^[a-z]+$"},
+    ///         "tags": {"items": {"pattern": "^#"}}
+    ///     }
+    /// });
+    /// let compiled = JSONSchema::compile(&schema).expect("A valid schema");
+    /// let tree = compiled.tree();
+    /// let patterns: Vec<String> = tree
+    ///     .iter()
+    ///     .filter(|node| node.keyword == "pattern")
+    ///     .map(|node| node.location.to_string())
+    ///     .collect();
+    /// assert_eq!(patterns, vec!["/properties/name/pattern", "/properties/tags/items/pattern"]);
+    /// ```
+    #[must_use]
+    pub fn iter(&self) -> KeywordIter<'_> {
+        KeywordIter {
+            stack: self.keywords.iter().rev().collect(),
+        }
+    }
+}
+
+/// Depth-first iterator over keywords of a `SchemaNode`.
+#[derive(Debug)]
+pub struct KeywordIter<'a> {
+    stack: Vec<&'a KeywordNode>,
+}
+
+impl<'a> Iterator for KeywordIter<'a> {
+    type Item = &'a KeywordNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        for subschema in node.subschemas.iter().rev() {
+            self.stack.extend(subschema.keywords.iter().rev());
+        }
+        Some(node)
+    }
+}
+
+impl<'a> IntoIterator for &'a SchemaNode {
+    type Item = &'a KeywordNode;
+    type IntoIter = KeywordIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> JSONSchema<'a> {
+    /// Get the structure of the compiled schema - which keywords are compiled into validators,
+    /// where they are located and what subschemas they contain.
+    ///
+    /// The tree is built from the compiled validators, so it describes the normalized schema
+    /// if `CompilationOptions::with_normalization` is used.
+    #[must_use]
+    pub fn tree(&self) -> SchemaNode {
+        let document = self.normalized.as_ref().unwrap_or(&self.schema);
+        schema_node(document, JSONPointer::default(), &self.validators)
+    }
+}
+
+fn schema_node(document: &Value, location: JSONPointer, validators: &[Validator]) -> SchemaNode {
+    let boolean = document
+        .pointer(&location.to_string())
+        .and_then(Value::as_bool);
+    let keywords = if boolean.is_some() {
+        // Boolean schemas are compiled into a single validator located at the schema itself
+        Vec::new()
+    } else {
+        validators
+            .iter()
+            .filter_map(|validator| keyword_node(document, &location, validator))
+            .collect()
+    };
+    SchemaNode {
+        location,
+        boolean,
+        keywords,
+    }
+}
+
+fn keyword_node(
+    document: &Value,
+    schema_location: &JSONPointer,
+    validator: &Validator,
+) -> Option<KeywordNode> {
+    let keyword = match validator.schema_path.iter().last()? {
+        PathChunk::Property(keyword) => keyword.clone(),
+        PathChunk::Index(_) => return None,
+    };
+    let value = document
+        .pointer(&validator.schema_path.to_string())?
+        .clone();
+    let subschemas = validator
+        .validator
+        .subschemas()
+        .into_iter()
+        .filter_map(|(relative, validators)| {
+            let location = relative
+                .iter()
+                .fold(schema_location.clone(), |location, chunk| {
+                    location.join(chunk.clone())
+                });
+            // E.g. property dependencies in `dependencies` are compiled into validators too
+            match document.pointer(&location.to_string()) {
+                Some(Value::Object(_)) | Some(Value::Bool(_)) => {
+                    Some(schema_node(document, location, validators))
+                }
+                _ => None,
+            }
+        })
+        .collect();
+    Some(KeywordNode {
+        keyword,
+        value,
+        location: validator.schema_path.clone(),
+        subschemas,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compilation::JSONSchema;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn locations(schema: &Value) -> Vec<String> {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        compiled
+            .tree()
+            .iter()
+            .map(|node| node.location.to_string())
+            .collect()
+    }
+
+    #[test_case(json!({"type": "string", "minLength": 1}), &["/minLength", "/type"])]
+    #[test_case(json!({"properties": {"a": {"required": ["b"]}}}), &["/properties", "/properties/a/required"])]
+    #[test_case(json!({"items": [{"type": "string"}, true]}), &["/items", "/items/0/type"])]
+    #[test_case(json!({"anyOf": [{"minimum": 1}, {"maximum": 0}]}), &["/anyOf", "/anyOf/0/minimum", "/anyOf/1/maximum"])]
+    #[test_case(json!({"if": {"minimum": 1}, "then": {"multipleOf": 2}, "else": {"const": 0}}), &["/if", "/if/minimum", "/then/multipleOf", "/else/const"])]
+    #[test_case(json!({"patternProperties": {"^a/": {"type": "null"}}}), &["/patternProperties", "/patternProperties/^a~1/type"])]
+    #[test_case(json!({"dependencies": {"a": ["b"], "c": {"minProperties": 2}}}), &["/dependencies", "/dependencies/c/minProperties"])]
+    #[test_case(json!({"$ref": "#/definitions/a", "definitions": {"a": {"type": "string"}}}), &["/$ref"])]
+    #[test_case(json!({"additionalProperties": true, "title": "Foo", "not": {"type": "null"}}), &["/not", "/not/type"])]
+    fn tree_locations(schema: Value, expected: &[&str]) {
+        assert_eq!(locations(&schema), expected)
+    }
+
+    #[test]
+    fn nodes() {
+        let schema = json!({"properties": {"a": false}, "required": ["a"]});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let tree = compiled.tree();
+        assert!(tree.location.is_empty());
+        assert_eq!(tree.boolean, None);
+        assert_eq!(tree.keywords.len(), 2);
+        let properties = &tree.keywords[0];
+        assert_eq!(properties.keyword, "properties");
+        assert_eq!(properties.value, json!({"a": false}));
+        assert_eq!(properties.subschemas.len(), 1);
+        assert_eq!(properties.subschemas[0].boolean, Some(false));
+        assert_eq!(
+            properties.subschemas[0].location.to_string(),
+            "/properties/a"
+        );
+        assert_eq!(tree.keywords[1].value, json!(["a"]));
+    }
+
+    #[test]
+    fn normalized() {
+        let schema = json!({"allOf": [{"allOf": [{"minimum": 1}]}], "type": ["integer", "number"]});
+        let compiled = JSONSchema::options()
+            .with_normalization()
+            .compile(&schema)
+            .unwrap();
+        let tree = compiled.tree();
+        let keywords: Vec<_> = tree
+            .iter()
+            .map(|node| (node.location.to_string(), node.value.clone()))
+            .collect();
+        assert_eq!(
+            keywords,
+            vec![
+                ("/minimum".to_string(), json!(1)),
+                ("/type".to_string(), json!("number"))
+            ]
+        );
+    }
+
+    #[test]
+    fn discriminator_branches_are_not_duplicated() {
+        let schema = json!({
+            "oneOf": [{"$ref": "#/components/schemas/A"}, {"$ref": "#/components/schemas/B"}],
+            "discriminator": {"propertyName": "kind"},
+            "components": {"schemas": {"A": {"type": "object"}, "B": {"type": "object"}}}
+        });
+        let compiled = JSONSchema::options()
+            .with_draft(crate::schemas::Draft::OpenAPI30)
+            .compile(&schema)
+            .unwrap();
+        let tree = compiled.tree();
+        assert_eq!(tree.keywords[0].subschemas.len(), 2);
+    }
+}
@@ -1,18 +1,28 @@
 //! Schema compilation.
 //! The main idea is to compile the input JSON Schema to a validators tree that will contain
 //! everything needed to perform such validation in runtime.
+pub(crate) mod cache;
 pub(crate) mod context;
+pub(crate) mod introspection;
 pub(crate) mod options;
+pub(crate) mod reachable;
+pub(crate) mod subschema;
 
 use crate::{
-    error::{CompilationError, ErrorIterator},
+    error::{CompilationError, ErrorIterator, ValidationError},
     keywords,
-    keywords::Validators,
+    keywords::{BoxedValidator, Validators},
+    location::KeywordNode,
+    paths::InstancePath,
+    profile,
     resolver::Resolver,
+    trace,
 };
 use context::CompilationContext;
 use options::CompilationOptions;
-use serde_json::Value;
+use parking_lot::RwLock;
+use serde_json::{Map, Value};
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
 
 use url::Url;
 
@@ -21,10 +31,13 @@ pub(crate) const DEFAULT_ROOT_URL: &str = "json-schema:///";
 /// The structure that holds a JSON Schema compiled into a validation tree
 #[derive(Debug)]
 pub struct JSONSchema<'a> {
-    pub(crate) schema: &'a Value,
+    pub(crate) schema: Cow<'a, Value>,
+    /// The normalized schema that is compiled instead of `schema`, see `CompilationOptions::with_normalization`.
+    pub(crate) normalized: Option<Value>,
     pub(crate) validators: Validators,
-    pub(crate) resolver: Resolver<'a>,
+    pub(crate) resolver: Resolver,
     pub(crate) context: CompilationContext<'a>,
+    pub(crate) subschemas: RwLock<HashMap<String, Arc<Validators>>>,
 }
 
 lazy_static::lazy_static! {
@@ -58,13 +71,15 @@ impl<'a> JSONSchema<'a> {
     }
 
     /// Run validation against `instance` and return an iterator over `ValidationError` in the error case.
+    ///
+    /// Errors are produced lazily - the instance is validated only as far as the iterator is consumed.
+    /// The exception is `items` with a single schema: without `CompilationOptions::with_max_errors`,
+    /// large arrays are validated in parallel and all their errors are collected at once.
+    /// If a custom `MessageFormatter` is set in `CompilationOptions`, then it is used to build error messages.
+    /// If `CompilationOptions::with_max_errors` is set, then validation stops after that many errors.
     #[inline]
     pub fn validate(&'a self, instance: &'a Value) -> Result<(), ErrorIterator<'a>> {
-        let mut errors = self
-            .validators
-            .iter()
-            .flat_map(move |validator| validator.validate(self, instance))
-            .peekable();
+        let mut errors = self.iter_errors(&self.validators, instance).peekable();
         if errors.peek().is_none() {
             Ok(())
         } else {
@@ -72,6 +87,51 @@ impl<'a> JSONSchema<'a> {
         }
     }
 
+    /// Run validation against `instance` and return only the first error, if any.
+    ///
+    /// Errors are produced lazily, so validation stops right after the first error is found.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({"items": {"type": "string"}});
+    /// let compiled = JSONSchema::compile(&schema).expect("A valid schema");
+    /// let instance = json!(["a", 1, 2]);
+    /// let error = compiled.validate_first(&instance).expect_err("Invalid instance");
+    /// assert_eq!(error.instance_path.to_string(), "/1");
+    /// ```
+    #[inline]
+    pub fn validate_first(&'a self, instance: &'a Value) -> Result<(), ValidationError<'a>> {
+        self.iter_errors(&self.validators, instance)
+            .next()
+            .map_or(Ok(()), Err)
+    }
+
+    /// Validate `instance` against `validators` that are compiled from this schema or its subschema.
+    #[inline]
+    pub(crate) fn iter_errors(
+        &'a self,
+        validators: &'a [KeywordNode],
+        instance: &'a Value,
+    ) -> ErrorIterator<'a> {
+        let instance_path = InstancePath::new();
+        let errors: ErrorIterator<'a> = Box::new(
+            validators
+                .iter()
+                .flat_map(move |validator| validator.validate(self, instance, &instance_path)),
+        );
+        let errors: ErrorIterator<'a> = match self.context.config.message_formatter() {
+            Some(formatter) => {
+                Box::new(errors.map(move |error| error.with_formatted_message(formatter)))
+            }
+            None => errors,
+        };
+        match self.context.config.max_errors() {
+            Some(max_errors) => Box::new(errors.take(max_errors)),
+            None => errors,
+        }
+    }
+
     /// Run validation against `instance` but return a boolean result instead of an iterator.
     /// It is useful for cases, where it is important to only know the fact if the data is valid or not.
     /// This approach is much faster, than `validate`.
@@ -85,46 +145,158 @@ impl<'a> JSONSchema<'a> {
 }
 
 /// Compile JSON schema into a tree of validators.
+///
+/// If the context collects problems, invalid keywords and subschemas are recorded and skipped,
+/// and the rest of the schema is still compiled.
 #[inline]
 pub(crate) fn compile_validators(
     schema: &Value,
     context: &CompilationContext,
 ) -> Result<Validators, CompilationError> {
-    let context = context.push(schema)?;
+    let context = match context.push(schema) {
+        Ok(context) => context,
+        Err(error) => {
+            context.problem(context.location(), error.into(), |_| {
+                format!("Invalid identifier: {}", error)
+            })?;
+            return Ok(Vec::new());
+        }
+    };
     match schema {
-        Value::Bool(value) => Ok(vec![
-            keywords::boolean::compile(*value).expect("Should always compile")?
-        ]),
+        Value::Bool(value) => {
+            let validator = keywords::boolean::compile(*value).expect("Should always compile")?;
+            Ok(vec![instrument(validator, &value.to_string(), &context)])
+        }
         Value::Object(object) => {
             if let Some(reference) = object.get("$ref") {
-                if let Value::String(reference) = reference {
-                    Ok(vec![keywords::ref_::compile(schema, reference, &context)
-                        .expect("Should always return Some")?])
-                } else {
-                    Err(CompilationError::SchemaError)
+                let context = context.with_path("$ref");
+                let validator = match reference {
+                    Value::String(reference) => {
+                        keywords::ref_::compile(schema, reference, &context)
+                            .expect("Should always return Some")
+                    }
+                    _ => Err(CompilationError::SchemaError),
+                };
+                match validator {
+                    Ok(validator) => Ok(vec![instrument(validator, "$ref", &context)]),
+                    Err(error) => context
+                        .problem(context.location(), error, |error| {
+                            describe("$ref", reference, error)
+                        })
+                        .map(|_| Vec::new()),
                 }
             } else {
-                let mut validators = Vec::with_capacity(object.len());
-                for (keyword, subschema) in object {
-                    if let Some(compilation_func) = context.config.draft().get_validator(keyword) {
-                        if let Some(validator) = compilation_func(object, subschema, &context) {
-                            validators.push(validator?)
-                        }
-                    }
+                compile_keywords(object, &context)
+            }
+        }
+        _ => context
+            .problem(context.location(), CompilationError::SchemaError, |_| {
+                "A schema must be an object or a boolean".to_string()
+            })
+            .map(|_| Vec::new()),
+    }
+}
+
+/// Compile keywords of an object schema.
+#[inline]
+fn compile_keywords(
+    object: &Map<String, Value>,
+    context: &CompilationContext,
+) -> Result<Validators, CompilationError> {
+    let mut validators = Vec::with_capacity(object.len());
+    for (keyword, subschema) in object {
+        if let Some(compilation_func) = context.config.draft().get_validator(keyword) {
+            let context = context.with_path(keyword.as_str());
+            let problems = context.problem_count();
+            let validator = compilation_func(object, subschema, &context);
+            match validator {
+                Some(Ok(validator)) => {
+                    validators.push((keyword.as_str(), instrument(validator, keyword, &context)))
                 }
-                Ok(validators)
+                // Problems in subschemas are already recorded at their own locations
+                Some(Err(_)) if context.problem_count() > problems => {}
+                Some(Err(error)) => context.problem(context.location(), error, |error| {
+                    describe(keyword, subschema, error)
+                })?,
+                None => {}
             }
+        } else if context.config.strict() && !context.config.draft().is_known_keyword(keyword) {
+            let location = context.location().join(keyword.as_str());
+            let error = CompilationError::UnknownKeyword {
+                keyword: keyword.clone(),
+                schema_path: location.clone(),
+            };
+            context.problem(location, error, |error| describe(keyword, subschema, error))?;
         }
-        _ => Err(CompilationError::SchemaError),
+    }
+    match object.get("errorMessage") {
+        Some(error_message) if context.config.error_messages() => {
+            let problems = context.problem_count();
+            keywords::error_message::apply(validators, error_message).or_else(|error| {
+                if context.problem_count() > problems {
+                    return Ok(Vec::new());
+                }
+                context
+                    .problem(context.location().join("errorMessage"), error, |error| {
+                        describe("errorMessage", error_message, error)
+                    })
+                    .map(|_| Vec::new())
+            })
+        }
+        _ => Ok(validators
+            .into_iter()
+            .map(|(_, validator)| validator)
+            .collect()),
     }
 }
 
+/// A message for a keyword that can't be compiled.
+fn describe(keyword: &str, value: &Value, error: &CompilationError) -> String {
+    match error {
+        CompilationError::SchemaError => format!("Invalid `{}` value: {}", keyword, value),
+        CompilationError::UnknownKeyword { keyword, .. } => {
+            format!("Unknown keyword `{}`", keyword)
+        }
+        CompilationError::UnknownFormat { format, .. } => format!("Unknown format `{}`", format),
+    }
+}
+
+/// Store the keyword together with its location and wrap it into validators that observe its evaluation,
+/// if it is requested in options.
+#[inline]
+fn instrument(
+    validator: BoxedValidator,
+    keyword: &str,
+    context: &CompilationContext,
+) -> KeywordNode {
+    let validator = if context.config.tracing() {
+        trace::wrap(validator, keyword, &context.location())
+    } else {
+        validator
+    };
+    let validator = match context.config.profiler() {
+        Some(profiler) => profile::wrap(validator, keyword, &context.location(), profiler),
+        None => validator,
+    };
+    KeywordNode::new(validator, context.location())
+}
+
 #[cfg(test)]
 mod tests {
     use super::JSONSchema;
-    use crate::{error::ValidationError, schemas};
+    use crate::{
+        error::{DefaultMessageFormatter, MessageFormatter, ValidationError, ValidationErrorKind},
+        schemas,
+    };
     use serde_json::{from_str, json, Value};
-    use std::{borrow::Cow, fs::File, io::Read, path::Path};
+    use std::{
+        borrow::Cow,
+        fs::File,
+        io::Read,
+        path::Path,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+    use test_case::test_case;
     use url::Url;
 
     fn load(path: &str, idx: usize) -> Value {
@@ -199,4 +371,124 @@ mod tests {
             r#"'"a"' is shorter than 3 characters"#
         );
     }
+
+    #[test]
+    fn instance_paths() {
+        let schema = json!({"properties": {"foo": {"items": {"type": "string"}}}});
+        let value = json!({"foo": ["a", 1]});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let errors: Vec<ValidationError> = compiled.validate(&value).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].instance_path.to_string(), "/foo/1");
+    }
+
+    struct Custom;
+
+    impl MessageFormatter for Custom {
+        fn format_message(&self, error: &ValidationError<'_>) -> String {
+            match *error.kind {
+                ValidationErrorKind::MinLength { limit } => {
+                    format!("at {}: at least {} chars", error.instance_path, limit)
+                }
+                _ => DefaultMessageFormatter.format_message(error),
+            }
+        }
+    }
+
+    #[test]
+    fn message_formatter() {
+        let schema = json!({"properties": {"foo": {"minLength": 3}}, "minProperties": 2});
+        let value = json!({"foo": "a"});
+        let compiled = JSONSchema::options()
+            .with_message_formatter(Custom)
+            .compile(&schema)
+            .unwrap();
+        let mut errors: Vec<String> = compiled
+            .validate(&value)
+            .unwrap_err()
+            .map(|error| error.to_string())
+            .collect();
+        errors.sort();
+        assert_eq!(
+            errors,
+            vec![
+                "at /foo: at least 3 chars".to_string(),
+                r#"{"foo":"a"} has less than 2 properties"#.to_string()
+            ]
+        );
+    }
+
+    static CHECKS: AtomicUsize = AtomicUsize::new(0);
+
+    fn counting_check(_: &str) -> bool {
+        CHECKS.fetch_add(1, Ordering::SeqCst);
+        false
+    }
+
+    fn counting_convert(_: &str) -> Result<Option<String>, ValidationError<'static>> {
+        Ok(None)
+    }
+
+    #[test]
+    fn errors_are_lazy() {
+        let schema = json!({"items": {"contentEncoding": "counting"}});
+        // Without a limit, large arrays are validated in parallel
+        let compiled = JSONSchema::options()
+            .with_content_encoding("counting", counting_check, counting_convert)
+            .with_max_errors(10)
+            .compile(&schema)
+            .unwrap();
+        let instance = json!(vec!["a"; 100]);
+        let mut errors = compiled.validate(&instance).unwrap_err();
+        assert!(errors.next().is_some());
+        assert_eq!(CHECKS.load(Ordering::SeqCst), 1);
+    }
+
+    #[test_case(None, 5)]
+    #[test_case(Some(0), 5)]
+    #[test_case(Some(1), 1)]
+    #[test_case(Some(2), 2)]
+    #[test_case(Some(10), 5)]
+    fn max_errors(max_errors: Option<usize>, expected: usize) {
+        let schema = json!({"items": {"type": "string"}});
+        let mut options = JSONSchema::options();
+        if let Some(max_errors) = max_errors {
+            options.with_max_errors(max_errors);
+        }
+        let compiled = options.compile(&schema).unwrap();
+        let instance = json!([1, 2, 3, 4, 5]);
+        let result = compiled.validate(&instance);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().count(), expected);
+    }
+
+    #[test_case(None)]
+    #[test_case(Some(100))]
+    fn parallel_items(max_errors: Option<usize>) {
+        // Errors are in the same order regardless of parallel validation
+        let schema = json!({"items": {"type": "string"}});
+        let mut options = JSONSchema::options();
+        if let Some(max_errors) = max_errors {
+            options.with_max_errors(max_errors);
+        }
+        let compiled = options.compile(&schema).unwrap();
+        let instance = json!((0..20).collect::<Vec<_>>());
+        let paths: Vec<_> = compiled
+            .validate(&instance)
+            .unwrap_err()
+            .map(|error| error.instance_path.to_string())
+            .collect();
+        let expected: Vec<_> = (0..20).map(|idx| format!("/{}", idx)).collect();
+        assert_eq!(paths, expected);
+    }
+
+    #[test]
+    fn validate_first() {
+        let schema = json!({"properties": {"foo": {"type": "string"}, "bar": {"type": "string"}}});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        assert!(compiled.validate_first(&json!({"foo": "a"})).is_ok());
+        let instance = json!({"foo": 1, "bar": 2});
+        let error = compiled.validate_first(&instance).unwrap_err();
+        assert_eq!(error.instance_path.to_string(), "/bar");
+    }
 }
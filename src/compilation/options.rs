@@ -1,16 +1,22 @@
 use crate::{
-    compilation::{compile_validators, context::CompilationContext, JSONSchema, DEFAULT_SCOPE},
+    compilation::{
+        cache::CacheLink, compile_validators, context::CompilationContext, reachable, JSONSchema,
+        DEFAULT_SCOPE,
+    },
     content_encoding::{
         ContentEncodingCheckType, ContentEncodingConverterType,
         DEFAULT_CONTENT_ENCODING_CHECKS_AND_CONVERTERS,
     },
     content_media_type::{ContentMediaTypeCheckType, DEFAULT_CONTENT_MEDIA_TYPE_CHECKS},
-    error::CompilationError,
+    error::{CompilationError, MessageFormatter},
+    normalizer,
+    profile::Profiler,
     resolver::Resolver,
     schemas,
 };
+use parking_lot::RwLock;
 use serde_json::Value;
-use std::{borrow::Cow, collections::HashMap, fmt};
+use std::{borrow::Cow, collections::HashMap, fmt, sync::Arc};
 
 /// Full configuration to guide the `JSONSchema` compilation.
 ///
@@ -19,9 +25,22 @@ use std::{borrow::Cow, collections::HashMap, fmt};
 #[derive(Clone, Default)]
 pub struct CompilationOptions {
     draft: Option<schemas::Draft>,
-    content_media_type_checks: HashMap<&'static str, Option<ContentMediaTypeCheckType>>,
-    content_encoding_checks_and_converters:
-        HashMap<&'static str, Option<(ContentEncodingCheckType, ContentEncodingConverterType)>>,
+    content_media_type_checks: HashMap<Cow<'static, str>, Option<ContentMediaTypeCheckType>>,
+    content_encoding_checks_and_converters: HashMap<
+        Cow<'static, str>,
+        Option<(ContentEncodingCheckType, ContentEncodingConverterType)>,
+    >,
+    message_formatter: Option<Arc<dyn MessageFormatter>>,
+    max_errors: Option<usize>,
+    openapi_direction: Option<schemas::OpenAPIDirection>,
+    cache: Option<CacheLink>,
+    tracing: bool,
+    profiling: bool,
+    profiler: Option<Arc<Profiler>>,
+    normalization: bool,
+    strict: bool,
+    ignore_formats: bool,
+    error_messages: bool,
 }
 
 impl CompilationOptions {
@@ -29,8 +48,27 @@ impl CompilationOptions {
         self.draft.unwrap_or_default()
     }
 
+    /// Draft that will be used to compile `schema`.
+    pub(crate) fn detect_draft(&self, schema: &Value) -> schemas::Draft {
+        self.draft
+            .or_else(|| schemas::draft_from_schema(schema))
+            .unwrap_or_default()
+    }
+
     /// Compile `schema` into `JSONSchema` using the currently defined options.
     pub fn compile<'a>(&self, schema: &'a Value) -> Result<JSONSchema<'a>, CompilationError> {
+        self.compile_cow(Cow::Borrowed(schema))
+    }
+
+    /// Compile `schema` into `JSONSchema` that owns the schema document.
+    pub(crate) fn compile_owned(
+        &self,
+        schema: Value,
+    ) -> Result<JSONSchema<'static>, CompilationError> {
+        self.compile_cow(Cow::Owned(schema))
+    }
+
+    fn compile_cow<'a>(&self, schema: Cow<'a, Value>) -> Result<JSONSchema<'a>, CompilationError> {
         // Draft is detected in the following precedence order:
         //   - Explicitly specified;
         //   - $schema field in the document;
@@ -44,28 +82,64 @@ impl CompilationOptions {
         // memory-related operations to be explicit
         let mut config = self.clone();
         if self.draft.is_none() {
-            if let Some(draft) = schemas::draft_from_schema(schema) {
+            if let Some(draft) = schemas::draft_from_schema(&schema) {
                 config.with_draft(draft);
             }
         }
-        let processed_config: Cow<'_, CompilationOptions> = Cow::Owned(config);
-        let draft = processed_config.draft();
+        let draft = config.draft();
 
-        let scope = match schemas::id_of(draft, schema) {
-            Some(url) => url::Url::parse(url)?,
+        // Relative IDs are resolved against the default base URI, like nested ones
+        let scope = match schemas::id_of(draft, &schema) {
+            Some(url) => DEFAULT_SCOPE.join(url)?,
             None => DEFAULT_SCOPE.clone(),
         };
-        let resolver = Resolver::new(draft, &scope, schema)?;
-        let context = CompilationContext::new(scope, processed_config);
+        let resolver = Resolver::new(draft, &scope, &schema)?;
+        config.build(schema, scope, resolver)
+    }
+
+    fn build<'a>(
+        mut self,
+        schema: Cow<'a, Value>,
+        scope: url::Url,
+        resolver: Resolver,
+    ) -> Result<JSONSchema<'a>, CompilationError> {
+        if self.profiling {
+            // Every compiled schema has its own statistics
+            self.profiler = Some(Arc::new(Profiler::default()));
+        }
+        let normalized = if self.normalization {
+            Some(normalizer::normalize(self.draft(), &schema))
+        } else {
+            None
+        };
+        let context = CompilationContext::new(scope, Cow::Owned(self));
 
-        let mut validators = compile_validators(schema, &context)?;
+        let mut validators = compile_validators(normalized.as_ref().unwrap_or(&schema), &context)?;
         validators.shrink_to_fit();
+        if context.config.strict() {
+            // Definitions and `$ref` targets are compiled lazily, if at all, but they are checked upfront
+            let reachable = reachable::find(
+                context.config.draft(),
+                &schema,
+                &context.scope,
+                &resolver,
+                &context.config,
+            );
+            for subschema in reachable.subschemas {
+                let context =
+                    CompilationContext::new(subschema.scope, Cow::Borrowed(&context.config))
+                        .with_schema_path(subschema.schema_path);
+                compile_validators(&subschema.schema, &context)?;
+            }
+        }
 
         Ok(JSONSchema {
             schema,
+            normalized,
             resolver,
             validators,
             context,
+            subschemas: RwLock::new(HashMap::new()),
         })
     }
 
@@ -116,11 +190,11 @@ impl CompilationOptions {
     /// ```
     pub fn with_content_media_type(
         &mut self,
-        media_type: &'static str,
+        media_type: impl Into<Cow<'static, str>>,
         media_type_check: ContentMediaTypeCheckType,
     ) -> &mut Self {
         self.content_media_type_checks
-            .insert(media_type, Some(media_type_check));
+            .insert(media_type.into(), Some(media_type_check));
         self
     }
 
@@ -132,8 +206,12 @@ impl CompilationOptions {
     /// // Disable support for application/json (which is supported by jsonschema crate)
     /// options.without_content_media_type_support("application/json");
     /// ```
-    pub fn without_content_media_type_support(&mut self, media_type: &'static str) -> &mut Self {
-        self.content_media_type_checks.insert(media_type, None);
+    pub fn without_content_media_type_support(
+        &mut self,
+        media_type: impl Into<Cow<'static, str>>,
+    ) -> &mut Self {
+        self.content_media_type_checks
+            .insert(media_type.into(), None);
         self
     }
 
@@ -223,12 +301,12 @@ impl CompilationOptions {
     /// ```
     pub fn with_content_encoding(
         &mut self,
-        content_encoding: &'static str,
+        content_encoding: impl Into<Cow<'static, str>>,
         content_encoding_check: ContentEncodingCheckType,
         content_encoding_converter: ContentEncodingConverterType,
     ) -> &mut Self {
         self.content_encoding_checks_and_converters.insert(
-            content_encoding,
+            content_encoding.into(),
             Some((content_encoding_check, content_encoding_converter)),
         );
         self
@@ -245,10 +323,221 @@ impl CompilationOptions {
     /// ```
     pub fn without_content_encoding_support(
         &mut self,
-        content_encoding: &'static str,
+        content_encoding: impl Into<Cow<'static, str>>,
     ) -> &mut Self {
         self.content_encoding_checks_and_converters
-            .insert(content_encoding, None);
+            .insert(content_encoding.into(), None);
+        self
+    }
+
+    pub(crate) fn message_formatter(&self) -> Option<&dyn MessageFormatter> {
+        self.message_formatter.as_deref()
+    }
+
+    /// Use a custom formatter to build messages for validation errors.
+    /// It is useful to translate messages or to rewrite them for end users.
+    ///
+    /// Messages defined via the `errorMessage` keyword (see `CompilationOptions::with_error_messages`)
+    /// take precedence over the formatter.
+    ///
+    /// ```rust
+    /// # use jsonschema::{CompilationOptions, MessageFormatter, ValidationError};
+    /// # let mut options = CompilationOptions::default();
+    /// struct Terse;
+    ///
+    /// impl MessageFormatter for Terse {
+    ///     fn format_message(&self, error: &ValidationError<'_>) -> String {
+    ///         format!("Invalid value at '{}'", error.instance_path)
+    ///     }
+    /// }
+    /// options.with_message_formatter(Terse);
+    /// ```
+    pub fn with_message_formatter(
+        &mut self,
+        formatter: impl MessageFormatter + 'static,
+    ) -> &mut Self {
+        self.message_formatter = Some(Arc::new(formatter));
+        self
+    }
+
+    pub(crate) const fn max_errors(&self) -> Option<usize> {
+        self.max_errors
+    }
+
+    /// Stop validation after `max_errors` errors are found.
+    /// `JSONSchema::validate` will not yield more errors than this number, and the remaining
+    /// parts of the instance are not validated at all. Zero means no limit.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({"items": {"type": "string"}});
+    /// let compiled = JSONSchema::options()
+    ///     .with_max_errors(2)
+    ///     .compile(&schema)
+    ///     .expect("A valid schema");
+    /// let instance = json!([1, 2, 3, 4]);
+    /// let result = compiled.validate(&instance);
+    /// assert_eq!(result.expect_err("Invalid instance").count(), 2);
+    /// ```
+    pub fn with_max_errors(&mut self, max_errors: usize) -> &mut Self {
+        self.max_errors = if max_errors == 0 {
+            None
+        } else {
+            Some(max_errors)
+        };
+        self
+    }
+
+    pub(crate) const fn openapi_direction(&self) -> Option<schemas::OpenAPIDirection> {
+        self.openapi_direction
+    }
+
+    /// Define which side of an API call is validated in the `Draft::OpenAPI30` mode.
+    /// Request payloads can't contain `readOnly` values and response payloads can't contain `writeOnly` ones.
+    /// Such properties are also not required on the corresponding side.
+    /// Without a direction, `readOnly` and `writeOnly` keywords are ignored.
+    ///
+    /// ```rust
+    /// # use jsonschema::{Draft, JSONSchema, OpenAPIDirection};
+    /// # use serde_json::json;
+    /// let schema = json!({
+    ///     "type": "object",
+    ///     "properties": {"id": {"type": "integer", "readOnly": true}},
+    ///     "required": ["id"]
+    /// });
+    /// let compiled = JSONSchema::options()
+    ///     .with_draft(Draft::OpenAPI30)
+    ///     .with_openapi_direction(OpenAPIDirection::Request)
+    ///     .compile(&schema)
+    ///     .expect("A valid schema");
+    /// assert!(compiled.is_valid(&json!({})));
+    /// assert!(!compiled.is_valid(&json!({"id": 1})));
+    /// ```
+    pub fn with_openapi_direction(&mut self, direction: schemas::OpenAPIDirection) -> &mut Self {
+        self.openapi_direction = Some(direction);
+        self
+    }
+
+    pub(crate) const fn tracing(&self) -> bool {
+        self.tracing
+    }
+
+    /// Record keyword evaluations during `JSONSchema::trace`.
+    /// It is useful for debugging, but makes validation slower, even outside of `JSONSchema::trace`.
+    ///
+    /// ```rust
+    /// # use jsonschema::CompilationOptions;
+    /// # let mut options = CompilationOptions::default();
+    /// options.with_tracing();
+    /// ```
+    pub fn with_tracing(&mut self) -> &mut Self {
+        self.tracing = true;
+        self
+    }
+
+    pub(crate) fn profiler(&self) -> Option<&Profiler> {
+        self.profiler.as_deref()
+    }
+
+    /// Accumulate time and call counts of `is_valid` calls for every keyword, see `JSONSchema::profile`.
+    /// Measuring makes validation slower, therefore it is not intended for production use.
+    ///
+    /// ```rust
+    /// # use jsonschema::CompilationOptions;
+    /// # let mut options = CompilationOptions::default();
+    /// options.with_profiling();
+    /// ```
+    pub fn with_profiling(&mut self) -> &mut Self {
+        self.profiling = true;
+        self
+    }
+
+    /// Compile a normalized version of the schema, see `CompilationOptions::normalize`.
+    /// Validation is faster for schemas with redundant constraints, but schema paths in errors,
+    /// traces and profiles point to locations in the normalized schema. Referenced subschemas are not normalized.
+    ///
+    /// ```rust
+    /// # use jsonschema::CompilationOptions;
+    /// # let mut options = CompilationOptions::default();
+    /// options.with_normalization();
+    /// ```
+    pub fn with_normalization(&mut self) -> &mut Self {
+        self.normalization = true;
+        self
+    }
+
+    pub(crate) const fn strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Reject schemas with keywords and formats that are unknown in the used draft.
+    /// It catches typos like `maxLenght` that otherwise make the schema silently accept more instances.
+    ///
+    /// ```rust
+    /// # use jsonschema::CompilationOptions;
+    /// # use serde_json::json;
+    /// let mut options = CompilationOptions::default();
+    /// options.with_strict_mode();
+    /// assert!(options.compile(&json!({"maxLenght": 5})).is_err());
+    /// ```
+    pub fn with_strict_mode(&mut self) -> &mut Self {
+        self.strict = true;
+        self
+    }
+
+    pub(crate) const fn format_assertion(&self) -> bool {
+        !self.ignore_formats
+    }
+
+    /// Treat the `format` keyword as an annotation that doesn't affect validation.
+    ///
+    /// ```rust
+    /// # use jsonschema::CompilationOptions;
+    /// # use serde_json::json;
+    /// let mut options = CompilationOptions::default();
+    /// options.without_format_assertion();
+    /// let schema = json!({"format": "ipv4"});
+    /// let compiled = options.compile(&schema).unwrap();
+    /// assert!(compiled.is_valid(&json!("not an address")));
+    /// ```
+    pub fn without_format_assertion(&mut self) -> &mut Self {
+        self.ignore_formats = true;
+        self
+    }
+
+    pub(crate) const fn error_messages(&self) -> bool {
+        self.error_messages
+    }
+
+    /// Override messages of validation errors via the `errorMessage` keyword.
+    /// Its value is either a string, which applies to all keywords of the schema,
+    /// or an object mapping keyword names to messages. Schemas with other values are rejected.
+    /// Without this option `errorMessage` is an annotation.
+    ///
+    /// ```rust
+    /// # use jsonschema::CompilationOptions;
+    /// # use serde_json::json;
+    /// let schema = json!({"type": "string", "errorMessage": "Must be a string"});
+    /// let compiled = CompilationOptions::default()
+    ///     .with_error_messages()
+    ///     .compile(&schema)
+    ///     .expect("A valid schema");
+    /// let instance = json!(1);
+    /// let error = compiled.validate_first(&instance).expect_err("Invalid instance");
+    /// assert_eq!(error.to_string(), "Must be a string");
+    /// ```
+    pub fn with_error_messages(&mut self) -> &mut Self {
+        self.error_messages = true;
+        self
+    }
+
+    pub(crate) const fn cache_link(&self) -> Option<&CacheLink> {
+        self.cache.as_ref()
+    }
+
+    pub(crate) fn with_cache_link(&mut self, link: CacheLink) -> &mut Self {
+        self.cache = Some(link);
         self
     }
 }
@@ -262,6 +551,16 @@ impl fmt::Debug for CompilationOptions {
                 "content_encoding",
                 &self.content_encoding_checks_and_converters.keys(),
             )
+            .field("message_formatter", &self.message_formatter.is_some())
+            .field("max_errors", &self.max_errors)
+            .field("openapi_direction", &self.openapi_direction)
+            .field("cache", &self.cache.is_some())
+            .field("tracing", &self.tracing)
+            .field("profiling", &self.profiling)
+            .field("normalization", &self.normalization)
+            .field("strict", &self.strict)
+            .field("ignore_formats", &self.ignore_formats)
+            .field("error_messages", &self.error_messages)
             .finish()
     }
 }
@@ -269,7 +568,7 @@ impl fmt::Debug for CompilationOptions {
 #[cfg(test)]
 mod tests {
     use super::CompilationOptions;
-    use crate::schemas::Draft;
+    use crate::{schemas::Draft, JSONSchema};
     use serde_json::{json, Value};
     use test_case::test_case;
 
@@ -287,4 +586,54 @@ mod tests {
         let compiled = options.compile(schema).unwrap();
         compiled.context.config.draft()
     }
+
+    #[test_case(Draft::Draft7, &json!({"maxLenght": 5}) => "Unknown keyword 'maxLenght' at '/maxLenght'"; "unknown keyword")]
+    #[test_case(Draft::Draft7, &json!({"properties": {"a": {"tpye": "string"}}}) => "Unknown keyword 'tpye' at '/properties/a/tpye'"; "nested unknown keyword")]
+    #[test_case(Draft::Draft4, &json!({"const": 1}) => "Unknown keyword 'const' at '/const'"; "keyword from another draft")]
+    #[test_case(Draft::Draft7, &json!({"format": "custom"}) => "Unknown format 'custom' at '/format'"; "unknown format")]
+    #[test_case(Draft::Draft6, &json!({"format": "iri"}) => "Unknown format 'iri' at '/format'"; "format from another draft")]
+    #[test_case(Draft::Draft7, &json!({"definitions": {"a": {"unknown": 1}}}) => "Unknown keyword 'unknown' at '/definitions/a/unknown'"; "definition")]
+    #[test_case(Draft::Draft7, &json!({"definitions": {"a": {"definitions": {"b": {"format": "custom"}}}}}) => "Unknown format 'custom' at '/definitions/a/definitions/b/format'"; "nested definition")]
+    #[test_case(Draft::Draft7, &json!({"not": {"$ref": "#/default"}, "default": {"maxLenght": 1}}) => "Unknown keyword 'maxLenght' at '/not/$ref/maxLenght'"; "reference target")]
+    fn strict_mode_rejects(draft: Draft, schema: &Value) -> String {
+        // Without strict mode the schema compiles
+        let mut options = CompilationOptions::default();
+        options.with_draft(draft);
+        assert!(options.compile(schema).is_ok());
+        let mut options = CompilationOptions::default();
+        options.with_draft(draft).with_strict_mode();
+        options
+            .compile(schema)
+            .expect_err("The schema should be rejected")
+            .to_string()
+    }
+
+    #[test_case(Draft::Draft4, &json!({"id": "http://example.com", "title": "A", "description": "B", "default": 1}); "draft 4 annotations")]
+    #[test_case(Draft::Draft7, &json!({"$id": "http://example.com", "$comment": "A", "examples": [1], "readOnly": true}); "draft 7 annotations")]
+    #[test_case(Draft::Draft7, &json!({"definitions": {"a": {"type": "string"}}, "items": {"$ref": "#/definitions/a"}}); "definition")]
+    #[test_case(Draft::Draft7, &json!({"$ref": "#/definitions/a", "definitions": {"a": {"properties": {"b": {"$ref": "#"}}}}}); "recursive reference")]
+    #[test_case(Draft::Draft7, &json!({"type": "string", "errorMessage": "Wrong"}); "error message")]
+    #[test_case(Draft::Draft7, &json!({"format": "email"}); "known format")]
+    #[test_case(Draft::OpenAPI30, &json!({"type": "string", "format": "password", "nullable": true, "example": "a"}); "openapi")]
+    #[test_case(Draft::Draft4, &json!({"minimum": 0, "exclusiveMinimum": true}); "draft 4 exclusive flag")]
+    #[test_case(Draft::OpenAPI30, &json!({"minimum": 0, "exclusiveMinimum": true}); "openapi exclusive flag")]
+    #[test_case(Draft::Draft7, &json!({"contentMediaType": "application/json", "contentSchema": {"type": "object"}}); "content schema")]
+    fn strict_mode_accepts(draft: Draft, schema: &Value) {
+        let mut options = CompilationOptions::default();
+        options.with_draft(draft).with_strict_mode();
+        assert!(options.compile(schema).is_ok());
+    }
+
+    #[test]
+    fn format_assertion() {
+        let schema = json!({"format": "ipv4", "type": "string"});
+        let mut options = CompilationOptions::default();
+        options.without_format_assertion();
+        let compiled = options.compile(&schema).unwrap();
+        assert!(compiled.is_valid(&json!("foo")));
+        assert!(!compiled.is_valid(&json!(1)));
+        assert!(!JSONSchema::compile(&schema)
+            .unwrap()
+            .is_valid(&json!("foo")));
+    }
 }
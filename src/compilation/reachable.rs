@@ -0,0 +1,231 @@
+//! Subschemas that are not compiled together with the root schema.
+//!
+//! Validators are built only for subschemas that apply to instances directly: definitions are never compiled,
+//! and `$ref` targets are compiled on demand during validation. Strict mode and schema checks compile
+//! every subschema found here on its own, so problems in them are reported upfront.
+use super::options::CompilationOptions;
+use crate::{
+    normalizer::{COMBINATORS, SUBSCHEMA_KEYWORDS, SUBSCHEMA_MAP_KEYWORDS},
+    paths::{JSONPointer, SchemaPath},
+    resolver::Resolver,
+    schemas::{id_of, Draft},
+};
+use serde_json::Value;
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+};
+use url::Url;
+
+/// A subschema to compile on its own.
+pub(crate) struct Subschema<'a> {
+    pub(crate) schema: Cow<'a, Value>,
+    /// Resolution scope of the subschema.
+    pub(crate) scope: Url,
+    /// Location of the subschema. Targets of `$ref`s that are not a part of the compiled root schema or
+    /// definitions are located under the `$ref` that points to them, like their validation errors.
+    pub(crate) schema_path: SchemaPath,
+}
+
+/// All subschemas that can be reached from the root schema, but are not compiled with it.
+#[derive(Default)]
+pub(crate) struct Reachable<'a> {
+    pub(crate) subschemas: Vec<Subschema<'a>>,
+    /// Invalid identifiers and `$ref`s that can't be resolved, with their locations.
+    pub(crate) unresolved: Vec<(JSONPointer, String)>,
+}
+
+/// Find definitions and `$ref` targets reachable from `root`, following references transitively.
+///
+/// Documents that are already in the `SchemaCache` of `config` are compiled on their own and they are skipped.
+pub(crate) fn find<'a>(
+    draft: Draft,
+    root: &'a Value,
+    scope: &Url,
+    resolver: &Resolver,
+    config: &CompilationOptions,
+) -> Reachable<'a> {
+    let mut walker = Walker {
+        draft,
+        visited: HashSet::new(),
+        references: Vec::new(),
+        reachable: Reachable::default(),
+    };
+    let mut definitions = Vec::new();
+    walker.walk(root, scope, &SchemaPath::new(), &mut definitions);
+    walker
+        .reachable
+        .subschemas
+        .extend(
+            definitions
+                .into_iter()
+                .map(|(schema, scope, schema_path)| Subschema {
+                    schema: Cow::Borrowed(schema),
+                    scope,
+                    schema_path,
+                }),
+        );
+    // Errors of every reference, so each target is resolved only once
+    let mut resolved: HashMap<Url, Option<String>> = HashMap::new();
+    let mut idx = 0;
+    while let Some((reference, url, schema_path)) = walker.references.get(idx).cloned() {
+        idx += 1;
+        if let Some(error) = resolved.get(&url) {
+            if let Some(error) = error {
+                walker
+                    .reachable
+                    .unresolved
+                    .push((JSONPointer::from(&schema_path), error.clone()));
+            }
+            continue;
+        }
+        if !resolver.is_local(&url) {
+            if let Some(link) = config.cache_link() {
+                let mut resource = url.clone();
+                resource.set_fragment(None);
+                if link.lookup(resource.as_str()).is_some() {
+                    resolved.insert(url, None);
+                    continue;
+                }
+            }
+        }
+        let (scope, target) = match resolver.resolve_fragment(draft, &url, root) {
+            Ok(resolved) => resolved,
+            Err(error) => {
+                let error = format!("Can't resolve '{}': {}", reference, error);
+                walker
+                    .reachable
+                    .unresolved
+                    .push((JSONPointer::from(&schema_path), error.clone()));
+                resolved.insert(url, Some(error));
+                continue;
+            }
+        };
+        resolved.insert(url, None);
+        match target {
+            Cow::Borrowed(target) => {
+                // The target is already compiled as a part of the root schema or a definition
+                let address: *const Value = target;
+                if walker.visited.contains(&address) {
+                    continue;
+                }
+                let mut definitions = Vec::new();
+                walker.walk(target, &scope, &schema_path, &mut definitions);
+                walker.reachable.subschemas.push(Subschema {
+                    schema: Cow::Borrowed(target),
+                    scope,
+                    schema_path,
+                });
+                walker
+                    .reachable
+                    .subschemas
+                    .extend(definitions.into_iter().map(|(schema, scope, schema_path)| {
+                        Subschema {
+                            schema: Cow::Borrowed(schema),
+                            scope,
+                            schema_path,
+                        }
+                    }));
+            }
+            Cow::Owned(target) => {
+                let mut definitions = Vec::new();
+                walker.walk(&target, &scope, &schema_path, &mut definitions);
+                let definitions: Vec<_> = definitions
+                    .into_iter()
+                    .map(|(schema, scope, schema_path)| Subschema {
+                        schema: Cow::Owned(schema.clone()),
+                        scope,
+                        schema_path,
+                    })
+                    .collect();
+                walker.reachable.subschemas.push(Subschema {
+                    schema: Cow::Owned(target),
+                    scope,
+                    schema_path,
+                });
+                walker.reachable.subschemas.extend(definitions);
+            }
+        }
+    }
+    walker.reachable
+}
+
+struct Walker<'a> {
+    draft: Draft,
+    /// Subschemas that are compiled as a part of the root schema or a definition.
+    visited: HashSet<*const Value>,
+    /// References to follow once the current document is walked.
+    references: Vec<(String, Url, SchemaPath)>,
+    reachable: Reachable<'a>,
+}
+
+impl Walker<'_> {
+    /// Walk `schema` and its subschemas, collect references and definitions that are not compiled with them.
+    fn walk<'v>(
+        &mut self,
+        schema: &'v Value,
+        scope: &Url,
+        path: &SchemaPath,
+        definitions: &mut Vec<(&'v Value, Url, SchemaPath)>,
+    ) {
+        let object = match schema {
+            Value::Object(object) => object,
+            _ => return,
+        };
+        self.visited.insert(schema);
+        let scope = match id_of(self.draft, schema).map(|id| scope.join(id)) {
+            Some(Ok(scope)) => scope,
+            Some(Err(error)) => {
+                return self.reachable.unresolved.push((
+                    JSONPointer::from(path),
+                    format!("Invalid identifier: {}", error),
+                ))
+            }
+            None => scope.clone(),
+        };
+        if let Some(Value::String(reference)) = object.get("$ref") {
+            let path = path.push("$ref");
+            match scope.join(reference) {
+                Ok(url) => self.references.push((reference.clone(), url, path)),
+                Err(error) => self.reachable.unresolved.push((
+                    JSONPointer::from(&path),
+                    format!("Can't resolve '{}': {}", reference, error),
+                )),
+            }
+        }
+        for (keyword, value) in object {
+            let keyword_path = path.push(keyword.as_str());
+            let keyword = keyword.as_str();
+            match value {
+                Value::Object(entries) if keyword == "definitions" => {
+                    for (name, value) in entries {
+                        let path = keyword_path.push(name.as_str());
+                        definitions.push((value, scope.clone(), path.clone()));
+                        self.walk(value, &scope, &path, definitions);
+                    }
+                }
+                // Siblings of `$ref` are never applied
+                _ if object.contains_key("$ref") => {}
+                Value::Array(items) if COMBINATORS.contains(&keyword) || keyword == "items" => {
+                    for (idx, item) in items.iter().enumerate() {
+                        self.walk(item, &scope, &keyword_path.push(idx), definitions);
+                    }
+                }
+                Value::Object(entries) if SUBSCHEMA_MAP_KEYWORDS.contains(&keyword) => {
+                    for (name, value) in entries {
+                        self.walk(
+                            value,
+                            &scope,
+                            &keyword_path.push(name.as_str()),
+                            definitions,
+                        );
+                    }
+                }
+                _ if SUBSCHEMA_KEYWORDS.contains(&keyword) => {
+                    self.walk(value, &scope, &keyword_path, definitions)
+                }
+                _ => {}
+            }
+        }
+    }
+}
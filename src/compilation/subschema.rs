@@ -0,0 +1,177 @@
+//! Validation against subschemas of a compiled schema.
+use super::{compile_validators, context::CompilationContext, JSONSchema};
+use crate::{
+    error::{ErrorIterator, ValidationError},
+    keywords::Validators,
+};
+use serde_json::Value;
+use std::{borrow::Cow, sync::Arc};
+use url::Url;
+
+/// A subschema of a compiled `JSONSchema`, addressed by a JSON Pointer or by `$id`.
+///
+/// References inside the subschema are resolved against the whole document, therefore
+/// it behaves the same way as if it was referenced via `$ref` from the root schema.
+#[derive(Debug)]
+pub struct SubSchema<'a> {
+    root: &'a JSONSchema<'a>,
+    validators: Arc<Validators>,
+}
+
+impl<'a> SubSchema<'a> {
+    /// Run validation against `instance` and return an iterator over `ValidationError` in the error case.
+    ///
+    /// Options of the root schema, like a custom `MessageFormatter`, are applied as well.
+    #[inline]
+    pub fn validate(&'a self, instance: &'a Value) -> Result<(), ErrorIterator<'a>> {
+        let mut errors = self.root.iter_errors(&self.validators, instance).peekable();
+        if errors.peek().is_none() {
+            Ok(())
+        } else {
+            Err(Box::new(errors))
+        }
+    }
+
+    /// Run validation against `instance` but return a boolean result instead of an iterator.
+    #[must_use]
+    #[inline]
+    pub fn is_valid(&self, instance: &Value) -> bool {
+        self.validators
+            .iter()
+            .all(|validator| validator.is_valid(self.root, instance))
+    }
+}
+
+impl<'a> JSONSchema<'a> {
+    /// Get a subschema by a JSON Pointer (e.g. `#/definitions/Order` or `/definitions/Order`)
+    /// or by its `$id` (`id` in Draft 4). Relative identifiers are resolved against the root schema scope.
+    ///
+    /// Subschemas are compiled on the first access and cached afterwards.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({
+    ///     "definitions": {
+    ///         "Order": {
+    ///             "properties": {"items": {"type": "array", "items": {"$ref": "#/definitions/Item"}}}
+    ///         },
+    ///         "Item": {"type": "string"}
+    ///     }
+    /// });
+    /// let compiled = JSONSchema::compile(&schema).expect("A valid schema");
+    /// let order = compiled.subschema("#/definitions/Order").expect("An existing subschema");
+    /// assert!(order.is_valid(&json!({"items": ["a"]})));
+    /// assert!(!order.is_valid(&json!({"items": [1]})));
+    /// ```
+    pub fn subschema(&'a self, reference: &str) -> Result<SubSchema<'a>, ValidationError<'a>> {
+        let url = if reference.starts_with('/') {
+            self.context.build_url(&format!("#{}", reference))?
+        } else {
+            self.context.build_url(reference)?
+        };
+        let validators = self.subschema_validators(&url)?;
+        Ok(SubSchema {
+            root: self,
+            validators,
+        })
+    }
+
+    /// Compiled validators for a subschema with the given canonical URL.
+    pub(crate) fn subschema_validators(
+        &self,
+        url: &Url,
+    ) -> Result<Arc<Validators>, ValidationError<'_>> {
+        if let Some(validators) = self.subschemas.read().get(url.as_str()) {
+            return Ok(Arc::clone(validators));
+        }
+        let (scope, resolved) =
+            self.resolver
+                .resolve_fragment(self.context.config.draft(), url, &self.schema)?;
+        let context = CompilationContext::new(scope, Cow::Borrowed(&self.context.config));
+        let validators = Arc::new(compile_validators(&resolved, &context)?);
+        self.subschemas
+            .write()
+            .insert(url.to_string(), Arc::clone(&validators));
+        Ok(validators)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{compilation::JSONSchema, error::ValidationErrorKind};
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+    use test_case::test_case;
+
+    fn document() -> Value {
+        json!({
+            "$id": "http://example.com/root.json",
+            "definitions": {
+                "Order": {
+                    "type": "object",
+                    "properties": {"item": {"$ref": "#/definitions/Item"}},
+                    "required": ["item"]
+                },
+                "Item": {"type": "string"},
+                "Nested": {
+                    "$id": "nested/",
+                    "properties": {"price": {"$ref": "price.json"}},
+                    "definitions": {
+                        "Price": {"$id": "price.json", "type": "number"}
+                    }
+                },
+                "Anchored": {"$id": "#anchored", "type": "integer"}
+            }
+        })
+    }
+
+    #[test_case("#/definitions/Order", &json!({"item": "a"}), true)]
+    #[test_case("/definitions/Order", &json!({"item": "a"}), true; "pointer without hash")]
+    #[test_case("#/definitions/Order", &json!({"item": 1}), false)]
+    #[test_case("#/definitions/Order", &json!({}), false)]
+    #[test_case("#/definitions/Nested", &json!({"price": 1}), true)]
+    #[test_case("#/definitions/Nested", &json!({"price": "1"}), false)]
+    #[test_case("http://example.com/nested/price.json", &json!(1), true)]
+    #[test_case("http://example.com/nested/price.json", &json!("1"), false)]
+    #[test_case("#anchored", &json!(1), true)]
+    #[test_case("#anchored", &json!("1"), false)]
+    #[test_case("", &json!({"definitions": 1}), true)]
+    fn validate_subschema(reference: &str, instance: &Value, expected: bool) {
+        let schema = document();
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let subschema = compiled.subschema(reference).unwrap();
+        assert_eq!(subschema.is_valid(instance), expected);
+        assert_eq!(subschema.validate(instance).is_ok(), expected);
+    }
+
+    #[test]
+    fn instance_path() {
+        let schema = document();
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let subschema = compiled.subschema("#/definitions/Order").unwrap();
+        let instance = json!({"item": 1});
+        let error = subschema.validate(&instance).unwrap_err().next().unwrap();
+        assert_eq!(error.instance_path.to_string(), "/item");
+    }
+
+    #[test]
+    fn unknown_subschema() {
+        let schema = document();
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let error = compiled.subschema("#/definitions/Unknown").unwrap_err();
+        assert!(matches!(
+            *error.kind,
+            ValidationErrorKind::InvalidReference { .. }
+        ));
+    }
+
+    #[test]
+    fn cached() {
+        let schema = document();
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let first = compiled.subschema("#/definitions/Order").unwrap();
+        let second = compiled.subschema("/definitions/Order").unwrap();
+        assert!(Arc::ptr_eq(&first.validators, &second.validators));
+    }
+}
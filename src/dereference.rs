@@ -0,0 +1,342 @@
+//! Replacing references with the schemas they point to.
+use crate::{
+    bundler::DATA_KEYWORDS,
+    compilation::{options::CompilationOptions, DEFAULT_SCOPE},
+    error::ValidationError,
+    paths::JSONPointer,
+    resolver::Resolver,
+    schemas::{id_of, Draft},
+};
+use serde_json::{Map, Value};
+use std::{borrow::Cow, error, fmt, ptr};
+use url::Url;
+
+/// A reference that points to one of the schemas containing it, therefore it can't be inlined.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecursiveReference {
+    /// Location of the reference in the dereferenced schema.
+    pub location: JSONPointer,
+    /// The value of `$ref`.
+    pub reference: String,
+    /// Resolved references that lead from the root schema to this one, the last one is the recursive one.
+    pub chain: Vec<String>,
+}
+
+impl fmt::Display for RecursiveReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Recursive reference '{}' at '{}' ({})",
+            self.reference,
+            self.location,
+            self.chain.join(" -> ")
+        )
+    }
+}
+
+/// An error that can occur during dereferencing.
+#[derive(Debug)]
+pub enum DereferenceError {
+    /// A reference can't be resolved.
+    Resolving(ValidationError<'static>),
+    /// Some references are recursive. The schema contains all other references inlined,
+    /// while recursive ones are kept as is.
+    Recursive {
+        /// The partially dereferenced schema.
+        schema: Value,
+        /// References that can't be inlined.
+        references: Vec<RecursiveReference>,
+    },
+}
+
+impl error::Error for DereferenceError {}
+
+impl fmt::Display for DereferenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DereferenceError::Resolving(error) => error.fmt(f),
+            DereferenceError::Recursive { references, .. } => {
+                for (idx, reference) in references.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str("\n")?;
+                    }
+                    reference.fmt(f)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl From<ValidationError<'static>> for DereferenceError {
+    fn from(error: ValidationError<'static>) -> Self {
+        DereferenceError::Resolving(error)
+    }
+}
+
+struct Dereferencer<'a> {
+    draft: Draft,
+    root: &'a Value,
+    resolver: Resolver,
+    // Resolved references that lead to the currently dereferenced schema
+    chain: Vec<Url>,
+    // Addresses of schemas that contain the currently dereferenced one. Only compared, never dereferenced
+    ancestors: Vec<*const Value>,
+    recursive: Vec<RecursiveReference>,
+}
+
+impl<'a> Dereferencer<'a> {
+    fn dereference(
+        &mut self,
+        schema: &Value,
+        scope: &Url,
+        location: &JSONPointer,
+    ) -> Result<Value, ValidationError<'static>> {
+        Ok(match schema {
+            Value::Object(object) => {
+                let new_scope;
+                let scope = match id_of(self.draft, schema) {
+                    Some(id) => {
+                        new_scope = scope.join(id)?;
+                        &new_scope
+                    }
+                    None => scope,
+                };
+                if let Some(Value::String(reference)) = object.get("$ref") {
+                    // Other keywords next to `$ref` are ignored, as they are during validation
+                    return self.inline(schema, reference, scope, location);
+                }
+                self.ancestors.push(schema);
+                let mut dereferenced = Map::with_capacity(object.len());
+                for (keyword, value) in object {
+                    let value = if DATA_KEYWORDS.contains(&keyword.as_str()) {
+                        value.clone()
+                    } else {
+                        match self.dereference(value, scope, &location.join(keyword.as_str())) {
+                            Ok(value) => value,
+                            Err(error) => {
+                                self.ancestors.pop();
+                                return Err(error);
+                            }
+                        }
+                    };
+                    dereferenced.insert(keyword.clone(), value);
+                }
+                self.ancestors.pop();
+                Value::Object(dereferenced)
+            }
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, item)| self.dereference(item, scope, &location.join(idx)))
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => schema.clone(),
+        })
+    }
+
+    fn inline(
+        &mut self,
+        schema: &Value,
+        reference: &str,
+        scope: &Url,
+        location: &JSONPointer,
+    ) -> Result<Value, ValidationError<'static>> {
+        let url = scope.join(reference)?;
+        // Remote documents are fetched on every resolving, so they are compared by their URLs
+        let mut is_recursive = self.chain.contains(&url);
+        let mut resolved = None;
+        if !is_recursive {
+            let (scope, target) = self
+                .resolver
+                .resolve_fragment(self.draft, &url, self.root)
+                .map_err(ValidationError::into_owned)?;
+            is_recursive = matches!(
+                target,
+                Cow::Borrowed(target) if self.ancestors.iter().any(|ancestor| ptr::eq(*ancestor, target))
+            );
+            resolved = Some((scope, target));
+        }
+        match resolved {
+            Some((scope, target)) if !is_recursive => {
+                self.chain.push(url);
+                let dereferenced = self.dereference(&target, &scope, location);
+                self.chain.pop();
+                dereferenced
+            }
+            _ => {
+                let mut chain: Vec<_> = self.chain.iter().map(Url::to_string).collect();
+                chain.push(url.to_string());
+                self.recursive.push(RecursiveReference {
+                    location: location.clone(),
+                    reference: reference.to_string(),
+                    chain,
+                });
+                Ok(schema.clone())
+            }
+        }
+    }
+}
+
+impl CompilationOptions {
+    /// Replace every `$ref` with the schema it points to, so the result can be consumed by tools that
+    /// don't support references. Keywords next to `$ref` are dropped, since they are ignored during validation.
+    ///
+    /// References that point to one of the schemas containing them can't be inlined.
+    /// In this case `DereferenceError::Recursive` contains all of them together with the partially
+    /// dereferenced schema.
+    ///
+    /// ```rust
+    /// # use jsonschema::{DereferenceError, JSONSchema};
+    /// # use serde_json::json;
+    /// let schema = json!({
+    ///     "properties": {"id": {"$ref": "#/definitions/id"}},
+    ///     "definitions": {"id": {"type": "integer"}}
+    /// });
+    /// let dereferenced = JSONSchema::options()
+    ///     .dereference(&schema)
+    ///     .expect("No recursive references");
+    /// assert_eq!(dereferenced["properties"]["id"], json!({"type": "integer"}));
+    ///
+    /// let schema = json!({"properties": {"child": {"$ref": "#"}}});
+    /// match JSONSchema::options().dereference(&schema) {
+    ///     Err(DereferenceError::Recursive { references, .. }) => {
+    ///         assert_eq!(references[0].location.to_string(), "/properties/child")
+    ///     }
+    ///     _ => panic!("The reference is recursive"),
+    /// }
+    /// ```
+    pub fn dereference(&self, schema: &Value) -> Result<Value, DereferenceError> {
+        let draft = self.detect_draft(schema);
+        let scope = match id_of(draft, schema) {
+            Some(id) => Url::parse(id).map_err(ValidationError::from)?,
+            None => DEFAULT_SCOPE.clone(),
+        };
+        let mut dereferencer = Dereferencer {
+            draft,
+            root: schema,
+            resolver: Resolver::new(draft, &scope, schema).map_err(ValidationError::from)?,
+            chain: Vec::new(),
+            ancestors: Vec::new(),
+            recursive: Vec::new(),
+        };
+        let dereferenced = dereferencer.dereference(schema, &scope, &JSONPointer::default())?;
+        if dereferencer.recursive.is_empty() {
+            Ok(dereferenced)
+        } else {
+            Err(DereferenceError::Recursive {
+                schema: dereferenced,
+                references: dereferencer.recursive,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DereferenceError;
+    use crate::compilation::JSONSchema;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(
+        json!({"items": {"$ref": "#/definitions/a"}, "definitions": {"a": {"type": "string"}}}),
+        json!({"items": {"type": "string"}, "definitions": {"a": {"type": "string"}}});
+        "pointer"
+    )]
+    #[test_case(
+        json!({"items": {"$ref": "#/definitions/a", "minItems": 1}, "definitions": {"a": {"$ref": "#/definitions/b"}, "b": {"const": 1}}}),
+        json!({"items": {"const": 1}, "definitions": {"a": {"const": 1}, "b": {"const": 1}}});
+        "chain"
+    )]
+    #[test_case(
+        json!({"items": {"$ref": "#a"}, "definitions": {"a": {"$id": "#a", "type": "null"}}}),
+        json!({"items": {"$id": "#a", "type": "null"}, "definitions": {"a": {"$id": "#a", "type": "null"}}});
+        "identifier"
+    )]
+    #[test_case(
+        json!({"enum": [{"$ref": "#/definitions/a"}], "definitions": {"a": {}}}),
+        json!({"enum": [{"$ref": "#/definitions/a"}], "definitions": {"a": {}}});
+        "data keywords"
+    )]
+    fn dereferenced(schema: Value, expected: Value) {
+        let dereferenced = JSONSchema::options().dereference(&schema).unwrap();
+        assert_eq!(dereferenced, expected)
+    }
+
+    #[test]
+    fn scope_changes() {
+        let schema = json!({
+            "$id": "http://example.com/root.json",
+            "items": {"$ref": "#/definitions/a"},
+            "definitions": {
+                "a": {"$id": "nested/", "items": {"$ref": "b.json"}},
+                "b": {"$id": "nested/b.json", "type": "integer"}
+            }
+        });
+        let dereferenced = JSONSchema::options().dereference(&schema).unwrap();
+        // `b.json` is resolved against `nested/`, not against the root document
+        assert_eq!(
+            dereferenced,
+            json!({
+                "$id": "http://example.com/root.json",
+                "items": {"$id": "nested/", "items": {"$id": "nested/b.json", "type": "integer"}},
+                "definitions": {
+                    "a": {"$id": "nested/", "items": {"$id": "nested/b.json", "type": "integer"}},
+                    "b": {"$id": "nested/b.json", "type": "integer"}
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn recursive() {
+        let schema = json!({
+            "properties": {
+                "tree": {"$ref": "#/definitions/node"},
+                "leaf": {"$ref": "#/definitions/leaf"}
+            },
+            "definitions": {
+                "node": {"properties": {"children": {"items": {"$ref": "#/definitions/node"}}}},
+                "leaf": {"type": "string"}
+            }
+        });
+        match JSONSchema::options().dereference(&schema) {
+            Err(DereferenceError::Recursive { schema, references }) => {
+                assert_eq!(schema["properties"]["leaf"], json!({"type": "string"}));
+                assert_eq!(
+                    schema["properties"]["tree"],
+                    json!({"properties": {"children": {"items": {"$ref": "#/definitions/node"}}}})
+                );
+                let locations: Vec<_> = references
+                    .iter()
+                    .map(|reference| reference.location.to_string())
+                    .collect();
+                // The recursive definition is reported where it is used and where it is defined
+                assert_eq!(
+                    locations,
+                    vec![
+                        "/definitions/node/properties/children/items",
+                        "/properties/tree/properties/children/items"
+                    ]
+                );
+                assert_eq!(
+                    references[1].to_string(),
+                    "Recursive reference '#/definitions/node' at '/properties/tree/properties/children/items' \
+                     (json-schema:///#/definitions/node -> json-schema:///#/definitions/node)"
+                );
+            }
+            _ => panic!("The schema is recursive"),
+        }
+    }
+
+    #[test]
+    fn invalid_reference() {
+        let schema = json!({"$ref": "#/definitions/unknown"});
+        assert!(matches!(
+            JSONSchema::options().dereference(&schema),
+            Err(DereferenceError::Resolving(_))
+        ));
+    }
+}
@@ -1,4 +1,7 @@
-use crate::primitive_type::{PrimitiveType, PrimitiveTypesBitMap};
+use crate::{
+    paths::JSONPointer,
+    primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
+};
 use serde_json::{Map, Number, Value};
 use std::{
     borrow::Cow,
@@ -6,6 +9,7 @@ use std::{
     fmt::{Error, Formatter},
     io,
     iter::{empty, once},
+    mem,
     str::Utf8Error,
     string::FromUtf8Error,
 };
@@ -18,6 +22,20 @@ use std::{
 pub enum CompilationError {
     /// Invalid schema structure
     SchemaError,
+    /// A keyword that is unknown in the used draft. Reported only in strict mode.
+    UnknownKeyword {
+        /// The keyword name, e.g. `maxLenght`.
+        keyword: String,
+        /// Location of the keyword within the schema.
+        schema_path: JSONPointer,
+    },
+    /// A format that is unknown in the used draft. Reported only in strict mode.
+    UnknownFormat {
+        /// The value of the `format` keyword.
+        format: String,
+        /// Location of the `format` keyword within the schema.
+        schema_path: JSONPointer,
+    },
 }
 
 impl error::Error for CompilationError {}
@@ -25,7 +43,17 @@ impl error::Error for CompilationError {}
 impl fmt::Display for CompilationError {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(f, "Schema compilation error")
+        match self {
+            CompilationError::SchemaError => write!(f, "Schema compilation error"),
+            CompilationError::UnknownKeyword {
+                keyword,
+                schema_path,
+            } => write!(f, "Unknown keyword '{}' at '{}'", keyword, schema_path),
+            CompilationError::UnknownFormat {
+                format,
+                schema_path,
+            } => write!(f, "Unknown format '{}' at '{}'", format, schema_path),
+        }
     }
 }
 
@@ -45,8 +73,18 @@ impl From<url::ParseError> for CompilationError {
 /// An error that can occur during validation.
 #[derive(Debug)]
 pub struct ValidationError<'a> {
-    instance: Cow<'a, Value>,
-    kind: ValidationErrorKind,
+    /// Value of the property that failed validation.
+    pub instance: Cow<'a, Value>,
+    /// Type of validation error. It is boxed to keep `Result<_, ValidationError>` small.
+    pub kind: Box<ValidationErrorKind>,
+    /// Path to the value that failed validation.
+    pub instance_path: JSONPointer,
+    /// Path to the keyword that failed validation, e.g. `/properties/name/type`.
+    /// Keywords inside referenced schemas are reported through the `$ref` that points to them.
+    pub schema_path: JSONPointer,
+    /// A message that overrides the default one. Set by a custom `MessageFormatter`
+    /// or by the `errorMessage` keyword.
+    pub(crate) message: Option<String>,
 }
 
 /// An iterator over instances of `ValidationError` that represent validation error for the
@@ -82,11 +120,16 @@ pub(crate) fn error(instance: ValidationError) -> ErrorIterator {
 
 /// Kinds of errors that may happen during validation
 #[derive(Debug)]
-pub(crate) enum ValidationErrorKind {
+#[allow(missing_docs)]
+#[non_exhaustive]
+pub enum ValidationErrorKind {
     /// The input array contain more items than expected.
     AdditionalItems { limit: usize },
     /// The input value is not valid under any of the given schemas.
-    AnyOf,
+    /// `context` contains errors for each of the schemas, in the same order as the schemas.
+    AnyOf {
+        context: Vec<Vec<ValidationError<'static>>>,
+    },
     /// The input value doesn't match expected constant.
     Constant { expected_value: Value },
     /// The input array doesn't contain items conforming to the specified schema.
@@ -138,11 +181,17 @@ pub(crate) enum ValidationErrorKind {
     /// Negated schema failed validation.
     Not { schema: Value },
     /// The given schema is valid under more than one of the given schemas.
-    OneOfMultipleValid,
+    /// `matched` contains indexes of all valid schemas.
+    OneOfMultipleValid { matched: Vec<usize> },
     /// The given schema is not valid under any on the given schemas.
-    OneOfNotValid,
+    /// `context` contains errors for each of the schemas, in the same order as the schemas.
+    OneOfNotValid {
+        context: Vec<Vec<ValidationError<'static>>>,
+    },
     /// When the input doesn't match to a pattern.
     Pattern { pattern: String },
+    /// When a `readOnly` value is present in an `OpenAPI` request.
+    ReadOnly,
     /// When a required property is missing.
     Required { property: String },
     /// Any error that happens during network request via `reqwest` crate
@@ -156,331 +205,645 @@ pub(crate) enum ValidationErrorKind {
     UniqueItems,
     /// Reference contains unknown scheme.
     UnknownReferenceScheme { scheme: String },
+    /// When a `writeOnly` value is present in an `OpenAPI` response.
+    WriteOnly,
 
     /// Unexpected error. This usually represent a bug into the validation
     Unexpected { validator_representation: String },
 }
 
+/// Expected types in the `type` keyword.
 #[derive(Debug)]
-pub(crate) enum TypeKind {
+pub enum TypeKind {
+    /// A single type, e.g. `{"type": "string"}`.
     Single(PrimitiveType),
+    /// Multiple types, e.g. `{"type": ["string", "null"]}`.
     Multiple(PrimitiveTypesBitMap),
 }
 
 /// Shortcuts for creation of specific error kinds.
 impl<'a> ValidationError<'a> {
-    pub(crate) fn into_owned(self) -> ValidationError<'static> {
+    /// Convert the error into one that owns the instance.
+    #[must_use]
+    pub fn into_owned(self) -> ValidationError<'static> {
         ValidationError {
             instance: Cow::Owned(self.instance.into_owned()),
             kind: self.kind,
+            instance_path: self.instance_path,
+            message: self.message,
+            schema_path: self.schema_path,
+        }
+    }
+
+    /// Format the error with the given formatter, unless its message is overridden by
+    /// the `errorMessage` keyword.
+    #[must_use]
+    pub fn format_with(&self, formatter: &dyn MessageFormatter) -> String {
+        match &self.message {
+            Some(message) => message.clone(),
+            None => formatter.format_message(self),
+        }
+    }
+
+    /// Set the location of the failed keyword, unless a nested keyword has already set it.
+    pub(crate) fn with_schema_path(mut self, schema_path: &JSONPointer) -> Self {
+        if self.schema_path.is_empty() {
+            self.schema_path = schema_path.clone()
         }
+        self
     }
 
-    pub(crate) fn additional_items(instance: &'a Value, limit: usize) -> ValidationError<'a> {
+    pub(crate) fn with_message(mut self, message: &str) -> Self {
+        if self.message.is_none() {
+            self.message = Some(message.to_string())
+        }
+        self
+    }
+
+    /// Build messages with `formatter` for this error and for errors of `anyOf` / `oneOf` branches.
+    pub(crate) fn with_formatted_message(mut self, formatter: &dyn MessageFormatter) -> Self {
+        if let ValidationErrorKind::AnyOf { context }
+        | ValidationErrorKind::OneOfNotValid { context } = &mut *self.kind
+        {
+            for branch in context.iter_mut() {
+                *branch = mem::take(branch)
+                    .into_iter()
+                    .map(|error| error.with_formatted_message(formatter))
+                    .collect();
+            }
+        }
+        if self.message.is_none() {
+            self.message = Some(formatter.format_message(&self))
+        }
+        self
+    }
+
+    pub(crate) fn additional_items(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: usize,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::AdditionalItems { limit },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::AdditionalItems { limit }),
         }
     }
-    pub(crate) fn any_of(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn any_of(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        context: Vec<Vec<ValidationError<'static>>>,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::AnyOf,
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::AnyOf { context }),
         }
     }
     pub(crate) fn constant_array(
+        instance_path: JSONPointer,
         instance: &'a Value,
         expected_value: &[Value],
     ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Constant {
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Constant {
                 expected_value: Value::Array(expected_value.to_vec()),
-            },
+            }),
         }
     }
     pub(crate) fn constant_boolean(
+        instance_path: JSONPointer,
         instance: &'a Value,
         expected_value: bool,
     ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Constant {
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Constant {
                 expected_value: Value::Bool(expected_value),
-            },
+            }),
         }
     }
-    pub(crate) fn constant_null(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn constant_null(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Constant {
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Constant {
                 expected_value: Value::Null,
-            },
+            }),
         }
     }
     pub(crate) fn constant_number(
+        instance_path: JSONPointer,
         instance: &'a Value,
         expected_value: &Number,
     ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Constant {
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Constant {
                 expected_value: Value::Number(expected_value.clone()),
-            },
+            }),
         }
     }
     pub(crate) fn constant_object(
+        instance_path: JSONPointer,
         instance: &'a Value,
         expected_value: &Map<String, Value>,
     ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Constant {
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Constant {
                 expected_value: Value::Object(expected_value.clone()),
-            },
+            }),
         }
     }
     pub(crate) fn constant_string(
+        instance_path: JSONPointer,
         instance: &'a Value,
         expected_value: &str,
     ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Constant {
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Constant {
                 expected_value: Value::String(expected_value.to_string()),
-            },
+            }),
         }
     }
-    pub(crate) fn contains(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn contains(instance_path: JSONPointer, instance: &'a Value) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Contains,
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Contains),
         }
     }
-    pub(crate) fn content_encoding(instance: &'a Value, encoding: &str) -> ValidationError<'a> {
+    pub(crate) fn content_encoding(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        encoding: &str,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::ContentEncoding {
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::ContentEncoding {
                 content_encoding: encoding.to_string(),
-            },
+            }),
         }
     }
-    pub(crate) fn content_media_type(instance: &'a Value, media_type: &str) -> ValidationError<'a> {
+    pub(crate) fn content_media_type(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        media_type: &str,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::ContentMediaType {
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::ContentMediaType {
                 content_media_type: media_type.to_string(),
-            },
+            }),
         }
     }
-    pub(crate) fn enumeration(instance: &'a Value, options: &Value) -> ValidationError<'a> {
+    pub(crate) fn enumeration(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        options: &Value,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Enum {
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Enum {
                 options: options.clone(),
-            },
+            }),
         }
     }
-    pub(crate) fn exclusive_maximum(instance: &'a Value, limit: f64) -> ValidationError<'a> {
+    pub(crate) fn exclusive_maximum(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: f64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::ExclusiveMaximum { limit },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::ExclusiveMaximum { limit }),
         }
     }
-    pub(crate) fn exclusive_minimum(instance: &'a Value, limit: f64) -> ValidationError<'a> {
+    pub(crate) fn exclusive_minimum(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: f64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::ExclusiveMinimum { limit },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::ExclusiveMinimum { limit }),
         }
     }
-    pub(crate) fn false_schema(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn false_schema(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::FalseSchema,
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::FalseSchema),
         }
     }
     pub(crate) fn file_not_found(error: io::Error) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
             instance: Cow::Owned(Value::Null),
-            kind: ValidationErrorKind::FileNotFound { error },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::FileNotFound { error }),
         }
     }
-    pub(crate) fn format(instance: &'a Value, format: &'static str) -> ValidationError<'a> {
+    pub(crate) fn format(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        format: &'static str,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Format { format },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Format { format }),
         }
     }
     pub(crate) fn from_utf8(error: FromUtf8Error) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
             instance: Cow::Owned(Value::Null),
-            kind: ValidationErrorKind::FromUtf8 { error },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::FromUtf8 { error }),
         }
     }
     pub(crate) fn json_parse(error: serde_json::Error) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
             instance: Cow::Owned(Value::Null),
-            kind: ValidationErrorKind::JSONParse { error },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::JSONParse { error }),
         }
     }
     pub(crate) fn invalid_reference(reference: String) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
             instance: Cow::Owned(Value::Null),
-            kind: ValidationErrorKind::InvalidReference { reference },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::InvalidReference { reference }),
         }
     }
     pub(crate) fn invalid_url(error: url::ParseError) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
             instance: Cow::Owned(Value::Null),
-            kind: ValidationErrorKind::InvalidURL { error },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::InvalidURL { error }),
         }
     }
-    pub(crate) fn max_items(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn max_items(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::MaxItems { limit },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::MaxItems { limit }),
         }
     }
-    pub(crate) fn maximum(instance: &'a Value, limit: f64) -> ValidationError<'a> {
+    pub(crate) fn maximum(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: f64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Maximum { limit },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Maximum { limit }),
         }
     }
-    pub(crate) fn max_length(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn max_length(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::MaxLength { limit },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::MaxLength { limit }),
         }
     }
-    pub(crate) fn max_properties(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn max_properties(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::MaxProperties { limit },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::MaxProperties { limit }),
         }
     }
-    pub(crate) fn min_items(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn min_items(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::MinItems { limit },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::MinItems { limit }),
         }
     }
-    pub(crate) fn minimum(instance: &'a Value, limit: f64) -> ValidationError<'a> {
+    pub(crate) fn minimum(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: f64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Minimum { limit },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Minimum { limit }),
         }
     }
-    pub(crate) fn min_length(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn min_length(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::MinLength { limit },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::MinLength { limit }),
         }
     }
-    pub(crate) fn min_properties(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn min_properties(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::MinProperties { limit },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::MinProperties { limit }),
         }
     }
-    pub(crate) fn multiple_of(instance: &'a Value, multiple_of: f64) -> ValidationError<'a> {
+    pub(crate) fn multiple_of(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        multiple_of: f64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::MultipleOf { multiple_of },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::MultipleOf { multiple_of }),
         }
     }
-    pub(crate) fn not(instance: &'a Value, schema: Value) -> ValidationError<'a> {
+    pub(crate) fn not(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        schema: Value,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Not { schema },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Not { schema }),
         }
     }
-    pub(crate) fn one_of_multiple_valid(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn one_of_multiple_valid(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        matched: Vec<usize>,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::OneOfMultipleValid,
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::OneOfMultipleValid { matched }),
         }
     }
-    pub(crate) fn one_of_not_valid(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn one_of_not_valid(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        context: Vec<Vec<ValidationError<'static>>>,
+    ) -> ValidationError<'a> {
+        ValidationError {
+            instance_path,
+            instance: Cow::Borrowed(instance),
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::OneOfNotValid { context }),
+        }
+    }
+    pub(crate) fn read_only(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::OneOfNotValid,
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::ReadOnly),
         }
     }
-    pub(crate) fn pattern(instance: &'a Value, pattern: String) -> ValidationError<'a> {
+    pub(crate) fn write_only(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+    ) -> ValidationError<'a> {
+        ValidationError {
+            instance_path,
+            instance: Cow::Borrowed(instance),
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::WriteOnly),
+        }
+    }
+    pub(crate) fn pattern(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        pattern: String,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Pattern { pattern },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Pattern { pattern }),
         }
     }
-    pub(crate) fn required(instance: &'a Value, property: String) -> ValidationError<'a> {
+    pub(crate) fn required(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        property: String,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Required { property },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Required { property }),
         }
     }
     #[cfg(any(feature = "reqwest", test))]
     pub(crate) fn reqwest(error: reqwest::Error) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
             instance: Cow::Owned(Value::Null),
-            kind: ValidationErrorKind::Reqwest { error },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Reqwest { error }),
         }
     }
     pub(crate) fn schema() -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
             instance: Cow::Owned(Value::Null),
-            kind: ValidationErrorKind::Schema,
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Schema),
         }
     }
     pub(crate) fn single_type_error(
+        instance_path: JSONPointer,
         instance: &'a Value,
         type_name: PrimitiveType,
     ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Type {
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Type {
                 kind: TypeKind::Single(type_name),
-            },
+            }),
         }
     }
     pub(crate) fn multiple_type_error(
+        instance_path: JSONPointer,
         instance: &'a Value,
         types: PrimitiveTypesBitMap,
     ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Type {
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Type {
                 kind: TypeKind::Multiple(types),
-            },
+            }),
         }
     }
-    pub(crate) fn unique_items(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn unique_items(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::UniqueItems,
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::UniqueItems),
         }
     }
     pub(crate) fn unknown_reference_scheme(scheme: String) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
             instance: Cow::Owned(Value::Null),
-            kind: ValidationErrorKind::UnknownReferenceScheme { scheme },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::UnknownReferenceScheme { scheme }),
         }
     }
     /// Unexpected `ValidationError`
     ///
     /// This validation error is the only `ValidationError` that can be created by external crates.
-    pub fn unexpected(instance: &'a Value, validator_representation: &str) -> ValidationError<'a> {
+    pub fn unexpected(
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        validator_representation: &str,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Unexpected {
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Unexpected {
                 validator_representation: validator_representation.to_string(),
-            },
+            }),
         }
     }
     pub(crate) fn utf8(error: Utf8Error) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
             instance: Cow::Owned(Value::Null),
-            kind: ValidationErrorKind::Utf8 { error },
+            message: None,
+            schema_path: JSONPointer::default(),
+            kind: Box::new(ValidationErrorKind::Utf8 { error }),
         }
     }
 }
@@ -530,12 +893,87 @@ impl From<reqwest::Error> for ValidationError<'_> {
     }
 }
 
+/// Builds textual representation of validation errors.
+///
+/// The default implementation produces English messages. Provide a custom implementation
+/// via `CompilationOptions::with_message_formatter` to translate or rewrite messages for end users.
+///
+/// ```rust
+/// use jsonschema::{
+///     DefaultMessageFormatter, JSONSchema, MessageFormatter, ValidationError, ValidationErrorKind,
+/// };
+/// use serde_json::json;
+///
+/// struct German;
+///
+/// impl MessageFormatter for German {
+///     fn format_message(&self, error: &ValidationError<'_>) -> String {
+///         match &*error.kind {
+///             ValidationErrorKind::Required { property } => {
+///                 format!("'{}' ist eine Pflichteigenschaft", property)
+///             }
+///             // Fallback to English messages for other errors
+///             _ => DefaultMessageFormatter.format_message(error),
+///         }
+///     }
+/// }
+///
+/// let schema = json!({"required": ["name"]});
+/// let compiled = JSONSchema::options()
+///     .with_message_formatter(German)
+///     .compile(&schema)
+///     .expect("A valid schema");
+/// let instance = json!({});
+/// let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+/// assert_eq!(errors[0].to_string(), "'name' ist eine Pflichteigenschaft");
+/// ```
+pub trait MessageFormatter: Send + Sync {
+    /// Build a message for the given error.
+    ///
+    /// Keyword-specific parameters are available in `error.kind` and the location of the failed value
+    /// is in `error.instance_path`.
+    fn format_message(&self, error: &ValidationError<'_>) -> String;
+}
+
+/// Formatter that produces the default English messages.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultMessageFormatter;
+
+impl MessageFormatter for DefaultMessageFormatter {
+    #[inline]
+    fn format_message(&self, error: &ValidationError<'_>) -> String {
+        DefaultMessage(error).to_string()
+    }
+}
+
+struct DefaultMessage<'e, 'a>(&'e ValidationError<'a>);
+
+impl fmt::Display for DefaultMessage<'_, '_> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.fmt_default(f)
+    }
+}
+
 /// Textual representation of various validation errors.
+///
+/// If the message was overridden by a custom `MessageFormatter` or by the `errorMessage` keyword,
+/// then it is used instead of the default one.
 impl fmt::Display for ValidationError<'_> {
-    #[allow(clippy::too_many_lines)] // The function is long but it does formatting only
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match &self.kind {
+        match &self.message {
+            Some(message) => f.write_str(message),
+            None => self.fmt_default(f),
+        }
+    }
+}
+
+impl ValidationError<'_> {
+    #[allow(clippy::too_many_lines)] // The function is long but it does formatting only
+    #[inline]
+    fn fmt_default(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &*self.kind {
             ValidationErrorKind::Schema => write!(f, "Schema error"),
             ValidationErrorKind::JSONParse { error } => write!(f, "{}", error),
             #[cfg(any(feature = "reqwest", test))]
@@ -576,7 +1014,7 @@ impl fmt::Display for ValidationError<'_> {
                     verb
                 )
             }
-            ValidationErrorKind::AnyOf | ValidationErrorKind::OneOfNotValid => write!(
+            ValidationErrorKind::AnyOf { .. } | ValidationErrorKind::OneOfNotValid { .. } => write!(
                 f,
                 "'{}' is not valid under any of the given schemas",
                 self.instance
@@ -669,7 +1107,7 @@ impl fmt::Display for ValidationError<'_> {
             ValidationErrorKind::Not { schema } => {
                 write!(f, "{} is not allowed for {}", schema, self.instance)
             }
-            ValidationErrorKind::OneOfMultipleValid => write!(
+            ValidationErrorKind::OneOfMultipleValid { .. } => write!(
                 f,
                 "'{}' is valid under more than one of the given schemas",
                 self.instance
@@ -680,6 +1118,14 @@ impl fmt::Display for ValidationError<'_> {
             ValidationErrorKind::Required { property } => {
                 write!(f, "'{}' is a required property", property)
             }
+            ValidationErrorKind::ReadOnly => {
+                write!(f, "'{}' is read-only and can't be sent in a request", self.instance)
+            }
+            ValidationErrorKind::WriteOnly => write!(
+                f,
+                "'{}' is write-only and can't be returned in a response",
+                self.instance
+            ),
             ValidationErrorKind::MultipleOf { multiple_of } => {
                 write!(f, "{} is not a multiple of {}", self.instance, multiple_of)
             }
@@ -720,7 +1166,11 @@ mod tests {
     #[test]
     fn single_type_error() {
         let instance = json!(42);
-        let err = ValidationError::single_type_error(&instance, PrimitiveType::String);
+        let err = ValidationError::single_type_error(
+            JSONPointer::default(),
+            &instance,
+            PrimitiveType::String,
+        );
         assert_eq!(err.to_string(), "'42' is not of type 'string'")
     }
 
@@ -728,6 +1178,7 @@ mod tests {
     fn multiple_types_error() {
         let instance = json!(42);
         let err = ValidationError::multiple_type_error(
+            JSONPointer::default(),
             &instance,
             vec![PrimitiveType::String, PrimitiveType::Number].into(),
         );
@@ -0,0 +1,774 @@
+//! Generating invalid instances by mutating valid ones.
+//!
+//! The instance is walked together with the flattened schema and every keyword that applies to the current
+//! location proposes a few candidate replacements for the value there, e.g. a string that is one character
+//! longer than `maxLength`. A candidate is accepted only if the mutated instance fails validation with
+//! exactly one error of the expected kind, so each result breaks exactly one constraint.
+use super::{number, Builder, Kind, Part, ALL_KINDS, MAX_SIZE};
+use crate::{
+    compilation::JSONSchema,
+    error::{ValidationError, ValidationErrorKind},
+    paths::{JSONPointer, PathChunk},
+    schemas::Draft,
+};
+use serde_json::{Map, Value};
+use std::convert::TryFrom;
+
+/// An instance that breaks exactly one constraint of a schema.
+#[derive(Debug)]
+pub struct InvalidInstance {
+    /// The invalid instance.
+    pub instance: Value,
+    /// Type of the error the instance triggers.
+    pub kind: ValidationErrorKind,
+    /// Path to the invalid part of the instance.
+    pub instance_path: JSONPointer,
+}
+
+type Expected = fn(&ValidationErrorKind) -> bool;
+
+pub(crate) struct Mutator<'r, 'g, 'i> {
+    builder: Builder<'r, 'g>,
+    /// The same schema compiled without `CompilationOptions::with_max_errors`, if it is set.
+    /// Otherwise a candidate that triggers more errors than the limit could be taken as a single-error one.
+    unlimited: Option<JSONSchema<'r>>,
+    /// The valid instance that is mutated.
+    instance: &'i Value,
+    found: Vec<InvalidInstance>,
+}
+
+impl<'r, 'g, 'i> Mutator<'r, 'g, 'i> {
+    pub(crate) fn new(builder: Builder<'r, 'g>, instance: &'i Value) -> Self {
+        let schema: &'r JSONSchema<'r> = builder.schema;
+        let unlimited = if schema.context.config.max_errors().is_some() {
+            let mut options = schema.context.config.clone().into_owned();
+            options.with_max_errors(0).compile(&schema.schema).ok()
+        } else {
+            None
+        };
+        Mutator {
+            builder,
+            unlimited,
+            instance,
+            found: Vec::new(),
+        }
+    }
+
+    pub(crate) fn mutate(mut self) -> Vec<InvalidInstance> {
+        let root = self.builder.root_part();
+        let instance = self.instance;
+        self.walk(instance, &JSONPointer::default(), vec![root]);
+        self.found
+    }
+
+    fn walk(&mut self, value: &Value, location: &JSONPointer, parts: Vec<Part<'r>>) {
+        let mut flat = Vec::new();
+        for (schema, scope) in parts {
+            if self.builder.flatten(schema, scope, &mut flat).is_none() {
+                return;
+            }
+        }
+        let parts = flat;
+        if let Some(allowed) = self.builder.types(&parts) {
+            let candidates = ALL_KINDS
+                .iter()
+                .filter(|kind| !allowed.contains(kind))
+                .map(|kind| sample(*kind))
+                .collect();
+            self.check(location, candidates, |kind| {
+                matches!(kind, ValidationErrorKind::Type { .. })
+            });
+        }
+        for part in &parts {
+            self.keywords(value, location, part, &parts);
+        }
+        match value {
+            Value::Object(object) => {
+                for (name, item) in object {
+                    let property_parts = self.builder.property_parts(&parts, name);
+                    self.walk(item, &location.join(name.as_str()), property_parts);
+                }
+            }
+            Value::Array(items) => {
+                for (idx, item) in items.iter().enumerate() {
+                    if let Some(item_parts) = self.builder.item_parts(&parts, idx as u64) {
+                        self.walk(item, &location.join(idx), item_parts);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Mutations proposed by keywords of a single subschema.
+    fn keywords(
+        &mut self,
+        value: &Value,
+        location: &JSONPointer,
+        (schema, scope): &Part<'r>,
+        parts: &[Part<'r>],
+    ) {
+        let draft = self.builder.draft;
+        let legacy = matches!(draft, Draft::Draft4 | Draft::OpenAPI30);
+        if let Some(expected) = schema.get("const") {
+            let candidates = variations(expected)
+                .into_iter()
+                .filter(|candidate| candidate != expected)
+                .collect();
+            self.check(location, candidates, |kind| {
+                matches!(kind, ValidationErrorKind::Constant { .. })
+            });
+        }
+        if let Some(Value::Array(options)) = schema.get("enum") {
+            let candidates = options
+                .iter()
+                .flat_map(variations)
+                .filter(|candidate| !options.contains(candidate))
+                .collect();
+            self.check(location, candidates, |kind| {
+                matches!(kind, ValidationErrorKind::Enum { .. })
+            });
+        }
+        if let Some(current) = value.as_f64() {
+            let multiple_of = schema.get("multipleOf").and_then(Value::as_f64);
+            if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+                let exclusive =
+                    legacy && matches!(schema.get("exclusiveMinimum"), Some(Value::Bool(true)));
+                self.check(location, below(minimum, exclusive, multiple_of), |kind| {
+                    matches!(
+                        kind,
+                        ValidationErrorKind::Minimum { .. }
+                            | ValidationErrorKind::ExclusiveMinimum { .. }
+                    )
+                });
+            }
+            if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+                let exclusive =
+                    legacy && matches!(schema.get("exclusiveMaximum"), Some(Value::Bool(true)));
+                self.check(location, above(maximum, exclusive, multiple_of), |kind| {
+                    matches!(
+                        kind,
+                        ValidationErrorKind::Maximum { .. }
+                            | ValidationErrorKind::ExclusiveMaximum { .. }
+                    )
+                });
+            }
+            if !legacy {
+                if let Some(minimum) = schema.get("exclusiveMinimum").and_then(Value::as_f64) {
+                    self.check(location, below(minimum, true, multiple_of), |kind| {
+                        matches!(kind, ValidationErrorKind::ExclusiveMinimum { .. })
+                    });
+                }
+                if let Some(maximum) = schema.get("exclusiveMaximum").and_then(Value::as_f64) {
+                    self.check(location, above(maximum, true, multiple_of), |kind| {
+                        matches!(kind, ValidationErrorKind::ExclusiveMaximum { .. })
+                    });
+                }
+            }
+            if let Some(multiple_of) = multiple_of {
+                let candidates = [current + 1.0, current + multiple_of / 2.0]
+                    .iter()
+                    .filter_map(|candidate| number(*candidate))
+                    .collect();
+                self.check(location, candidates, |kind| {
+                    matches!(kind, ValidationErrorKind::MultipleOf { .. })
+                });
+            }
+        }
+        if let Value::String(current) = value {
+            let length = current.chars().count() as u64;
+            if let Some(min_length) = schema.get("minLength").and_then(Value::as_u64) {
+                if min_length > 0 && min_length <= length {
+                    let shorter = usize::try_from(min_length - 1).unwrap_or(usize::MAX);
+                    let candidates = vec![
+                        Value::String(current.chars().take(shorter).collect()),
+                        Value::String("a".repeat(shorter)),
+                    ];
+                    self.check(location, candidates, |kind| {
+                        matches!(kind, ValidationErrorKind::MinLength { .. })
+                    });
+                }
+            }
+            if let Some(max_length) = schema.get("maxLength").and_then(Value::as_u64) {
+                if max_length >= length && max_length < MAX_SIZE {
+                    let extra = usize::try_from(max_length + 1 - length).unwrap_or(usize::MAX);
+                    let last = current.chars().last().unwrap_or('a');
+                    let longer = usize::try_from(max_length + 1).unwrap_or(usize::MAX);
+                    let mut candidates = vec![Value::String(format!(
+                        "{}{}",
+                        current,
+                        last.to_string().repeat(extra)
+                    ))];
+                    candidates.push(Value::String("a".repeat(longer)));
+                    self.check(location, candidates, |kind| {
+                        matches!(kind, ValidationErrorKind::MaxLength { .. })
+                    });
+                }
+            }
+            if schema.get("pattern").is_some() {
+                let candidates = [
+                    String::new(),
+                    "!".to_string(),
+                    "0".to_string(),
+                    "a".to_string(),
+                    "invalid value".to_string(),
+                    format!("{}!", current),
+                    format!("!{}", current),
+                ]
+                .iter()
+                .cloned()
+                .map(Value::String)
+                .collect();
+                self.check(location, candidates, |kind| {
+                    matches!(kind, ValidationErrorKind::Pattern { .. })
+                });
+            }
+            if schema.get("format").is_some() {
+                let candidates = ["invalid", "", "!", "0"]
+                    .iter()
+                    .map(|candidate| Value::String((*candidate).to_string()))
+                    .collect();
+                self.check(location, candidates, |kind| {
+                    matches!(kind, ValidationErrorKind::Format { .. })
+                });
+            }
+            if draft != Draft::Draft4 {
+                if schema.get("contentEncoding").is_some() {
+                    self.check(location, vec![Value::String("!".to_string())], |kind| {
+                        matches!(kind, ValidationErrorKind::ContentEncoding { .. })
+                    });
+                }
+                if schema.get("contentMediaType").is_some() {
+                    let candidates = vec![
+                        Value::String("{".to_string()),
+                        // `{` encoded with base64
+                        Value::String("ew==".to_string()),
+                    ];
+                    self.check(location, candidates, |kind| {
+                        matches!(kind, ValidationErrorKind::ContentMediaType { .. })
+                    });
+                }
+            }
+        }
+        if let Value::Object(object) = value {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for name in required.iter().filter_map(Value::as_str) {
+                    self.check(location, vec![without(object, name)], |kind| {
+                        matches!(kind, ValidationErrorKind::Required { .. })
+                    });
+                }
+            }
+            if draft != Draft::OpenAPI30 {
+                if let Some(Value::Object(dependencies)) = schema.get("dependencies") {
+                    for (name, dependency) in dependencies {
+                        if let (true, Value::Array(dependency)) =
+                            (object.contains_key(name), dependency)
+                        {
+                            for required in dependency.iter().filter_map(Value::as_str) {
+                                self.check(location, vec![without(object, required)], |kind| {
+                                    matches!(kind, ValidationErrorKind::Required { .. })
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            if matches!(schema.get("additionalProperties"), Some(Value::Bool(false))) {
+                let candidates = ["additionalProperty", "unexpected"]
+                    .iter()
+                    .map(|name| with(object, name))
+                    .collect();
+                self.check(location, candidates, |kind| {
+                    matches!(kind, ValidationErrorKind::FalseSchema)
+                });
+            }
+            if let Some(min_properties) = schema.get("minProperties").and_then(Value::as_u64) {
+                if min_properties > 0 && min_properties <= object.len() as u64 {
+                    let mut candidate = object.clone();
+                    while candidate.len() as u64 >= min_properties {
+                        let last = candidate.keys().next_back().cloned().unwrap_or_default();
+                        candidate.remove(&last);
+                    }
+                    self.check(location, vec![Value::Object(candidate)], |kind| {
+                        matches!(kind, ValidationErrorKind::MinProperties { .. })
+                    });
+                }
+            }
+            if let Some(max_properties) = schema
+                .get("maxProperties")
+                .and_then(Value::as_u64)
+                .filter(|max_properties| *max_properties < MAX_SIZE)
+            {
+                let mut candidate = object.clone();
+                let mut counter = 0;
+                while candidate.len() as u64 <= max_properties {
+                    candidate.insert(format!("property{}", counter), Value::Null);
+                    counter += 1;
+                }
+                self.check(location, vec![Value::Object(candidate)], |kind| {
+                    matches!(kind, ValidationErrorKind::MaxProperties { .. })
+                });
+            }
+        }
+        if let Value::Array(items) = value {
+            if let Some(min_items) = schema.get("minItems").and_then(Value::as_u64) {
+                if min_items > 0 && min_items <= items.len() as u64 {
+                    let shorter = usize::try_from(min_items - 1).unwrap_or(usize::MAX);
+                    let candidate = items.iter().take(shorter).cloned().collect();
+                    self.check(location, vec![Value::Array(candidate)], |kind| {
+                        matches!(kind, ValidationErrorKind::MinItems { .. })
+                    });
+                }
+            }
+            if let Some(max_items) = schema
+                .get("maxItems")
+                .and_then(Value::as_u64)
+                .filter(|max_items| *max_items < MAX_SIZE)
+            {
+                let mut candidates = Vec::new();
+                if let Some(last) = items.last() {
+                    let mut candidate = items.clone();
+                    while candidate.len() as u64 <= max_items {
+                        candidate.push(last.clone());
+                    }
+                    candidates.push(Value::Array(candidate));
+                }
+                if let Some(candidate) = self.extend(items, max_items + 1, parts) {
+                    candidates.push(candidate);
+                }
+                self.check(location, candidates, |kind| {
+                    matches!(kind, ValidationErrorKind::MaxItems { .. })
+                });
+            }
+            if matches!(schema.get("uniqueItems"), Some(Value::Bool(true))) {
+                let mut candidates = Vec::new();
+                if let Some(first) = items.first() {
+                    let mut candidate = items.clone();
+                    candidate.push(first.clone());
+                    candidates.push(Value::Array(candidate));
+                    if items.len() > 1 {
+                        let mut candidate = items.clone();
+                        candidate[1] = first.clone();
+                        candidates.push(Value::Array(candidate));
+                    }
+                }
+                self.check(location, candidates, |kind| {
+                    matches!(kind, ValidationErrorKind::UniqueItems)
+                });
+            }
+            if matches!(draft, Draft::Draft6 | Draft::Draft7) && schema.get("contains").is_some() {
+                self.check(location, vec![Value::Array(Vec::new())], |kind| {
+                    matches!(kind, ValidationErrorKind::Contains)
+                });
+            }
+            if draft != Draft::OpenAPI30 {
+                if let (Some(Value::Array(positional)), Some(Value::Bool(false))) =
+                    (schema.get("items"), schema.get("additionalItems"))
+                {
+                    let mut candidates = Vec::new();
+                    if let Some(Value::Array(mut candidate)) =
+                        self.extend(items, positional.len() as u64, parts)
+                    {
+                        candidate.push(Value::Null);
+                        candidates.push(Value::Array(candidate));
+                    }
+                    self.check(location, candidates, |kind| {
+                        matches!(kind, ValidationErrorKind::AdditionalItems { .. })
+                    });
+                }
+            }
+        }
+        if let Some(not) = super::child(schema, "not") {
+            // An instance that satisfies the negated subschema and ideally everything else
+            let mut with_others = parts.to_vec();
+            with_others.push((not.clone(), scope.clone()));
+            let candidates = vec![
+                self.builder.build(with_others),
+                self.builder.build(vec![(not, scope.clone())]),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            self.check(location, candidates, |kind| {
+                matches!(kind, ValidationErrorKind::Not { .. })
+            });
+        }
+    }
+
+    /// Append built items to `items` until it has `length` items.
+    fn extend(&mut self, items: &[Value], length: u64, parts: &[Part<'r>]) -> Option<Value> {
+        let mut extended = items.to_vec();
+        while (extended.len() as u64) < length {
+            let item_parts = self.builder.item_parts(parts, extended.len() as u64)?;
+            extended.push(self.builder.build(item_parts)?);
+        }
+        Some(Value::Array(extended))
+    }
+
+    /// Replace the value at `location` with the first candidate that triggers exactly one error of the expected kind.
+    fn check(&mut self, location: &JSONPointer, candidates: Vec<Value>, expected: Expected) {
+        for candidate in candidates {
+            let mut instance = self.instance.clone();
+            if let Some(target) = pointer_mut(&mut instance, location) {
+                *target = candidate;
+            } else {
+                return;
+            }
+            if self
+                .found
+                .iter()
+                .any(|existing| existing.instance == instance)
+            {
+                continue;
+            }
+            let schema = self.unlimited.as_ref().unwrap_or(self.builder.schema);
+            let error = match schema.validate(&instance) {
+                Ok(()) => continue,
+                Err(errors) => {
+                    let mut errors = errors.take(2);
+                    match (errors.next(), errors.next()) {
+                        (Some(error), None) if expected(&error.kind) => error,
+                        _ => continue,
+                    }
+                }
+            };
+            let ValidationError {
+                kind,
+                instance_path,
+                ..
+            } = error;
+            self.found.push(InvalidInstance {
+                instance,
+                kind: *kind,
+                instance_path,
+            });
+            return;
+        }
+    }
+}
+
+/// A value of the given type.
+fn sample(kind: Kind) -> Value {
+    match kind {
+        Kind::Null => Value::Null,
+        Kind::Boolean => Value::Bool(false),
+        Kind::Integer => Value::from(0),
+        Kind::Number => Value::from(0.5),
+        Kind::String => Value::String(String::new()),
+        Kind::Array => Value::Array(Vec::new()),
+        Kind::Object => Value::Object(Map::new()),
+    }
+}
+
+/// Values that are close to `value`, values of the same type go first.
+fn variations(value: &Value) -> Vec<Value> {
+    let mut variations = match value {
+        Value::Null => vec![],
+        Value::Bool(value) => vec![Value::Bool(!value)],
+        Value::Number(value) => value
+            .as_f64()
+            .and_then(|value| number(value + 1.0))
+            .into_iter()
+            .collect(),
+        Value::String(value) => vec![Value::String(format!("{}_", value))],
+        Value::Array(items) => {
+            let mut items = items.clone();
+            items.push(Value::Null);
+            vec![Value::Array(items)]
+        }
+        Value::Object(object) => vec![with(object, "property")],
+    };
+    variations.extend(ALL_KINDS.iter().map(|kind| sample(*kind)));
+    variations
+}
+
+/// Numbers that are less than the lower bound.
+fn below(limit: f64, exclusive: bool, multiple_of: Option<f64>) -> Vec<Value> {
+    let mut candidates = Vec::new();
+    if exclusive {
+        candidates.push(limit);
+    }
+    if let Some(multiple_of) = multiple_of {
+        candidates.push(((limit / multiple_of).ceil() - 1.0) * multiple_of);
+    }
+    candidates.push((limit - 1.0).floor());
+    candidates.into_iter().filter_map(number).collect()
+}
+
+/// Numbers that are greater than the upper bound.
+fn above(limit: f64, exclusive: bool, multiple_of: Option<f64>) -> Vec<Value> {
+    let mut candidates = Vec::new();
+    if exclusive {
+        candidates.push(limit);
+    }
+    if let Some(multiple_of) = multiple_of {
+        candidates.push(((limit / multiple_of).floor() + 1.0) * multiple_of);
+    }
+    candidates.push((limit + 1.0).ceil());
+    candidates.into_iter().filter_map(number).collect()
+}
+
+fn without(object: &Map<String, Value>, name: &str) -> Value {
+    let mut object = object.clone();
+    object.remove(name);
+    Value::Object(object)
+}
+
+fn with(object: &Map<String, Value>, name: &str) -> Value {
+    let mut object = object.clone();
+    object.insert(name.to_string(), Value::Null);
+    Value::Object(object)
+}
+
+fn pointer_mut<'v>(value: &'v mut Value, location: &JSONPointer) -> Option<&'v mut Value> {
+    let mut target = value;
+    for chunk in location {
+        target = match chunk {
+            PathChunk::Property(name) => target.get_mut(name.as_str())?,
+            PathChunk::Index(idx) => target.get_mut(*idx)?,
+        };
+    }
+    Some(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Expected, InvalidInstance};
+    use crate::{
+        compilation::JSONSchema, error::ValidationErrorKind, generator::InstanceGenerator,
+    };
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn mutations(schema: &Value) -> Vec<InvalidInstance> {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        InstanceGenerator::new(&compiled, 0).invalid()
+    }
+
+    #[test_case(
+        json!({"type": "string"}),
+        json!(null),
+        |kind| matches!(kind, ValidationErrorKind::Type { .. }),
+        "";
+        "wrong type"
+    )]
+    #[test_case(
+        json!({"const": 1}),
+        json!(2),
+        |kind| matches!(kind, ValidationErrorKind::Constant { .. }),
+        "";
+        "constant"
+    )]
+    #[test_case(
+        json!({"enum": ["a", "b"]}),
+        json!("a_"),
+        |kind| matches!(kind, ValidationErrorKind::Enum { .. }),
+        "";
+        "enumeration"
+    )]
+    #[test_case(
+        json!({"type": "integer", "minimum": 3}),
+        json!(2),
+        |kind| matches!(kind, ValidationErrorKind::Minimum { .. }),
+        "";
+        "minimum"
+    )]
+    #[test_case(
+        json!({"type": "integer", "exclusiveMaximum": 3}),
+        json!(3),
+        |kind| matches!(kind, ValidationErrorKind::ExclusiveMaximum { .. }),
+        "";
+        "exclusive maximum"
+    )]
+    #[test_case(
+        json!({"type": "integer", "multipleOf": 2}),
+        json!(1),
+        |kind| matches!(kind, ValidationErrorKind::MultipleOf { .. }),
+        "";
+        "multiple of"
+    )]
+    #[test_case(
+        json!({"type": "string", "minLength": 2}),
+        json!("a"),
+        |kind| matches!(kind, ValidationErrorKind::MinLength { .. }),
+        "";
+        "min length"
+    )]
+    #[test_case(
+        json!({"type": "string", "maxLength": 2}),
+        json!("aaa"),
+        |kind| matches!(kind, ValidationErrorKind::MaxLength { .. }),
+        "";
+        "max length"
+    )]
+    #[test_case(
+        json!({"type": "string", "pattern": "^a+$"}),
+        json!(""),
+        |kind| matches!(kind, ValidationErrorKind::Pattern { .. }),
+        "";
+        "pattern"
+    )]
+    #[test_case(
+        json!({"type": "string", "format": "ipv4"}),
+        json!("invalid"),
+        |kind| matches!(kind, ValidationErrorKind::Format { .. }),
+        "";
+        "format"
+    )]
+    #[test_case(
+        json!({"required": ["a"]}),
+        json!({}),
+        |kind| matches!(kind, ValidationErrorKind::Required { .. }),
+        "";
+        "required"
+    )]
+    #[test_case(
+        json!({"properties": {"a": {"type": "integer"}}, "required": ["a"]}),
+        json!({"a": null}),
+        |kind| matches!(kind, ValidationErrorKind::Type { .. }),
+        "/a";
+        "nested"
+    )]
+    #[test_case(
+        json!({"type": "object", "additionalProperties": false}),
+        json!({"additionalProperty": null}),
+        |kind| matches!(kind, ValidationErrorKind::FalseSchema),
+        "";
+        "additional properties"
+    )]
+    #[test_case(
+        json!({"type": "object", "maxProperties": 1}),
+        json!({"property0": null, "property1": null}),
+        |kind| matches!(kind, ValidationErrorKind::MaxProperties { .. }),
+        "";
+        "max properties"
+    )]
+    #[test_case(
+        json!({"type": "array", "minItems": 1}),
+        json!([]),
+        |kind| matches!(kind, ValidationErrorKind::MinItems { .. }),
+        "";
+        "min items"
+    )]
+    #[test_case(
+        json!({"type": "array", "minItems": 1, "maxItems": 1}),
+        json!([null, null]),
+        |kind| matches!(kind, ValidationErrorKind::MaxItems { .. }),
+        "";
+        "max items"
+    )]
+    #[test_case(
+        json!({"type": "array", "minItems": 1, "uniqueItems": true}),
+        json!([null, null]),
+        |kind| matches!(kind, ValidationErrorKind::UniqueItems),
+        "";
+        "unique items"
+    )]
+    #[test_case(
+        json!({"contains": {"const": 1}}),
+        json!([]),
+        |kind| matches!(kind, ValidationErrorKind::Contains),
+        "";
+        "contains"
+    )]
+    #[test_case(
+        json!({"items": [{"type": "null"}], "additionalItems": false}),
+        json!([null, null]),
+        |kind| matches!(kind, ValidationErrorKind::AdditionalItems { .. }),
+        "";
+        "additional items"
+    )]
+    #[test_case(
+        json!({"type": "integer", "not": {"const": 0}}),
+        json!(0),
+        |kind| matches!(kind, ValidationErrorKind::Not { .. }),
+        "";
+        "negation"
+    )]
+    #[test_case(
+        json!({"$ref": "#/definitions/a", "definitions": {"a": {"type": "boolean"}}}),
+        json!(null),
+        |kind| matches!(kind, ValidationErrorKind::Type { .. }),
+        "";
+        "reference"
+    )]
+    fn mutation(schema: Value, instance: Value, expected: Expected, path: &str) {
+        let mutations = mutations(&schema);
+        assert!(
+            mutations.iter().any(|invalid| invalid.instance == instance
+                && expected(&invalid.kind)
+                && invalid.instance_path.to_string() == path),
+            "{:?}",
+            mutations
+        );
+    }
+
+    #[test]
+    fn single_error() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "id": {"type": "integer", "minimum": 1, "maximum": 10},
+                "name": {"type": "string", "minLength": 1, "maxLength": 5, "pattern": "^[a-z]+$"},
+                "tags": {"type": "array", "items": {"enum": ["a", "b"]}, "minItems": 1, "uniqueItems": true}
+            },
+            "required": ["id", "name", "tags"],
+            "additionalProperties": false
+        });
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let invalid = InstanceGenerator::new(&compiled, 0).invalid();
+        assert!(invalid.len() > 10);
+        for invalid in invalid {
+            let errors: Vec<_> = compiled.validate(&invalid.instance).unwrap_err().collect();
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].instance_path, invalid.instance_path);
+        }
+    }
+
+    #[test]
+    fn mutate() {
+        let schema = json!({"properties": {"a": {"type": "string", "maxLength": 1}}});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let generator = InstanceGenerator::new(&compiled, 0);
+        // The optional property is absent in the minimal instance
+        assert!(generator.invalid().is_empty());
+        let invalid = generator.mutate(&json!({"a": "b"}));
+        assert!(invalid
+            .iter()
+            .any(|invalid| matches!(invalid.kind, ValidationErrorKind::MaxLength { .. })));
+        // Invalid instances are not mutated
+        assert!(generator.mutate(&json!({"a": 1})).is_empty());
+    }
+
+    #[test_case(json!({"type": "string", "maxLength": u64::MAX}); "max length")]
+    #[test_case(json!({"type": "object", "maxProperties": u64::MAX}); "max properties")]
+    #[test_case(json!({"type": "array", "minItems": 1, "maxItems": u64::MAX}); "max items")]
+    fn huge_limits(schema: Value) {
+        // Such limits can't be broken within a reasonable size
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let invalid = InstanceGenerator::new(&compiled, 0).invalid();
+        assert!(!invalid.is_empty());
+        assert!(!invalid.iter().any(|invalid| matches!(
+            invalid.kind,
+            ValidationErrorKind::MaxLength { .. }
+                | ValidationErrorKind::MaxProperties { .. }
+                | ValidationErrorKind::MaxItems { .. }
+        )));
+    }
+
+    #[test]
+    fn max_errors() {
+        let schema = json!({"type": "object", "properties": {"a": {"type": "string", "minLength": 2}}, "required": ["a"]});
+        let compiled = JSONSchema::options()
+            .with_max_errors(1)
+            .compile(&schema)
+            .unwrap();
+        let invalid = InstanceGenerator::new(&compiled, 0).invalid();
+        let unlimited = JSONSchema::compile(&schema).unwrap();
+        assert!(!invalid.is_empty());
+        for invalid in invalid {
+            let errors: Vec<_> = unlimited.validate(&invalid.instance).unwrap_err().collect();
+            assert_eq!(errors.len(), 1, "{}", invalid.instance);
+        }
+    }
+}
@@ -0,0 +1,1141 @@
+//! Generating instances that are valid against a schema.
+//!
+//! A schema is flattened into a list of parts - `$ref` targets, `allOf` members and chosen `anyOf` / `oneOf` /
+//! `if` branches. Then constraints from all parts are combined to build an instance of a suitable type.
+//! Values that are valid against a `not` subschema are rebuilt randomly. Some keywords (e.g. `uniqueItems`
+//! inside nested arrays) are not taken into account during building, therefore every generated instance is verified with `JSONSchema::is_valid` and rebuilt if it is invalid.
+mod invalid;
+mod pattern;
+pub(crate) mod random;
+
+use crate::{
+    compilation::{compile_validators, context::CompilationContext, JSONSchema},
+    keywords::{pattern::convert_regex, Validators},
+    schemas::{id_of, Draft},
+};
+use ::regex::Regex;
+use base64::Engine;
+pub use invalid::InvalidInstance;
+use invalid::Mutator;
+use random::Random;
+use serde_json::{Map, Number, Value};
+use std::{borrow::Cow, convert::TryFrom};
+use url::Url;
+
+/// How many times an invalid instance is rebuilt before giving up.
+pub(crate) const MAX_ATTEMPTS: usize = 100;
+/// Subschemas deeper than this level are built as minimal instances, which usually stops recursion.
+const MAX_DEPTH: usize = 8;
+/// Building is stopped at this level.
+const DEPTH_LIMIT: usize = 32;
+/// How many items or properties are added on top of the required ones in random instances.
+const MAX_EXTRA_ITEMS: u64 = 3;
+/// Instances that need more characters, items or properties than this are not built.
+pub(crate) const MAX_SIZE: u64 = 10_000;
+/// Characters for strings without a pattern or a format.
+const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+/// Generates instances that are valid against a compiled schema.
+///
+/// The output depends only on the schema and the seed, so the same seed always produces the same instances.
+///
+/// ```rust
+/// # use jsonschema::{InstanceGenerator, JSONSchema};
+/// # use serde_json::json;
+/// let schema = json!({
+///     "type": "object",
+///     "properties": {
+///         "id": {"type": "integer", "minimum": 1},
+///         "email": {"type": "string", "format": "email"},
+///         "tags": {"type": "array", "items": {"pattern": "^[a-z]{3,8}$"}}
+///     },
+///     "required": ["id", "email"]
+/// });
+/// let compiled = JSONSchema::compile(&schema).expect("A valid schema");
+/// let mut generator = InstanceGenerator::new(&compiled, 42);
+/// assert_eq!(
+///     generator.minimal(),
+///     Some(json!({"id": 1, "email": "user@example.com"}))
+/// );
+/// for instance in generator.take(10) {
+///     assert!(compiled.is_valid(&instance));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct InstanceGenerator<'s, 'a> {
+    schema: &'s JSONSchema<'a>,
+    random: Random,
+}
+
+impl<'s, 'a> InstanceGenerator<'s, 'a> {
+    /// Create a generator of instances valid against `schema`.
+    #[must_use]
+    pub const fn new(schema: &'s JSONSchema<'a>, seed: u64) -> Self {
+        InstanceGenerator {
+            schema,
+            random: Random::new(seed),
+        }
+    }
+
+    /// The simplest valid instance - the first allowed type, the smallest numbers, the shortest strings,
+    /// only required properties and as few items as possible.
+    ///
+    /// Returns `None` if such an instance can't be built.
+    #[must_use]
+    pub fn minimal(&self) -> Option<Value> {
+        let mut random = self.random.clone();
+        let instance = Builder::new(self.schema, &mut random, true).root()?;
+        if self.schema.is_valid(&instance) {
+            Some(instance)
+        } else {
+            None
+        }
+    }
+
+    /// Instances that break exactly one constraint each, built by mutating the minimal instance
+    /// (or a random one if there is no minimal instance).
+    ///
+    /// Only constraints that apply to the parts present in the mutated instance are covered,
+    /// use `InstanceGenerator::mutate` with random instances to cover optional properties and items.
+    ///
+    /// ```rust
+    /// # use jsonschema::{InstanceGenerator, JSONSchema, ValidationErrorKind};
+    /// # use serde_json::json;
+    /// let schema = json!({
+    ///     "type": "object",
+    ///     "properties": {"name": {"type": "string", "maxLength": 3}},
+    ///     "required": ["name"]
+    /// });
+    /// let compiled = JSONSchema::compile(&schema).expect("A valid schema");
+    /// let invalid = InstanceGenerator::new(&compiled, 42).invalid();
+    /// let too_long = invalid
+    ///     .iter()
+    ///     .find(|invalid| matches!(invalid.kind, ValidationErrorKind::MaxLength { .. }))
+    ///     .expect("`maxLength` is broken");
+    /// assert_eq!(too_long.instance, json!({"name": "aaaa"}));
+    /// assert_eq!(too_long.instance_path.to_string(), "/name");
+    /// ```
+    #[must_use]
+    pub fn invalid(&self) -> Vec<InvalidInstance> {
+        let instance = self.minimal().or_else(|| {
+            InstanceGenerator {
+                schema: self.schema,
+                random: self.random.clone(),
+            }
+            .next()
+        });
+        match instance {
+            Some(instance) => self.mutate(&instance),
+            None => Vec::new(),
+        }
+    }
+
+    /// Instances that break exactly one constraint each, built by mutating `instance`.
+    /// Each one is paired with the error it triggers.
+    ///
+    /// Returns nothing if `instance` itself is invalid.
+    #[must_use]
+    pub fn mutate(&self, instance: &Value) -> Vec<InvalidInstance> {
+        if !self.schema.is_valid(instance) {
+            return Vec::new();
+        }
+        let mut random = self.random.clone();
+        Mutator::new(Builder::new(self.schema, &mut random, true), instance).mutate()
+    }
+}
+
+impl<'s, 'a> Iterator for InstanceGenerator<'s, 'a> {
+    type Item = Value;
+
+    /// Generate a random valid instance. Returns `None` if no valid instance was built
+    /// after a number of attempts, e.g. if the schema can't be satisfied.
+    fn next(&mut self) -> Option<Self::Item> {
+        let schema = self.schema;
+        let mut builder = Builder::new(schema, &mut self.random, false);
+        for _ in 0..MAX_ATTEMPTS {
+            if let Some(instance) = builder.root() {
+                if schema.is_valid(&instance) {
+                    return Some(instance);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// A subschema together with its resolution scope.
+type Part<'r> = (Cow<'r, Value>, Url);
+
+/// JSON types in the order of their preference in minimal instances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Null,
+    Boolean,
+    Integer,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+const ALL_KINDS: &[Kind] = &[
+    Kind::Null,
+    Kind::Boolean,
+    Kind::Integer,
+    Kind::Number,
+    Kind::String,
+    Kind::Array,
+    Kind::Object,
+];
+
+impl Kind {
+    fn from_name(name: &str) -> &'static [Kind] {
+        match name {
+            "null" => &[Kind::Null],
+            "boolean" => &[Kind::Boolean],
+            "integer" => &[Kind::Integer],
+            "number" => &[Kind::Integer, Kind::Number],
+            "string" => &[Kind::String],
+            "array" => &[Kind::Array],
+            "object" => &[Kind::Object],
+            _ => &[],
+        }
+    }
+}
+
+pub(crate) struct Builder<'r, 'g> {
+    schema: &'r JSONSchema<'r>,
+    draft: Draft,
+    random: &'g mut Random,
+    minimal: bool,
+    depth: usize,
+    // Compiled `not` subschemas with their scopes, so they are compiled once even if values are rebuilt many times
+    negations: Vec<(Value, Url, Option<Validators>)>,
+}
+
+impl<'r, 'g> Builder<'r, 'g> {
+    pub(crate) fn new(schema: &'r JSONSchema<'r>, random: &'g mut Random, minimal: bool) -> Self {
+        Builder {
+            schema,
+            draft: schema.context.config.draft(),
+            random,
+            minimal,
+            depth: 0,
+            negations: Vec::new(),
+        }
+    }
+
+    pub(crate) fn root(&mut self) -> Option<Value> {
+        let root = self.root_part();
+        self.build(vec![root])
+    }
+
+    /// The root schema together with its scope.
+    fn root_part(&self) -> Part<'r> {
+        (
+            Cow::Borrowed(self.schema.schema.as_ref()),
+            self.schema.context.scope.clone().into_owned(),
+        )
+    }
+
+    /// Whether the simplest option should be chosen at the current level.
+    const fn is_minimal(&self) -> bool {
+        self.minimal || self.depth > MAX_DEPTH
+    }
+
+    fn choose(&mut self, length: usize) -> usize {
+        if self.is_minimal() || length == 0 {
+            0
+        } else {
+            self.random.index(length)
+        }
+    }
+
+    fn between(&mut self, low: u64, high: u64) -> u64 {
+        if self.is_minimal() {
+            low
+        } else {
+            self.random.between(low, high)
+        }
+    }
+
+    /// Build an instance that satisfies all `parts`.
+    pub(crate) fn build(&mut self, parts: Vec<Part<'r>>) -> Option<Value> {
+        if self.depth > DEPTH_LIMIT {
+            return None;
+        }
+        self.depth += 1;
+        let result = self.build_parts(parts);
+        self.depth -= 1;
+        result
+    }
+
+    fn build_parts(&mut self, parts: Vec<Part<'r>>) -> Option<Value> {
+        let mut flat = Vec::new();
+        for (schema, scope) in parts {
+            self.flatten(schema, scope, &mut flat)?;
+        }
+        let parts = flat;
+        let negated: Vec<usize> = parts
+            .iter()
+            .filter_map(|(schema, scope)| child(schema, "not").map(|sub| self.compile(&sub, scope)))
+            .collect();
+        if negated.is_empty() {
+            return self.value(&parts);
+        }
+        // Values that are valid against a negated subschema are replaced with random ones
+        let minimal = self.minimal;
+        let mut result = None;
+        for _ in 0..MAX_ATTEMPTS {
+            if let Some(value) = self.value(&parts) {
+                if !negated.iter().any(|idx| self.is_valid(*idx, &value)) {
+                    result = Some(value);
+                    break;
+                }
+            }
+            self.minimal = false;
+        }
+        self.minimal = minimal;
+        result
+    }
+
+    /// Compile a negated subschema unless it is already compiled. Returns its index in `negations`.
+    fn compile(&mut self, schema: &Value, scope: &Url) -> usize {
+        if let Some(idx) = self
+            .negations
+            .iter()
+            .position(|(compiled, compiled_scope, _)| compiled == schema && compiled_scope == scope)
+        {
+            return idx;
+        }
+        let context = CompilationContext::new(
+            scope.clone(),
+            Cow::Borrowed(self.schema.context.config.as_ref()),
+        );
+        let validators = compile_validators(schema, &context).ok();
+        self.negations
+            .push((schema.clone(), scope.clone(), validators));
+        self.negations.len() - 1
+    }
+
+    /// Whether `value` is valid against the negated subschema with the given index.
+    fn is_valid(&self, idx: usize, value: &Value) -> bool {
+        self.negations[idx].2.as_ref().is_some_and(|validators| {
+            validators
+                .iter()
+                .all(|validator| validator.is_valid(self.schema, value))
+        })
+    }
+
+    /// Build a value of a suitable type for the flattened `parts`.
+    fn value(&mut self, parts: &[Part<'r>]) -> Option<Value> {
+        // Fixed values
+        for (schema, _) in parts {
+            if let Some(value) = schema.get("const") {
+                return Some(value.clone());
+            }
+            if let Some(Value::Array(values)) = schema.get("enum") {
+                let idx = self.choose(values.len());
+                return values.get(idx).cloned();
+            }
+        }
+        let kinds = self.kinds(parts);
+        if kinds.is_empty() {
+            return None;
+        }
+        let kind = kinds[self.choose(kinds.len())];
+        match kind {
+            Kind::Null => Some(Value::Null),
+            Kind::Boolean => Some(Value::Bool(!self.is_minimal() && self.random.boolean())),
+            Kind::Integer => self.integer(parts),
+            Kind::Number => self.number(parts),
+            Kind::String => self.string(parts).map(Value::String),
+            Kind::Array => self.array(parts),
+            Kind::Object => self.object(parts),
+        }
+    }
+
+    /// Resolve references and combine subschemas into a flat list of object schemas.
+    fn flatten(
+        &mut self,
+        schema: Cow<'r, Value>,
+        scope: Url,
+        parts: &mut Vec<Part<'r>>,
+    ) -> Option<()> {
+        let scope = match id_of(self.draft, &schema) {
+            Some(id) => scope.join(id).ok()?,
+            None => scope,
+        };
+        match schema.as_ref() {
+            Value::Bool(true) => return Some(()),
+            Value::Object(_) => {}
+            _ => return None,
+        }
+        if let Some(Value::String(reference)) = schema.get("$ref") {
+            if self.depth > DEPTH_LIMIT {
+                return None;
+            }
+            let url = scope.join(reference).ok()?;
+            let schema: &'r JSONSchema<'r> = self.schema;
+            let (scope, resolved) = schema
+                .resolver
+                .resolve_fragment(self.draft, &url, schema.schema.as_ref())
+                .ok()?;
+            self.depth += 1;
+            let result = self.flatten(resolved, scope, parts);
+            self.depth -= 1;
+            return result;
+        }
+        for member in elements(&child(&schema, "allOf")) {
+            self.flatten(member, scope.clone(), parts)?;
+        }
+        for keyword in &["anyOf", "oneOf"] {
+            let branches = elements(&child(&schema, keyword));
+            if !branches.is_empty() {
+                let idx = self.choose(branches.len());
+                let branch = branches.into_iter().nth(idx)?;
+                self.flatten(branch, scope.clone(), parts)?;
+            }
+        }
+        if self.draft == Draft::Draft7 {
+            if let Some(condition) = child(&schema, "if") {
+                // Either satisfy both `if` and `then`, or take the `else` branch
+                let branch = if self.is_minimal() || self.random.boolean() {
+                    match child(&schema, "then") {
+                        Some(then) => {
+                            self.flatten(condition, scope.clone(), parts)?;
+                            Some(then)
+                        }
+                        None => None,
+                    }
+                } else {
+                    child(&schema, "else")
+                };
+                if let Some(branch) = branch {
+                    self.flatten(branch, scope.clone(), parts)?;
+                }
+            }
+        }
+        parts.push((schema, scope));
+        Some(())
+    }
+
+    /// Types that are allowed by the `type` keywords of all parts. `None` if there are no such keywords.
+    fn types(&self, parts: &[Part<'r>]) -> Option<Vec<Kind>> {
+        let mut kinds = ALL_KINDS.to_vec();
+        let mut has_type = false;
+        for (schema, _) in parts {
+            let allowed: Vec<Kind> = match schema.get("type") {
+                Some(Value::String(name)) => Kind::from_name(name).to_vec(),
+                Some(Value::Array(names)) => names
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .flat_map(Kind::from_name)
+                    .copied()
+                    .collect(),
+                _ => continue,
+            };
+            has_type = true;
+            let nullable = self.draft == Draft::OpenAPI30
+                && matches!(schema.get("nullable"), Some(Value::Bool(true)));
+            kinds.retain(|kind| allowed.contains(kind) || (nullable && *kind == Kind::Null));
+        }
+        if has_type {
+            Some(kinds)
+        } else {
+            None
+        }
+    }
+
+    /// Types that are allowed by all parts.
+    fn kinds(&self, parts: &[Part<'r>]) -> Vec<Kind> {
+        let mut kinds = match self.types(parts) {
+            Some(kinds) => kinds,
+            None => {
+                // The type is implied by the keywords in use
+                let implied: Vec<Kind> = ALL_KINDS
+                    .iter()
+                    .copied()
+                    .filter(|kind| {
+                        parts.iter().any(|(schema, _)| {
+                            implied_keywords(*kind)
+                                .iter()
+                                .any(|keyword| schema.get(*keyword).is_some())
+                        })
+                    })
+                    .collect();
+                if implied.is_empty() {
+                    ALL_KINDS.to_vec()
+                } else {
+                    return implied;
+                }
+            }
+        };
+        if kinds.contains(&Kind::Integer) && kinds.contains(&Kind::Number) && self.is_minimal() {
+            // Numbers are integers in the minimal mode, unless there are none between the bounds
+            kinds.retain(|kind| *kind != Kind::Integer);
+        }
+        kinds
+    }
+
+    fn integer(&mut self, parts: &[Part<'r>]) -> Option<Value> {
+        let bounds = self.bounds(parts);
+        let mut low = bounds.low.map_or(-1000.0, |(value, exclusive)| {
+            if exclusive && value.fract() == 0.0 {
+                value + 1.0
+            } else {
+                value.ceil()
+            }
+        });
+        let mut high = bounds.high.map_or(1000.0, |(value, exclusive)| {
+            if exclusive && value.fract() == 0.0 {
+                value - 1.0
+            } else {
+                value.floor()
+            }
+        });
+        if bounds.low.is_none() && bounds.high.is_some() {
+            low = low.min(high - 1000.0);
+        }
+        if bounds.high.is_none() && bounds.low.is_some() {
+            high = high.max(low + 1000.0);
+        }
+        for (schema, _) in parts {
+            match schema.get("format").and_then(Value::as_str) {
+                Some("int32") if self.draft == Draft::OpenAPI30 => {
+                    low = low.max(f64::from(i32::MIN));
+                    high = high.min(f64::from(i32::MAX));
+                }
+                _ => {}
+            }
+        }
+        let step = bounds.multiple_of.unwrap_or(1.0);
+        let (first, last) = ((low / step).ceil(), (high / step).floor());
+        if first > last {
+            return None;
+        }
+        let factor = if self.is_minimal() {
+            // The value closest to zero
+            0_f64.max(first).min(last)
+        } else {
+            (first + (self.random.unit() * (last - first + 1.0)).floor()).min(last)
+        };
+        number(factor * step)
+    }
+
+    fn number(&mut self, parts: &[Part<'r>]) -> Option<Value> {
+        let bounds = self.bounds(parts);
+        if self.is_minimal() || bounds.multiple_of.is_some() || self.random.boolean() {
+            let integer = self.integer(parts);
+            // There could be no integers between the bounds
+            if integer.is_some() || bounds.multiple_of.is_some() {
+                return integer;
+            }
+        }
+        let low = bounds.low.map_or(-1000.0, |(value, _)| value);
+        let high = bounds
+            .high
+            .map_or(low.max(0.0) + 1000.0, |(value, _)| value);
+        let low = if bounds.low.is_none() {
+            low.min(high - 1000.0)
+        } else {
+            low
+        };
+        if low > high {
+            return None;
+        }
+        if self.is_minimal() {
+            return match bounds.low {
+                Some((value, false)) => number(value),
+                _ => number((low + high) / 2.0),
+            };
+        }
+        let value = low + self.random.unit() * (high - low);
+        // Keep two decimal digits for readability, unless it breaks the bounds
+        let rounded = (value * 100.0).round() / 100.0;
+        if rounded > low && rounded < high {
+            number(rounded)
+        } else {
+            number(value)
+        }
+    }
+
+    fn bounds(&self, parts: &[Part<'r>]) -> Bounds {
+        let mut bounds = Bounds::default();
+        for (schema, _) in parts {
+            let legacy = matches!(self.draft, Draft::Draft4 | Draft::OpenAPI30);
+            if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+                let exclusive =
+                    legacy && matches!(schema.get("exclusiveMinimum"), Some(Value::Bool(true)));
+                bounds.raise((minimum, exclusive));
+            }
+            if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+                let exclusive =
+                    legacy && matches!(schema.get("exclusiveMaximum"), Some(Value::Bool(true)));
+                bounds.lower((maximum, exclusive));
+            }
+            if !legacy {
+                if let Some(minimum) = schema.get("exclusiveMinimum").and_then(Value::as_f64) {
+                    bounds.raise((minimum, true));
+                }
+                if let Some(maximum) = schema.get("exclusiveMaximum").and_then(Value::as_f64) {
+                    bounds.lower((maximum, true));
+                }
+            }
+            if let Some(multiple_of) = schema.get("multipleOf").and_then(Value::as_f64) {
+                bounds.multiple_of = Some(match bounds.multiple_of {
+                    Some(current) if current % multiple_of == 0.0 => current,
+                    Some(current) if multiple_of % current == 0.0 => multiple_of,
+                    Some(current) => current * multiple_of,
+                    None => multiple_of,
+                });
+            }
+        }
+        bounds
+    }
+
+    fn string(&mut self, parts: &[Part<'r>]) -> Option<String> {
+        let (min_length, max_length) = self.limits(parts, "minLength", "maxLength");
+        if min_length > max_length || min_length > MAX_SIZE {
+            return None;
+        }
+        for (schema, _) in parts {
+            if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+                let minimal = self.is_minimal();
+                return pattern::generate_sized(
+                    pattern,
+                    self.random,
+                    minimal,
+                    min_length,
+                    max_length,
+                );
+            }
+        }
+        for (schema, _) in parts {
+            if let Some(samples) = schema
+                .get("format")
+                .and_then(Value::as_str)
+                .and_then(|format| format_samples(self.draft, format))
+            {
+                let idx = self.choose(samples.len());
+                return Some(samples[idx].to_string());
+            }
+        }
+        let content_encoding = parts
+            .iter()
+            .find_map(|(schema, _)| schema.get("contentEncoding").and_then(Value::as_str));
+        let content_media_type = parts
+            .iter()
+            .find_map(|(schema, _)| schema.get("contentMediaType").and_then(Value::as_str));
+        let content = match content_media_type {
+            Some("application/json") if self.draft != Draft::Draft4 => Some("{}".to_string()),
+            _ => None,
+        };
+        let length = self.between(min_length, max_length.min(min_length.saturating_add(10)));
+        let mut string = content.unwrap_or_else(|| {
+            (0..length)
+                .map(|_| {
+                    if self.is_minimal() {
+                        'a'
+                    } else {
+                        char::from(LETTERS[self.random.index(LETTERS.len())])
+                    }
+                })
+                .collect()
+        });
+        if content_encoding == Some("base64") && self.draft != Draft::Draft4 {
+            string = base64::engine::general_purpose::STANDARD.encode(string);
+        }
+        Some(string)
+    }
+
+    /// Combined lower and upper limits for sizes, e.g. `minItems` and `maxItems`.
+    fn limits(&self, parts: &[Part<'r>], min_keyword: &str, max_keyword: &str) -> (u64, u64) {
+        let mut low = 0;
+        let mut high = u64::MAX;
+        for (schema, _) in parts {
+            if let Some(value) = schema.get(min_keyword).and_then(Value::as_u64) {
+                low = low.max(value);
+            }
+            if let Some(value) = schema.get(max_keyword).and_then(Value::as_u64) {
+                high = high.min(value);
+            }
+        }
+        (low, high)
+    }
+
+    fn array(&mut self, parts: &[Part<'r>]) -> Option<Value> {
+        let (min_items, max_items) = self.limits(parts, "minItems", "maxItems");
+        if min_items > max_items || min_items > MAX_SIZE {
+            return None;
+        }
+        let unique = parts
+            .iter()
+            .any(|(schema, _)| matches!(schema.get("uniqueItems"), Some(Value::Bool(true))));
+        let contains: Vec<Part<'r>> = if matches!(self.draft, Draft::Draft6 | Draft::Draft7) {
+            parts
+                .iter()
+                .filter_map(|(schema, scope)| {
+                    child(schema, "contains").map(|sub| (sub, scope.clone()))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let low = min_items.max(if contains.is_empty() { 0 } else { 1 });
+        let length = self.between(low, max_items.min(low.saturating_add(MAX_EXTRA_ITEMS)));
+        let mut items: Vec<Value> = Vec::new();
+        for idx in 0..length {
+            let mut item_parts = self.item_parts(parts, idx)?;
+            if idx == 0 {
+                item_parts.extend(contains.iter().cloned());
+            }
+            let mut item = self.build(item_parts.clone())?;
+            if unique {
+                let mut attempts = 0;
+                // Minimal instances are the same for the same subschemas, random ones are used instead
+                let minimal = self.minimal;
+                self.minimal = false;
+                while items.contains(&item) && attempts < MAX_ATTEMPTS {
+                    item = self.build(item_parts.clone())?;
+                    attempts += 1;
+                }
+                self.minimal = minimal;
+            }
+            items.push(item);
+        }
+        Some(Value::Array(items))
+    }
+
+    /// Subschemas that apply to the array item at `idx`.
+    fn item_parts(&self, parts: &[Part<'r>], idx: u64) -> Option<Vec<Part<'r>>> {
+        let mut item_parts = Vec::new();
+        for (schema, scope) in parts {
+            match child(schema, "items") {
+                Some(items) if items.is_array() => {
+                    let position = usize::try_from(idx).ok()?;
+                    match elements(&Some(items)).into_iter().nth(position) {
+                        Some(item) => item_parts.push((item, scope.clone())),
+                        None if self.draft != Draft::OpenAPI30 => {
+                            if let Some(additional) = child(schema, "additionalItems") {
+                                item_parts.push((additional, scope.clone()))
+                            }
+                        }
+                        None => {}
+                    }
+                }
+                Some(items) => item_parts.push((items, scope.clone())),
+                None => {}
+            }
+        }
+        Some(item_parts)
+    }
+
+    fn object(&mut self, parts: &[Part<'r>]) -> Option<Value> {
+        let (min_properties, max_properties) = self.limits(parts, "minProperties", "maxProperties");
+        if min_properties > max_properties || min_properties > MAX_SIZE {
+            return None;
+        }
+        let mut names: Vec<String> = Vec::new();
+        for (schema, _) in parts {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !names.iter().any(|existing| existing == name) {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+        }
+        let mut optional: Vec<String> = Vec::new();
+        for (schema, _) in parts {
+            if let Some(Value::Object(properties)) = schema.get("properties") {
+                for (name, subschema) in properties {
+                    let is_read_write_only = self.draft == Draft::OpenAPI30
+                        && ["readOnly", "writeOnly"].iter().any(|keyword| {
+                            matches!(subschema.get(*keyword), Some(Value::Bool(true)))
+                        });
+                    if !names.contains(name) && !optional.contains(name) && !is_read_write_only {
+                        optional.push(name.clone());
+                    }
+                }
+            }
+        }
+        if !self.is_minimal() {
+            for name in &optional {
+                if self.random.boolean() {
+                    names.push(name.clone());
+                }
+            }
+        }
+        // Fill up to `minProperties` with known properties first
+        let mut known = optional.into_iter();
+        let mut counter = 0;
+        while (names.len() as u64) < min_properties {
+            match known.next() {
+                Some(name) => {
+                    if !names.contains(&name) {
+                        names.push(name)
+                    }
+                }
+                None => {
+                    let name = self.property_name(parts, counter)?;
+                    counter += 1;
+                    if !names.contains(&name) {
+                        names.push(name)
+                    }
+                    if counter > MAX_ATTEMPTS {
+                        return None;
+                    }
+                }
+            }
+        }
+        while names.len() as u64 > max_properties {
+            names.pop();
+        }
+        // Dependencies only add property names
+        if self.draft != Draft::OpenAPI30 {
+            let mut idx = 0;
+            while idx < names.len() {
+                for (schema, _) in parts {
+                    if let Some(Value::Array(dependencies)) = schema
+                        .get("dependencies")
+                        .and_then(|value| value.get(&names[idx]))
+                    {
+                        for dependency in dependencies.iter().filter_map(Value::as_str) {
+                            if !names.iter().any(|name| name == dependency) {
+                                names.push(dependency.to_string());
+                            }
+                        }
+                    }
+                }
+                idx += 1;
+            }
+        }
+        let mut object = Map::new();
+        for name in names {
+            let value = self.build(self.property_parts(parts, &name))?;
+            object.insert(name, value);
+        }
+        Some(Value::Object(object))
+    }
+
+    /// A name for an additional property.
+    fn property_name(&mut self, parts: &[Part<'r>], counter: usize) -> Option<String> {
+        let additional_allowed = !parts.iter().any(|(schema, _)| {
+            matches!(schema.get("additionalProperties"), Some(Value::Bool(false)))
+        });
+        let name_parts: Vec<Part<'r>> = if matches!(self.draft, Draft::Draft6 | Draft::Draft7) {
+            parts
+                .iter()
+                .filter_map(|(schema, scope)| {
+                    child(schema, "propertyNames").map(|sub| (sub, scope.clone()))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        if !additional_allowed {
+            // Only names that match `patternProperties` are allowed
+            let patterns: Vec<String> = parts
+                .iter()
+                .filter_map(|(schema, _)| {
+                    schema.get("patternProperties").and_then(Value::as_object)
+                })
+                .flat_map(|patterns| patterns.keys().cloned())
+                .collect();
+            if patterns.is_empty() {
+                return None;
+            }
+            let idx = self.random.index(patterns.len());
+            return pattern::generate(&patterns[idx], self.random, false);
+        }
+        if !name_parts.is_empty() {
+            let minimal = self.minimal;
+            self.minimal = false;
+            let mut name_parts = name_parts;
+            let scope = name_parts[0].1.clone();
+            name_parts.push((Cow::Owned(serde_json::json!({"type": "string"})), scope));
+            let name = self.build(name_parts);
+            self.minimal = minimal;
+            return name.and_then(|name| name.as_str().map(str::to_string));
+        }
+        Some(format!("property{}", counter))
+    }
+
+    /// Subschemas that apply to the property with the given name.
+    fn property_parts(&self, parts: &[Part<'r>], name: &str) -> Vec<Part<'r>> {
+        let mut property_parts = Vec::new();
+        for (schema, scope) in parts {
+            let mut matched = false;
+            if let Some(property) =
+                child(schema, "properties").and_then(|properties| child(&properties, name))
+            {
+                property_parts.push((property, scope.clone()));
+                matched = true;
+            }
+            if self.draft != Draft::OpenAPI30 {
+                if let Some(patterns) = child(schema, "patternProperties") {
+                    for (pattern, subschema) in entries(&patterns) {
+                        if matches!(Regex::new(&convert_regex(&pattern)), Ok(regex) if regex.is_match(name))
+                        {
+                            property_parts.push((subschema, scope.clone()));
+                            matched = true;
+                        }
+                    }
+                }
+            }
+            if !matched {
+                if let Some(additional) = child(schema, "additionalProperties") {
+                    property_parts.push((additional, scope.clone()));
+                }
+            }
+        }
+        property_parts
+    }
+}
+
+#[derive(Debug, Default)]
+struct Bounds {
+    low: Option<(f64, bool)>,
+    high: Option<(f64, bool)>,
+    multiple_of: Option<f64>,
+}
+
+impl Bounds {
+    fn raise(&mut self, bound: (f64, bool)) {
+        match self.low {
+            Some((value, exclusive)) if value > bound.0 || (value == bound.0 && exclusive) => {}
+            _ => self.low = Some(bound),
+        }
+    }
+
+    fn lower(&mut self, bound: (f64, bool)) {
+        match self.high {
+            Some((value, exclusive)) if value < bound.0 || (value == bound.0 && exclusive) => {}
+            _ => self.high = Some(bound),
+        }
+    }
+}
+
+/// Keywords that make sense only for the given type.
+const fn implied_keywords(kind: Kind) -> &'static [&'static str] {
+    match kind {
+        Kind::Null | Kind::Boolean | Kind::Integer => &[],
+        Kind::Number => &[
+            "multipleOf",
+            "minimum",
+            "maximum",
+            "exclusiveMinimum",
+            "exclusiveMaximum",
+        ],
+        Kind::String => &[
+            "pattern",
+            "minLength",
+            "maxLength",
+            "format",
+            "contentEncoding",
+            "contentMediaType",
+        ],
+        Kind::Array => &[
+            "items",
+            "additionalItems",
+            "minItems",
+            "maxItems",
+            "uniqueItems",
+            "contains",
+        ],
+        Kind::Object => &[
+            "properties",
+            "patternProperties",
+            "additionalProperties",
+            "required",
+            "minProperties",
+            "maxProperties",
+            "dependencies",
+            "propertyNames",
+        ],
+    }
+}
+
+/// Sample values for formats. The first one is used in minimal instances.
+fn format_samples(draft: Draft, format: &str) -> Option<&'static [&'static str]> {
+    Some(match format {
+        "date" => &["2020-01-01", "1999-12-31"],
+        "date-time" => &["2020-01-01T00:00:00Z", "1999-12-31T23:59:59+01:00"],
+        "time" => &["00:00:00Z", "23:59:59+01:00"],
+        "email" | "idn-email" => &["user@example.com", "first.last@example.org"],
+        "hostname" | "idn-hostname" => &["example.com", "api.example.org"],
+        "ipv4" => &["127.0.0.1", "192.168.0.1"],
+        "ipv6" => &["::1", "2001:db8::1"],
+        "uri" | "iri" => &["https://example.com", "https://example.com/path?query=1"],
+        "uri-reference" | "iri-reference" => &["/path", "https://example.com/#fragment"],
+        "uri-template" => &["https://example.com/{id}", "/users{?page}"],
+        "json-pointer" => &["", "/items/0"],
+        "relative-json-pointer" => &["0", "1/items"],
+        "regex" => &["^[a-z]+$", ".*"],
+        "byte" if draft == Draft::OpenAPI30 => &["", "aGVsbG8="],
+        _ => return None,
+    })
+}
+
+fn number(value: f64) -> Option<Value> {
+    if value.fract() == 0.0 && value.abs() < 9_007_199_254_740_992.0 {
+        #[allow(clippy::cast_possible_truncation)]
+        let integer = value as i64;
+        Some(Value::Number(integer.into()))
+    } else {
+        Number::from_f64(value).map(Value::Number)
+    }
+}
+
+/// A subschema under the given keyword.
+fn child<'r>(schema: &Cow<'r, Value>, keyword: &str) -> Option<Cow<'r, Value>> {
+    match schema {
+        Cow::Borrowed(value) => value.get(keyword).map(Cow::Borrowed),
+        Cow::Owned(value) => value.get(keyword).cloned().map(Cow::Owned),
+    }
+}
+
+/// Items of an array of subschemas.
+fn elements<'r>(value: &Option<Cow<'r, Value>>) -> Vec<Cow<'r, Value>> {
+    match value {
+        Some(Cow::Borrowed(Value::Array(items))) => items.iter().map(Cow::Borrowed).collect(),
+        Some(Cow::Owned(Value::Array(items))) => items.iter().cloned().map(Cow::Owned).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Entries of an object of subschemas.
+fn entries<'r>(value: &Cow<'r, Value>) -> Vec<(String, Cow<'r, Value>)> {
+    match value {
+        Cow::Borrowed(Value::Object(items)) => items
+            .iter()
+            .map(|(key, value)| (key.clone(), Cow::Borrowed(value)))
+            .collect(),
+        Cow::Owned(Value::Object(items)) => items
+            .iter()
+            .map(|(key, value)| (key.clone(), Cow::Owned(value.clone())))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InstanceGenerator;
+    use crate::{compilation::JSONSchema, schemas::Draft};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(json!(true), json!(null))]
+    #[test_case(json!({"type": "string", "minLength": 3}), json!("aaa"))]
+    #[test_case(json!({"type": ["string", "null"]}), json!(null))]
+    #[test_case(json!({"type": "integer", "minimum": 5, "exclusiveMaximum": 10}), json!(5))]
+    #[test_case(json!({"type": "integer", "exclusiveMinimum": 5, "multipleOf": 4}), json!(8))]
+    #[test_case(json!({"type": "number", "maximum": -2.5}), json!(-3))]
+    #[test_case(json!({"type": "number", "exclusiveMinimum": 0.5, "maximum": 0.75}), json!(0.625))]
+    #[test_case(json!({"minimum": 0.25, "maximum": 0.75}), json!(0.25))]
+    #[test_case(json!({"enum": ["b", "a"]}), json!("b"))]
+    #[test_case(json!({"const": {"a": 1}}), json!({"a": 1}))]
+    #[test_case(json!({"pattern": "^[A-Z]{2}-\\d+$"}), json!("AA-0"))]
+    #[test_case(json!({"format": "date-time"}), json!("2020-01-01T00:00:00Z"))]
+    #[test_case(json!({"type": "array", "minItems": 2, "items": {"type": "boolean"}}), json!([false, false]))]
+    #[test_case(json!({"items": [{"const": 1}, {"type": "string"}], "minItems": 2}), json!([1, ""]))]
+    #[test_case(json!({"contains": {"const": 3}}), json!([3]))]
+    #[test_case(json!({"required": ["a"], "properties": {"a": {"type": "integer"}, "b": {}}}), json!({"a": 0}))]
+    #[test_case(json!({"minProperties": 2, "properties": {"a": {"type": "null"}}}), json!({"a": null, "property0": null}))]
+    #[test_case(json!({"required": ["a"], "dependencies": {"a": ["b"]}}), json!({"a": null, "b": null}))]
+    #[test_case(json!({"allOf": [{"required": ["a"]}, {"properties": {"a": {"type": "string"}}}]}), json!({"a": ""}))]
+    #[test_case(json!({"oneOf": [{"type": "integer"}, {"type": "string"}]}), json!(0))]
+    #[test_case(json!({"if": {"minimum": 10}, "then": {"multipleOf": 7}, "else": {"maximum": 0}}), json!(14))]
+    #[test_case(json!({"$ref": "#/definitions/a", "definitions": {"a": {"type": "boolean"}}}), json!(false))]
+    #[test_case(json!({"contentMediaType": "application/json", "contentEncoding": "base64"}), json!("e30="))]
+    #[test_case(json!({"pattern": "^a+$", "minLength": 3}), json!("aaa"))]
+    #[test_case(json!({"type": "integer", "not": {"const": 0}}), json!(-137))]
+    #[test_case(json!({"not": {"type": ["null", "boolean"]}}), json!(-137))]
+    fn minimal(schema: Value, expected: Value) {
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        assert_eq!(
+            InstanceGenerator::new(&compiled, 0).minimal(),
+            Some(expected)
+        );
+    }
+
+    #[test_case(json!({"type": "string", "minLength": 3, "maxLength": 5}))]
+    #[test_case(json!({"type": "number", "minimum": 0.5, "exclusiveMaximum": 1}))]
+    #[test_case(json!({"type": "integer", "minimum": -3, "maximum": 3, "multipleOf": 3}))]
+    #[test_case(json!({"type": "string", "format": "ipv6"}))]
+    #[test_case(json!({"type": "string", "pattern": "^[a-f0-9]{8}-[a-f0-9]{4}$"}))]
+    #[test_case(json!({"type": "array", "items": {"enum": [1, 2, 3]}, "minItems": 3, "uniqueItems": true}))]
+    #[test_case(json!({"type": "array", "items": [{"type": "string"}], "additionalItems": false}))]
+    #[test_case(json!({
+        "type": "object",
+        "properties": {"name": {"type": "string"}, "age": {"type": "integer", "minimum": 0}},
+        "patternProperties": {"^x-": {"type": "boolean"}},
+        "additionalProperties": false,
+        "required": ["name"],
+        "minProperties": 3
+    }))]
+    #[test_case(json!({"propertyNames": {"pattern": "^[a-z]+$"}, "minProperties": 2}))]
+    #[test_case(json!({"anyOf": [{"type": "string", "maxLength": 2}, {"type": "array", "maxItems": 1}]}))]
+    #[test_case(json!({"type": "integer", "not": {"multipleOf": 2}}))]
+    #[test_case(json!({"enum": [1, 2, 3], "not": {"$ref": "#/definitions/one"}, "definitions": {"one": {"const": 1}}}))]
+    #[test_case(json!({"type": "string", "pattern": "^[a-z]+\\d*$", "minLength": 5, "maxLength": 7}))]
+    #[test_case(json!({
+        "$ref": "#/definitions/node",
+        "definitions": {"node": {"type": "object", "properties": {"children": {"type": "array", "items": {"$ref": "#/definitions/node"}}}}}
+    }))]
+    #[test_case(json!({"$schema": "http://json-schema.org/draft-04/schema#", "minimum": 1, "exclusiveMinimum": true, "maximum": 2}))]
+    fn random(schema: Value) {
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let generator = InstanceGenerator::new(&compiled, 42);
+        let instances: Vec<_> = generator.take(50).collect();
+        assert_eq!(instances.len(), 50);
+        for instance in &instances {
+            assert!(compiled.is_valid(instance), "{}", instance);
+        }
+    }
+
+    #[test]
+    fn openapi() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"id": {"type": "integer", "format": "int32", "nullable": true}, "data": {"type": "string", "format": "byte"}},
+            "required": ["id", "data"]
+        });
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::OpenAPI30)
+            .compile(&schema)
+            .unwrap();
+        let generator = InstanceGenerator::new(&compiled, 1);
+        assert_eq!(generator.minimal(), Some(json!({"id": null, "data": ""})));
+        for instance in generator.take(20) {
+            assert!(compiled.is_valid(&instance));
+        }
+    }
+
+    #[test]
+    fn deterministic() {
+        let schema = json!({"type": "object", "properties": {"a": {"type": "string"}, "b": {"type": "number"}}});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let first: Vec<_> = InstanceGenerator::new(&compiled, 7).take(10).collect();
+        let second: Vec<_> = InstanceGenerator::new(&compiled, 7).take(10).collect();
+        let other: Vec<_> = InstanceGenerator::new(&compiled, 8).take(10).collect();
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
+
+    #[test_case(json!(false))]
+    #[test_case(json!({"type": "integer", "minimum": 2, "maximum": 1}))]
+    #[test_case(json!({"allOf": [{"type": "string"}, {"type": "number"}]}))]
+    #[test_case(json!({"type": "string", "minLength": u64::MAX}))]
+    #[test_case(json!({"type": "array", "minItems": u64::MAX}))]
+    #[test_case(json!({"type": "object", "minProperties": 1_000_000_000}))]
+    #[test_case(json!({"not": {}}))]
+    fn unsatisfiable(schema: Value) {
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let mut generator = InstanceGenerator::new(&compiled, 0);
+        assert_eq!(generator.minimal(), None);
+        assert_eq!(generator.next(), None);
+    }
+}
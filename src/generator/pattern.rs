@@ -0,0 +1,247 @@
+//! Generating strings that match regular expressions.
+use super::{random::Random, MAX_ATTEMPTS, MAX_SIZE};
+use crate::keywords::pattern::convert_regex;
+use regex_syntax::{
+    hir::{Class, Hir, HirKind},
+    Parser,
+};
+use std::{char, convert::TryFrom, str};
+
+/// Repetitions without an upper bound (e.g. `a*`) are limited to this number of extra items.
+const MAX_EXTRA_REPETITIONS: u32 = 3;
+
+/// How a string is generated.
+struct Settings {
+    /// Choose the first alternative and the first character of classes.
+    simplest: bool,
+    /// Repeat items as few times as possible.
+    shortest: bool,
+    /// How many extra items repetitions without an upper bound may have.
+    extra: u32,
+    /// Generation stops once the output is longer than this number of bytes.
+    limit: usize,
+}
+
+/// Generate a string that matches the ECMA 262 `pattern`. If `minimal` is set, the shortest
+/// and the simplest string is generated.
+///
+/// Returns `None` if the pattern is invalid or it can't match anything.
+pub(crate) fn generate(pattern: &str, random: &mut Random, minimal: bool) -> Option<String> {
+    generate_sized(pattern, random, minimal, 0, u64::MAX)
+}
+
+/// Generate a string that matches the ECMA 262 `pattern` and has from `min_length` to `max_length`
+/// characters. If the usual string doesn't fit, repetitions without an upper bound are stretched and a few
+/// random repetition counts are tried - the shortest suitable string is chosen if `minimal` is set.
+///
+/// Returns `None` if no such string was generated.
+pub(crate) fn generate_sized(
+    pattern: &str,
+    random: &mut Random,
+    minimal: bool,
+    min_length: u64,
+    max_length: u64,
+) -> Option<String> {
+    let hir = Parser::new().parse(&convert_regex(pattern)).ok()?;
+    // A character takes at most 4 bytes in UTF-8
+    let limit = usize::try_from(max_length.min(MAX_SIZE).saturating_mul(4)).unwrap_or(usize::MAX);
+    let fits = |output: &str| (min_length..=max_length).contains(&(output.chars().count() as u64));
+    let mut output = String::new();
+    let settings = Settings {
+        simplest: minimal,
+        shortest: minimal,
+        extra: MAX_EXTRA_REPETITIONS,
+        limit,
+    };
+    if write(&hir, random, &settings, &mut output) && fits(&output) {
+        return Some(output);
+    }
+    let settings = Settings {
+        simplest: minimal,
+        shortest: false,
+        extra: u32::try_from(min_length)
+            .unwrap_or(u32::MAX)
+            .max(MAX_EXTRA_REPETITIONS),
+        limit,
+    };
+    let mut shortest: Option<String> = None;
+    for _ in 0..MAX_ATTEMPTS {
+        output.clear();
+        if write(&hir, random, &settings, &mut output) && fits(&output) {
+            if !minimal {
+                return Some(output);
+            }
+            if shortest
+                .as_ref()
+                .is_none_or(|shortest| shortest.chars().count() > output.chars().count())
+            {
+                shortest = Some(output.clone());
+            }
+        }
+    }
+    shortest
+}
+
+fn write(hir: &Hir, random: &mut Random, settings: &Settings, output: &mut String) -> bool {
+    let minimal = settings.simplest;
+    match hir.kind() {
+        // Anchors and word boundaries don't produce any characters
+        HirKind::Empty | HirKind::Look(_) => true,
+        HirKind::Literal(literal) => match str::from_utf8(&literal.0) {
+            Ok(literal) => {
+                output.push_str(literal);
+                output.len() <= settings.limit
+            }
+            Err(_) => false,
+        },
+        HirKind::Class(Class::Unicode(class)) => {
+            let ranges: Vec<_> = class
+                .ranges()
+                .iter()
+                .map(|range| (u32::from(range.start()), u32::from(range.end())))
+                .collect();
+            match pick(&ranges, random, minimal).and_then(char::from_u32) {
+                Some(character) => {
+                    output.push(character);
+                    output.len() <= settings.limit
+                }
+                None => false,
+            }
+        }
+        HirKind::Class(Class::Bytes(class)) => {
+            let ranges: Vec<_> = class
+                .ranges()
+                .iter()
+                .map(|range| (u32::from(range.start()), u32::from(range.end())))
+                .filter(|(start, _)| *start < 0x80)
+                .map(|(start, end)| (start, end.min(0x7f)))
+                .collect();
+            match pick(&ranges, random, minimal).and_then(char::from_u32) {
+                Some(character) => {
+                    output.push(character);
+                    output.len() <= settings.limit
+                }
+                None => false,
+            }
+        }
+        HirKind::Repetition(repetition) => {
+            let max = repetition
+                .max
+                .unwrap_or(u32::MAX)
+                .min(repetition.min.saturating_add(settings.extra));
+            let count = if settings.shortest {
+                repetition.min
+            } else {
+                #[allow(clippy::cast_possible_truncation)]
+                let count = random.between(u64::from(repetition.min), u64::from(max)) as u32;
+                count
+            };
+            (0..count).all(|_| write(&repetition.sub, random, settings, output))
+        }
+        HirKind::Capture(capture) => write(&capture.sub, random, settings, output),
+        HirKind::Concat(items) => items
+            .iter()
+            .all(|item| write(item, random, settings, output)),
+        HirKind::Alternation(items) => {
+            let idx = if minimal {
+                0
+            } else {
+                random.index(items.len())
+            };
+            write(&items[idx], random, settings, output)
+        }
+    }
+}
+
+/// Pick a character code from the given inclusive ranges. Printable ASCII characters are preferred.
+fn pick(ranges: &[(u32, u32)], random: &mut Random, minimal: bool) -> Option<u32> {
+    let printable: Vec<_> = ranges
+        .iter()
+        .filter(|(start, end)| *start <= 0x7e && *end >= 0x20)
+        .map(|(start, end)| ((*start).max(0x20), (*end).min(0x7e)))
+        .collect();
+    let ranges = if printable.is_empty() {
+        ranges
+    } else {
+        &printable
+    };
+    if ranges.is_empty() {
+        return None;
+    }
+    if minimal {
+        return Some(ranges[0].0);
+    }
+    let (start, end) = ranges[random.index(ranges.len())];
+    // Surrogates are not valid characters
+    let code = random.between(u64::from(start), u64::from(end));
+    match u32::try_from(code) {
+        Ok(code) if char::from_u32(code).is_some() => Some(code),
+        _ => Some(start),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate, generate_sized, Random};
+    use regex::Regex;
+    use test_case::test_case;
+
+    #[test_case("^[a-z]+$", "a"; "class")]
+    #[test_case("^\\d{3}-\\d{2}$", "000-00"; "digits")]
+    #[test_case("^(foo|bar)baz?$", "fooba"; "alternation")]
+    #[test_case("^\\w+@example\\.com$", "A@example.com"; "word")]
+    #[test_case("a", "a"; "unanchored")]
+    #[test_case("^$", ""; "empty")]
+    fn minimal(pattern: &str, expected: &str) {
+        assert_eq!(
+            generate(pattern, &mut Random::new(0), true).unwrap(),
+            expected
+        )
+    }
+
+    #[test_case("^[a-z]+$"; "class")]
+    #[test_case("^\\d{3}-\\d{2}$"; "digits")]
+    #[test_case("^(foo|bar)baz?$"; "alternation")]
+    #[test_case("^(?i)[A-F0-9]{8}$"; "case insensitive")]
+    #[test_case("^\\S+\\s\\S+$"; "whitespace")]
+    #[test_case("^[^a-z]{2,}$"; "negated class")]
+    #[test_case("^.?[😀-😃]$"; "non ascii")]
+    fn random(pattern: &str) {
+        let regex = Regex::new(pattern).unwrap();
+        let mut random = Random::new(42);
+        for _ in 0..20 {
+            let generated = generate(pattern, &mut random, false).unwrap();
+            assert!(regex.is_match(&generated), "{}", generated);
+        }
+    }
+
+    #[test_case("^a+$", 3, 3, "aaa"; "stretched")]
+    #[test_case("^[a-z]{2,}$", 5, 6, "aaaaa"; "bounded")]
+    #[test_case("^(foo|bar)$", 0, 3, "foo"; "fits")]
+    fn sized(pattern: &str, min_length: u64, max_length: u64, expected: &str) {
+        let generated =
+            generate_sized(pattern, &mut Random::new(0), true, min_length, max_length).unwrap();
+        assert_eq!(generated, expected);
+        let mut random = Random::new(42);
+        for _ in 0..20 {
+            let generated =
+                generate_sized(pattern, &mut random, false, min_length, max_length).unwrap();
+            let length = generated.chars().count() as u64;
+            assert!((min_length..=max_length).contains(&length), "{}", generated);
+        }
+    }
+
+    #[test_case("^a{2}$", 3, 5; "too short")]
+    #[test_case("^a+$", 0, 0; "too long")]
+    fn wrong_length(pattern: &str, min_length: u64, max_length: u64) {
+        assert!(
+            generate_sized(pattern, &mut Random::new(0), false, min_length, max_length).is_none()
+        );
+    }
+
+    #[test_case("["; "invalid")]
+    #[test_case("[^\\s\\S]"; "empty class")]
+    fn impossible(pattern: &str) {
+        assert!(generate(pattern, &mut Random::new(0), false).is_none());
+    }
+}
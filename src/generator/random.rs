@@ -0,0 +1,87 @@
+//! A small seeded pseudo-random number generator.
+//!
+//! The `SplitMix64` algorithm is used - it is fast, has a good quality for test data generation and,
+//! most importantly, produces the same sequence for the same seed on every platform and crate version.
+
+#[derive(Debug, Clone)]
+pub(crate) struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub(crate) const fn new(seed: u64) -> Random {
+        Random { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        value ^ (value >> 31)
+    }
+
+    /// A number in the `[0, bound)` range. `bound` should be positive.
+    pub(crate) fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    /// A number in the `[low, high]` range.
+    pub(crate) fn between(&mut self, low: u64, high: u64) -> u64 {
+        if low >= high {
+            low
+        } else {
+            match (high - low).checked_add(1) {
+                Some(size) => low + self.below(size),
+                None => self.next_u64(),
+            }
+        }
+    }
+
+    /// An index in the `[0, length)` range. `length` should be positive.
+    pub(crate) fn index(&mut self, length: usize) -> usize {
+        // `usize` is at most 64 bits wide, the result is always less than `length`
+        #[allow(clippy::cast_possible_truncation)]
+        let index = self.below(length as u64) as usize;
+        index
+    }
+
+    pub(crate) fn boolean(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    /// A number in the `[0, 1)` range.
+    pub(crate) fn unit(&mut self) -> f64 {
+        // 53 random bits fit into the `f64` mantissa
+        #[allow(clippy::cast_precision_loss)]
+        let value = (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64;
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Random;
+
+    #[test]
+    fn deterministic() {
+        let mut first = Random::new(42);
+        let mut second = Random::new(42);
+        for _ in 0..10 {
+            assert_eq!(first.next_u64(), second.next_u64());
+        }
+        assert_ne!(Random::new(1).next_u64(), Random::new(2).next_u64());
+    }
+
+    #[test]
+    fn bounds() {
+        let mut random = Random::new(7);
+        for _ in 0..100 {
+            assert!((3..=5).contains(&random.between(3, 5)));
+            assert!(random.index(4) < 4);
+            let unit = random.unit();
+            assert!((0.0..1.0).contains(&unit));
+        }
+        assert_eq!(random.between(5, 5), 5);
+    }
+}
@@ -0,0 +1,1246 @@
+//! Checking whether every instance valid against one schema is valid against another.
+//!
+//! Both schemas are walked side by side, references are resolved on the fly. Every keyword of the including
+//! schema (`b`) has to be implied by the keywords of the included one (`a`), `anyOf` / `oneOf` branches of `a`
+//! are checked one by one. The check never claims inclusion that doesn't hold, but it might miss some, e.g.
+//! equal `pattern`s are recognized, but not a narrower one.
+//!
+//! To make failures actionable, instances generated from `a` are validated against `b` to find a concrete
+//! counterexample.
+use crate::{
+    compatibility::{Document, Node},
+    compilation::{options::CompilationOptions, JSONSchema},
+    error::ValidationError,
+    generator::InstanceGenerator,
+    keywords::pattern::convert_regex,
+    paths::JSONPointer,
+    satisfiability::{accepts, bounds, has_reference, limit, types, Bound},
+    schemas::Draft,
+};
+use regex::Regex;
+use serde_json::{json, Value};
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashSet},
+    fmt, iter,
+};
+
+/// How many generated instances are checked against `b`.
+const SAMPLES: usize = 64;
+/// How many of the generated instances are mutated to break `b`.
+const MUTATED_SAMPLES: usize = 8;
+/// Subschemas deeper than this level are not compared.
+const MAX_DEPTH: usize = 32;
+
+/// A location where `a` accepts instances that `b` might reject.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Counterexample {
+    /// Location within the instance. `*` stands for any property that is not listed explicitly.
+    pub instance_path: JSONPointer,
+    /// Location of the keyword in `b` that is not implied by `a`.
+    pub schema_path: JSONPointer,
+    /// Human-readable explanation.
+    pub reason: String,
+}
+
+impl fmt::Display for Counterexample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at '{}' (see '{}')",
+            self.reason, self.instance_path, self.schema_path
+        )
+    }
+}
+
+/// The result of `is_subschema`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubschemaCheck {
+    /// Locations where inclusion is not proven, empty if `a` is a subschema of `b`.
+    pub counterexamples: Vec<Counterexample>,
+    /// An instance that is valid against `a` and invalid against `b`, if one was found.
+    pub instance: Option<Value>,
+}
+
+impl SubschemaCheck {
+    /// Whether every instance valid against `a` is valid against `b`.
+    #[must_use]
+    pub fn holds(&self) -> bool {
+        self.counterexamples.is_empty()
+    }
+}
+
+impl fmt::Display for SubschemaCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, counterexample) in self.counterexamples.iter().enumerate() {
+            if idx > 0 {
+                f.write_str("\n")?;
+            }
+            counterexample.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+struct Checker<'a> {
+    a: Document<'a>,
+    b: Document<'a>,
+    // Compared locations are assumed to be included while they are compared, which stops recursion
+    visited: HashSet<(Vec<String>, String)>,
+    counterexamples: Vec<Counterexample>,
+    depth: usize,
+}
+
+impl<'a> Checker<'a> {
+    fn fail(&mut self, instance_path: &JSONPointer, schema_path: &JSONPointer, reason: String) {
+        let counterexample = Counterexample {
+            instance_path: instance_path.clone(),
+            schema_path: schema_path.clone(),
+            reason,
+        };
+        if !self.counterexamples.contains(&counterexample) {
+            self.counterexamples.push(counterexample);
+        }
+    }
+
+    /// Check that `b` accepts every instance that is valid against all `a` parts.
+    fn include(
+        &mut self,
+        a: Vec<Node<'a>>,
+        b: Node<'a>,
+        instance_path: &JSONPointer,
+        schema_path: &JSONPointer,
+    ) -> Result<(), ValidationError<'static>> {
+        let b = self.b.resolve(b)?;
+        let mut parts = Vec::with_capacity(a.len());
+        for node in a {
+            self.flatten(node, &mut parts)?;
+        }
+        // Already flattened parts are flattened again, so their `allOf` members are repeated
+        let mut locations = HashSet::new();
+        parts.retain(|part| locations.insert(part.location.clone()));
+        let is_trivial = match b.schema.as_ref() {
+            Value::Bool(value) => *value,
+            Value::Object(object) => object.is_empty(),
+            _ => true,
+        };
+        if is_trivial
+            || parts
+                .iter()
+                .any(|part| part.schema.as_ref() == &Value::Bool(false))
+        {
+            return Ok(());
+        }
+        let key = (
+            parts.iter().map(|part| part.location.clone()).collect(),
+            b.location.clone(),
+        );
+        if !self.visited.insert(key) {
+            return Ok(());
+        }
+        if self.depth >= MAX_DEPTH {
+            self.fail(
+                instance_path,
+                schema_path,
+                "Schemas are nested too deep to be compared".to_string(),
+            );
+            return Ok(());
+        }
+        self.depth += 1;
+        let result = self.include_parts(&parts, &b, instance_path, schema_path);
+        self.depth -= 1;
+        result
+    }
+
+    /// Whether inclusion is proven. Failures are not reported.
+    fn holds(&mut self, a: Vec<Node<'a>>, b: Node<'a>) -> Result<bool, ValidationError<'static>> {
+        let (visited, count) = (self.visited.clone(), self.counterexamples.len());
+        self.include(a, b, &JSONPointer::default(), &JSONPointer::default())?;
+        if self.counterexamples.len() == count {
+            Ok(true)
+        } else {
+            self.counterexamples.truncate(count);
+            self.visited = visited;
+            Ok(false)
+        }
+    }
+
+    /// Resolve references and inline `allOf` members.
+    fn flatten(
+        &self,
+        node: Node<'a>,
+        parts: &mut Vec<Node<'a>>,
+    ) -> Result<(), ValidationError<'static>> {
+        let node = self.a.resolve(node)?;
+        if let Some(Value::Array(members)) = node.schema.get("allOf") {
+            for idx in 0..members.len() {
+                self.flatten(node.element("allOf", idx), parts)?;
+            }
+        }
+        parts.push(node);
+        Ok(())
+    }
+
+    fn include_parts(
+        &mut self,
+        parts: &[Node<'a>],
+        b: &Node<'a>,
+        instance_path: &JSONPointer,
+        schema_path: &JSONPointer,
+    ) -> Result<(), ValidationError<'static>> {
+        // Every `anyOf` / `oneOf` branch of `a` should be included on its own
+        for (idx, part) in parts.iter().enumerate() {
+            for keyword in &["anyOf", "oneOf"] {
+                let (mut rest, branches) = match (part.schema.as_ref(), part.schema.get(keyword)) {
+                    (Value::Object(object), Some(Value::Array(branches))) => {
+                        (object.clone(), branches.len())
+                    }
+                    _ => continue,
+                };
+                rest.remove(*keyword);
+                let rest = Node {
+                    schema: Cow::Owned(Value::Object(rest)),
+                    scope: part.scope.clone(),
+                    location: format!("{}/-{}", part.location, keyword),
+                };
+                for branch in 0..branches {
+                    let mut a = parts.to_vec();
+                    a[idx] = rest.clone();
+                    a.push(part.element(keyword, branch));
+                    self.include(a, b.clone(), instance_path, schema_path)?;
+                }
+                return Ok(());
+            }
+        }
+        let values = self.values(parts);
+        if let Some(values) = &values {
+            // A finite set of values is checked precisely, unless `b` contains references
+            if !has_reference(&b.schema) {
+                for value in values {
+                    if !accepts(self.b.draft, &b.schema, value) {
+                        self.fail(
+                            instance_path,
+                            schema_path,
+                            format!("{} is valid against `a`, but not against `b`", value),
+                        );
+                    }
+                }
+                return Ok(());
+            }
+        }
+        let object = match b.schema.as_ref() {
+            Value::Object(object) => object,
+            _ => {
+                self.fail(
+                    instance_path,
+                    schema_path,
+                    "`b` rejects every instance".to_string(),
+                );
+                return Ok(());
+            }
+        };
+        let types = self.types(parts, values.as_deref());
+        let allows =
+            |name: &str| types.contains(&name) || (name == "number" && types.contains(&"integer"));
+        let (mut properties_checked, mut items_checked) = (false, false);
+        for (keyword, value) in object {
+            let keyword_path = schema_path.join(keyword.as_str());
+            let keyword = keyword.as_str();
+            if self.b.draft.get_validator(keyword).is_none() {
+                // Annotations and keywords that are validated together with others
+                continue;
+            }
+            let is_implied = |parts: &[Node<'a>]| {
+                parts
+                    .iter()
+                    .any(|part| part.schema.get(keyword) == Some(value))
+            };
+            match keyword {
+                "type" => {
+                    let expected = self::types(self.b.draft, iter::once(b.schema.as_ref()));
+                    for name in &types {
+                        let is_expected = expected.contains(name)
+                            || (*name == "integer" && expected.contains(&"number"));
+                        if !is_expected {
+                            self.fail(
+                                instance_path,
+                                &keyword_path,
+                                format!("`a` accepts {} instances", name),
+                            );
+                        }
+                    }
+                }
+                "enum" | "const" => match &values {
+                    Some(values) => {
+                        let restriction = json!({ keyword: value });
+                        for value in values {
+                            if !accepts(self.b.draft, &restriction, value) {
+                                self.fail(
+                                    instance_path,
+                                    &keyword_path,
+                                    format!("{} is valid against `a`, but not against `b`", value),
+                                );
+                            }
+                        }
+                    }
+                    None => self.fail(
+                        instance_path,
+                        &keyword_path,
+                        format!("`a` is not restricted to values of `{}`", keyword),
+                    ),
+                },
+                "minimum" | "maximum" | "exclusiveMinimum" | "exclusiveMaximum"
+                    if allows("number") =>
+                {
+                    let bound = match value.as_f64() {
+                        Some(bound) => bound,
+                        // Legacy exclusive flags are checked together with `minimum` / `maximum`
+                        None => continue,
+                    };
+                    let is_lower = keyword.ends_with("inimum");
+                    let flag = if is_lower {
+                        "exclusiveMinimum"
+                    } else {
+                        "exclusiveMaximum"
+                    };
+                    let exclusive = keyword.starts_with("exclusive")
+                        || (matches!(self.b.draft, Draft::Draft4 | Draft::OpenAPI30)
+                            && object.get(flag) == Some(&Value::Bool(true)));
+                    let (lower, upper) =
+                        bounds(self.a.draft, parts.iter().map(|part| part.schema.as_ref()));
+                    let actual = if is_lower { lower } else { upper };
+                    if !is_within(actual, (bound, exclusive), is_lower) {
+                        self.fail(
+                            instance_path,
+                            &keyword_path,
+                            format!("`{}` = {} is not guaranteed by `a`", keyword, value),
+                        );
+                    }
+                }
+                "multipleOf" if allows("number") => {
+                    let divisor = value.as_f64().unwrap_or(1.0);
+                    let is_divisible = (divisor == 1.0 && !types.contains(&"number"))
+                        || parts.iter().any(|part| {
+                            matches!(
+                                part.schema.get("multipleOf").and_then(Value::as_f64),
+                                Some(multiple) if (multiple / divisor).fract() == 0.0
+                            )
+                        });
+                    if !is_divisible {
+                        self.fail(
+                            instance_path,
+                            &keyword_path,
+                            format!("`{}` = {} is not guaranteed by `a`", keyword, value),
+                        );
+                    }
+                }
+                "minLength" | "maxLength" | "minItems" | "maxItems" | "minProperties"
+                | "maxProperties" => {
+                    let applies = match keyword {
+                        "minLength" | "maxLength" => allows("string"),
+                        "minItems" | "maxItems" => allows("array"),
+                        _ => allows("object"),
+                    };
+                    let expected = match value.as_u64() {
+                        Some(expected) if applies => expected,
+                        _ => continue,
+                    };
+                    let is_lower = keyword.starts_with("min");
+                    let mut actual = limit(
+                        parts.iter().map(|part| part.schema.as_ref()),
+                        keyword,
+                        is_lower,
+                    );
+                    if keyword == "minProperties" {
+                        let required = required(parts).len() as u64;
+                        actual = Some(actual.map_or(required, |actual| actual.max(required)));
+                    }
+                    let is_implied = if is_lower {
+                        actual.unwrap_or(0) >= expected
+                    } else {
+                        matches!(actual, Some(actual) if actual <= expected)
+                    };
+                    if !is_implied {
+                        self.fail(
+                            instance_path,
+                            &keyword_path,
+                            format!("`{}` = {} is not guaranteed by `a`", keyword, value),
+                        );
+                    }
+                }
+                "pattern" | "format" | "contentMediaType" | "contentEncoding" => {
+                    if allows("string") && !is_implied(parts) {
+                        self.fail(
+                            instance_path,
+                            &keyword_path,
+                            format!("`{}` = {} is not guaranteed by `a`", keyword, value),
+                        );
+                    }
+                }
+                "required" if allows("object") => {
+                    let actual = required(parts);
+                    for name in value
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(Value::as_str)
+                    {
+                        if !actual.contains(name) {
+                            self.fail(
+                                &instance_path.join(name),
+                                &keyword_path,
+                                format!("`a` doesn't require property '{}'", name),
+                            );
+                        }
+                    }
+                }
+                "dependencies" if allows("object") => {
+                    let actual = required(parts);
+                    for (name, dependency) in value.as_object().into_iter().flatten() {
+                        let is_implied = parts.iter().any(|part| {
+                            part.schema
+                                .get("dependencies")
+                                .and_then(|value| value.get(name))
+                                == Some(dependency)
+                        }) || matches!(dependency, Value::Array(names) if names
+                            .iter()
+                            .filter_map(Value::as_str)
+                            .all(|name| actual.contains(name)));
+                        if !is_implied {
+                            self.fail(
+                                instance_path,
+                                &keyword_path.join(name.as_str()),
+                                format!("Dependency of '{}' is not guaranteed by `a`", name),
+                            );
+                        }
+                    }
+                }
+                "propertyNames" if allows("object") => {
+                    self.property_names(parts, b, instance_path, &keyword_path)?
+                }
+                "properties" | "patternProperties" | "additionalProperties" if allows("object") => {
+                    if !properties_checked {
+                        properties_checked = true;
+                        self.properties(parts, b, instance_path, schema_path)?;
+                    }
+                }
+                "items" | "additionalItems" if allows("array") => {
+                    if !items_checked {
+                        items_checked = true;
+                        self.items(parts, b, instance_path, schema_path)?;
+                    }
+                }
+                "contains" if allows("array") => {
+                    let expected = b.keyword("contains");
+                    let mut is_implied = false;
+                    for part in parts {
+                        if part.schema.get("contains").is_some()
+                            && self.holds(vec![part.keyword("contains")], expected.clone())?
+                        {
+                            is_implied = true;
+                            break;
+                        }
+                    }
+                    // Non-empty arrays where every item matches
+                    let is_tuple = parts
+                        .iter()
+                        .any(|part| matches!(part.schema.get("items"), Some(Value::Array(_))));
+                    let min_items = limit(
+                        parts.iter().map(|part| part.schema.as_ref()),
+                        "minItems",
+                        true,
+                    );
+                    if !is_implied && !is_tuple && min_items.unwrap_or(0) > 0 {
+                        let items = parts
+                            .iter()
+                            .filter(|part| part.schema.get("items").is_some())
+                            .map(|part| part.keyword("items"))
+                            .collect();
+                        is_implied = self.holds(items, expected)?;
+                    }
+                    if !is_implied {
+                        self.fail(
+                            instance_path,
+                            &keyword_path,
+                            "`contains` is not guaranteed by `a`".to_string(),
+                        );
+                    }
+                }
+                "uniqueItems" if allows("array") && value == &Value::Bool(true) => {
+                    let max_items = limit(
+                        parts.iter().map(|part| part.schema.as_ref()),
+                        "maxItems",
+                        false,
+                    );
+                    if !is_implied(parts) && !matches!(max_items, Some(max_items) if max_items <= 1)
+                    {
+                        self.fail(
+                            instance_path,
+                            &keyword_path,
+                            "`uniqueItems` is not guaranteed by `a`".to_string(),
+                        );
+                    }
+                }
+                "allOf" => {
+                    for idx in 0..value.as_array().map_or(0, Vec::len) {
+                        self.include(
+                            parts.to_vec(),
+                            b.element("allOf", idx),
+                            instance_path,
+                            &keyword_path.join(idx),
+                        )?;
+                    }
+                }
+                "anyOf" => {
+                    let mut is_included = false;
+                    for idx in 0..value.as_array().map_or(0, Vec::len) {
+                        if self.holds(parts.to_vec(), b.element("anyOf", idx))? {
+                            is_included = true;
+                            break;
+                        }
+                    }
+                    if !is_included {
+                        self.fail(
+                            instance_path,
+                            &keyword_path,
+                            "`a` is not included in any `anyOf` branch".to_string(),
+                        );
+                    }
+                }
+                "oneOf" => {
+                    let branches = value.as_array().map_or(0, Vec::len);
+                    let mut is_included = false;
+                    for idx in 0..branches {
+                        if self.holds(parts.to_vec(), b.element("oneOf", idx))? {
+                            let mut is_exclusive = true;
+                            for other in (0..branches).filter(|other| *other != idx) {
+                                if !self.is_disjoint(parts, b.element("oneOf", other))? {
+                                    is_exclusive = false;
+                                    break;
+                                }
+                            }
+                            if is_exclusive {
+                                is_included = true;
+                                break;
+                            }
+                        }
+                    }
+                    if !is_included {
+                        self.fail(
+                            instance_path,
+                            &keyword_path,
+                            "`a` is not included in exactly one `oneOf` branch".to_string(),
+                        );
+                    }
+                }
+                "not" => {
+                    if !is_implied(parts) && !self.is_disjoint(parts, b.keyword("not"))? {
+                        self.fail(
+                            instance_path,
+                            &keyword_path,
+                            "`a` accepts instances that are valid against `not`".to_string(),
+                        );
+                    }
+                }
+                "if" => self.condition(parts, b, instance_path, schema_path)?,
+                "nullable"
+                | "minimum"
+                | "maximum"
+                | "exclusiveMinimum"
+                | "exclusiveMaximum"
+                | "multipleOf"
+                | "required"
+                | "dependencies"
+                | "propertyNames"
+                | "properties"
+                | "patternProperties"
+                | "additionalProperties"
+                | "items"
+                | "additionalItems"
+                | "contains"
+                | "uniqueItems" => {
+                    // `nullable` is checked with `type`, others apply to types that `a` doesn't allow
+                }
+                _ => {
+                    if !is_implied(parts) {
+                        self.fail(
+                            instance_path,
+                            &keyword_path,
+                            format!("`{}` is not guaranteed by `a`", keyword),
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn condition(
+        &mut self,
+        parts: &[Node<'a>],
+        b: &Node<'a>,
+        instance_path: &JSONPointer,
+        schema_path: &JSONPointer,
+    ) -> Result<(), ValidationError<'static>> {
+        let (condition, then, otherwise) = (b.keyword("if"), b.keyword("then"), b.keyword("else"));
+        let is_same = parts.iter().any(|part| {
+            ["if", "then", "else"]
+                .iter()
+                .all(|keyword| part.schema.get(keyword) == b.schema.get(keyword))
+        });
+        if is_same
+            || (self.holds(parts.to_vec(), then.clone())?
+                && self.holds(parts.to_vec(), otherwise.clone())?)
+        {
+            return Ok(());
+        }
+        if self.holds(parts.to_vec(), condition.clone())? {
+            self.include(
+                parts.to_vec(),
+                then,
+                instance_path,
+                &schema_path.join("then"),
+            )
+        } else if self.is_disjoint(parts, condition)? {
+            self.include(
+                parts.to_vec(),
+                otherwise,
+                instance_path,
+                &schema_path.join("else"),
+            )
+        } else {
+            self.fail(
+                instance_path,
+                &schema_path.join("if"),
+                "`a` is not known to be within either `then` or `else`".to_string(),
+            );
+            Ok(())
+        }
+    }
+
+    fn property_names(
+        &mut self,
+        parts: &[Node<'a>],
+        b: &Node<'a>,
+        instance_path: &JSONPointer,
+        schema_path: &JSONPointer,
+    ) -> Result<(), ValidationError<'static>> {
+        let names: Vec<_> = parts
+            .iter()
+            .filter(|part| part.schema.get("propertyNames").is_some())
+            .map(|part| part.keyword("propertyNames"))
+            .collect();
+        if !names.is_empty() {
+            return self.include(
+                names,
+                b.keyword("propertyNames"),
+                instance_path,
+                schema_path,
+            );
+        }
+        // Only declared properties are allowed
+        let closed = parts.iter().find(|part| {
+            part.schema.get("additionalProperties") == Some(&Value::Bool(false))
+                && part.schema.get("patternProperties").is_none()
+        });
+        let expected = b.keyword("propertyNames");
+        match closed {
+            Some(closed) if !has_reference(&expected.schema) => {
+                let declared = closed.schema.get("properties").and_then(Value::as_object);
+                for name in declared.into_iter().flat_map(|declared| declared.keys()) {
+                    if !accepts(self.b.draft, &expected.schema, &Value::String(name.clone())) {
+                        self.fail(
+                            &instance_path.join(name.as_str()),
+                            schema_path,
+                            format!("Property name '{}' is rejected by `propertyNames`", name),
+                        );
+                    }
+                }
+            }
+            _ => self.fail(
+                instance_path,
+                schema_path,
+                "`a` doesn't restrict property names".to_string(),
+            ),
+        }
+        Ok(())
+    }
+
+    fn properties(
+        &mut self,
+        parts: &[Node<'a>],
+        b: &Node<'a>,
+        instance_path: &JSONPointer,
+        schema_path: &JSONPointer,
+    ) -> Result<(), ValidationError<'static>> {
+        let expected_patterns: Vec<String> = keys(&b.schema, "patternProperties");
+        let mut names: BTreeSet<String> = keys(&b.schema, "properties").into_iter().collect();
+        for part in parts {
+            names.extend(keys(&part.schema, "properties"));
+        }
+        // Explicitly listed properties
+        for name in &names {
+            let mut expected = Vec::new();
+            if b.schema
+                .get("properties")
+                .and_then(|value| value.get(name))
+                .is_some()
+            {
+                expected.push((
+                    b.keyword("properties").keyword(name),
+                    schema_path.join("properties").join(name.as_str()),
+                ));
+            }
+            for pattern in &expected_patterns {
+                match Regex::new(&convert_regex(pattern)) {
+                    Ok(regex) if !regex.is_match(name) => {}
+                    Ok(_) => expected.push((
+                        b.keyword("patternProperties").keyword(pattern),
+                        schema_path.join("patternProperties").join(pattern.as_str()),
+                    )),
+                    Err(_) => self.fail(
+                        &instance_path.join(name.as_str()),
+                        &schema_path.join("patternProperties").join(pattern.as_str()),
+                        format!("Unsupported pattern '{}'", pattern),
+                    ),
+                }
+            }
+            if expected.is_empty() && b.schema.get("additionalProperties").is_some() {
+                expected.push((
+                    b.keyword("additionalProperties"),
+                    schema_path.join("additionalProperties"),
+                ));
+            }
+            let actual = property(parts, name);
+            for (expected, path) in expected {
+                self.include(
+                    actual.clone(),
+                    expected,
+                    &instance_path.join(name.as_str()),
+                    &path,
+                )?;
+            }
+        }
+        // Other properties matching a pattern
+        for pattern in &expected_patterns {
+            let actual = parts
+                .iter()
+                .filter_map(|part| {
+                    if part
+                        .schema
+                        .get("patternProperties")
+                        .and_then(|value| value.get(pattern))
+                        .is_some()
+                    {
+                        Some(part.keyword("patternProperties").keyword(pattern))
+                    } else if part.schema.get("patternProperties").is_none()
+                        && part.schema.get("additionalProperties").is_some()
+                    {
+                        Some(part.keyword("additionalProperties"))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            self.include(
+                actual,
+                b.keyword("patternProperties").keyword(pattern),
+                &instance_path.join(pattern.as_str()),
+                &schema_path.join("patternProperties").join(pattern.as_str()),
+            )?;
+        }
+        // All other properties
+        if b.schema.get("additionalProperties").is_some() {
+            let actual = parts
+                .iter()
+                .filter(|part| {
+                    part.schema.get("additionalProperties").is_some()
+                        && keys(&part.schema, "patternProperties")
+                            .iter()
+                            .all(|pattern| expected_patterns.contains(pattern))
+                })
+                .map(|part| part.keyword("additionalProperties"))
+                .collect();
+            self.include(
+                actual,
+                b.keyword("additionalProperties"),
+                &instance_path.join("*"),
+                &schema_path.join("additionalProperties"),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn items(
+        &mut self,
+        parts: &[Node<'a>],
+        b: &Node<'a>,
+        instance_path: &JSONPointer,
+        schema_path: &JSONPointer,
+    ) -> Result<(), ValidationError<'static>> {
+        let max_items = limit(
+            parts.iter().map(|part| part.schema.as_ref()),
+            "maxItems",
+            false,
+        );
+        let is_reachable =
+            |idx: usize| !matches!(max_items, Some(max_items) if idx as u64 >= max_items);
+        // Positions after the longest tuple are all the same
+        let length = parts
+            .iter()
+            .filter_map(|part| {
+                part.schema
+                    .get("items")
+                    .and_then(Value::as_array)
+                    .map(Vec::len)
+            })
+            .max()
+            .unwrap_or(0);
+        match b.schema.get("items") {
+            Some(Value::Array(expected)) => {
+                for idx in (0..expected.len()).filter(|idx| is_reachable(*idx)) {
+                    self.include(
+                        item(parts, idx),
+                        b.element("items", idx),
+                        &instance_path.join(idx),
+                        &schema_path.join("items").join(idx),
+                    )?;
+                }
+                if b.schema.get("additionalItems").is_some() {
+                    for idx in (expected.len()..=length.max(expected.len()))
+                        .filter(|idx| is_reachable(*idx))
+                    {
+                        self.include(
+                            item(parts, idx),
+                            b.keyword("additionalItems"),
+                            &instance_path.join(idx),
+                            &schema_path.join("additionalItems"),
+                        )?;
+                    }
+                }
+            }
+            Some(_) => {
+                for idx in (0..=length).filter(|idx| is_reachable(*idx)) {
+                    self.include(
+                        item(parts, idx),
+                        b.keyword("items"),
+                        &instance_path.join(idx),
+                        &schema_path.join("items"),
+                    )?;
+                }
+            }
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Whether no instance is valid against both `a` and `b`.
+    fn is_disjoint(
+        &self,
+        parts: &[Node<'a>],
+        b: Node<'a>,
+    ) -> Result<bool, ValidationError<'static>> {
+        let b = self.b.resolve(b)?;
+        if b.schema.as_ref() == &Value::Bool(false) {
+            return Ok(true);
+        }
+        let values = self.values(parts);
+        let actual = self.types(parts, values.as_deref());
+        let expected = types(self.b.draft, iter::once(b.schema.as_ref()));
+        let overlaps = actual.iter().any(|name| {
+            expected.contains(name)
+                || (*name == "integer" && expected.contains(&"number"))
+                || (*name == "number" && expected.contains(&"integer"))
+        });
+        if !overlaps {
+            return Ok(true);
+        }
+        if let Some(values) = values {
+            if !has_reference(&b.schema) {
+                return Ok(values
+                    .iter()
+                    .all(|value| !accepts(self.b.draft, &b.schema, value)));
+            }
+        }
+        let expected_values = match (b.schema.get("const"), b.schema.get("enum")) {
+            (Some(value), _) if self.b.draft.get_validator("const").is_some() => {
+                vec![value.clone()]
+            }
+            (_, Some(Value::Array(values))) => values.clone(),
+            _ => return Ok(false),
+        };
+        Ok(expected_values.iter().all(|value| {
+            !parts
+                .iter()
+                .all(|part| accepts(self.a.draft, &part.schema, value))
+        }))
+    }
+
+    /// Values allowed by `const` / `enum` of `a` that are valid against all parts.
+    fn values(&self, parts: &[Node<'a>]) -> Option<Vec<Value>> {
+        let has_const = self.a.draft.get_validator("const").is_some();
+        let candidates = parts
+            .iter()
+            .filter_map(
+                |part| match (part.schema.get("const"), part.schema.get("enum")) {
+                    (Some(value), _) if has_const => Some(std::slice::from_ref(value)),
+                    (_, Some(Value::Array(values))) => Some(values.as_slice()),
+                    _ => None,
+                },
+            )
+            .min_by_key(|values| values.len())?;
+        Some(
+            candidates
+                .iter()
+                .filter(|value| {
+                    parts
+                        .iter()
+                        .all(|part| accepts(self.a.draft, &part.schema, value))
+                })
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Types of instances valid against `a`, `integer` is present only if `number` is not.
+    fn types(&self, parts: &[Node<'a>], values: Option<&[Value]>) -> Vec<&'static str> {
+        match values {
+            Some(values) => {
+                let mut names: Vec<&str> = values.iter().map(type_of).collect();
+                if names.contains(&"number") {
+                    names.retain(|name| *name != "integer");
+                }
+                names.sort_unstable();
+                names.dedup();
+                names
+            }
+            None => types(self.a.draft, parts.iter().map(|part| part.schema.as_ref())),
+        }
+    }
+}
+
+/// Whether `actual` bound is at least as tight as `expected`.
+fn is_within(actual: Option<Bound>, expected: Bound, is_lower: bool) -> bool {
+    match actual {
+        Some((value, exclusive)) => {
+            let is_tighter = if is_lower {
+                value > expected.0
+            } else {
+                value < expected.0
+            };
+            is_tighter || (value == expected.0 && (exclusive || !expected.1))
+        }
+        None => false,
+    }
+}
+
+fn type_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) => {
+            if number.is_i64()
+                || number.is_u64()
+                || matches!(number.as_f64(), Some(number) if number.fract() == 0.0)
+            {
+                "integer"
+            } else {
+                "number"
+            }
+        }
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn keys(schema: &Value, keyword: &str) -> Vec<String> {
+    schema
+        .get(keyword)
+        .and_then(Value::as_object)
+        .map(|object| object.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Names of properties required by any part.
+fn required<'p>(parts: &'p [Node<'_>]) -> BTreeSet<&'p str> {
+    parts
+        .iter()
+        .filter_map(|part| part.schema.get("required").and_then(Value::as_array))
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect()
+}
+
+/// Subschemas that apply to property `name` in all parts.
+fn property<'a>(parts: &[Node<'a>], name: &str) -> Vec<Node<'a>> {
+    let mut schemas = Vec::new();
+    for part in parts {
+        let mut is_declared = false;
+        if part
+            .schema
+            .get("properties")
+            .and_then(|value| value.get(name))
+            .is_some()
+        {
+            is_declared = true;
+            schemas.push(part.keyword("properties").keyword(name));
+        }
+        for pattern in keys(&part.schema, "patternProperties") {
+            match Regex::new(&convert_regex(&pattern)) {
+                Ok(regex) if !regex.is_match(name) => {}
+                Ok(_) => {
+                    is_declared = true;
+                    schemas.push(part.keyword("patternProperties").keyword(&pattern));
+                }
+                // Ignoring a constraint of `a` makes it only broader
+                Err(_) => is_declared = true,
+            }
+        }
+        if !is_declared && part.schema.get("additionalProperties").is_some() {
+            schemas.push(part.keyword("additionalProperties"));
+        }
+    }
+    schemas
+}
+
+/// Subschemas that apply to the item at `idx` in all parts.
+fn item<'a>(parts: &[Node<'a>], idx: usize) -> Vec<Node<'a>> {
+    parts
+        .iter()
+        .filter_map(|part| match part.schema.get("items") {
+            Some(Value::Array(items)) if idx < items.len() => Some(part.element("items", idx)),
+            Some(Value::Array(_)) => part
+                .schema
+                .get("additionalItems")
+                .map(|_| part.keyword("additionalItems")),
+            Some(_) => Some(part.keyword("items")),
+            None => None,
+        })
+        .collect()
+}
+
+impl CompilationOptions {
+    /// Check whether `a` is a subschema of `b`, i.e. every instance valid against `a` is valid against `b`.
+    /// See `is_subschema` for details.
+    pub fn is_subschema(
+        &self,
+        a: &Value,
+        b: &Value,
+    ) -> Result<SubschemaCheck, ValidationError<'static>> {
+        let mut checker = Checker {
+            a: Document::new(self, a)?,
+            b: Document::new(self, b)?,
+            visited: HashSet::new(),
+            counterexamples: Vec::new(),
+            depth: 0,
+        };
+        let (a_root, b_root) = (checker.a.root_node()?, checker.b.root_node()?);
+        let root = JSONPointer::default();
+        checker.include(vec![a_root], b_root, &root, &root)?;
+        let mut counterexamples = checker.counterexamples;
+        let (a, b) = (self.compile(a)?, self.compile(b)?);
+        let generator = InstanceGenerator::new(&a, 0);
+        let samples: Vec<Value> = generator
+            .minimal()
+            .into_iter()
+            .chain(generator.take(SAMPLES))
+            .collect();
+        // Instances of `a` that break one constraint of `b` are more likely to be rejected only by `b`
+        let mutator = InstanceGenerator::new(&b, 0);
+        let instance = samples
+            .iter()
+            .find(|instance| !b.is_valid(instance))
+            .cloned()
+            .or_else(|| {
+                samples
+                    .iter()
+                    .take(MUTATED_SAMPLES)
+                    .flat_map(|instance| mutator.mutate(instance))
+                    .map(|mutation| mutation.instance)
+                    .find(|instance| a.is_valid(instance))
+            });
+        if let Some(instance) = &instance {
+            if counterexamples.is_empty() {
+                // The instance is rejected by something that the check doesn't know about
+                if let Err(errors) = b.validate(instance) {
+                    counterexamples.extend(errors.map(|error| Counterexample {
+                        instance_path: error.instance_path.clone(),
+                        schema_path: JSONPointer::default(),
+                        reason: error.to_string(),
+                    }));
+                }
+            }
+        }
+        Ok(SubschemaCheck {
+            counterexamples,
+            instance,
+        })
+    }
+}
+
+/// Check whether `a` is a subschema of `b`, i.e. every instance valid against `a` is valid against `b`.
+///
+/// Types, numeric and length limits, `enum` / `const`, object properties, arrays and composition keywords
+/// are compared. Keywords that can't be compared precisely, e.g. different `pattern`s, are reported as
+/// counterexamples, so a failed check doesn't always mean that such instances exist. In addition,
+/// instances generated from `a` are validated against `b` to find a concrete counterexample.
+///
+/// ```rust
+/// # use jsonschema::is_subschema;
+/// # use serde_json::json;
+/// let producer = json!({
+///     "type": "object",
+///     "properties": {
+///         "id": {"type": "integer", "minimum": 1},
+///         "name": {"type": "string", "maxLength": 100}
+///     },
+///     "required": ["id", "name"],
+///     "additionalProperties": false
+/// });
+/// let consumer = json!({
+///     "type": "object",
+///     "properties": {
+///         "id": {"type": "number", "minimum": 0},
+///         "name": {"type": "string", "maxLength": 50}
+///     },
+///     "required": ["id"]
+/// });
+/// let check = is_subschema(&producer, &consumer).expect("Valid references");
+/// assert!(!check.holds());
+/// assert_eq!(
+///     check.to_string(),
+///     "`maxLength` = 50 is not guaranteed by `a` at '/name' (see '/properties/name/maxLength')"
+/// );
+/// assert!(check.instance.is_some());
+/// ```
+pub fn is_subschema(a: &Value, b: &Value) -> Result<SubschemaCheck, ValidationError<'static>> {
+    JSONSchema::options().is_subschema(a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_subschema;
+    use crate::{compilation::JSONSchema, schemas::Draft};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(json!({"type": "integer"}), json!({"type": "number"}); "integer in number")]
+    #[test_case(json!({"type": "integer", "minimum": 1, "maximum": 10}), json!({"minimum": 0, "maximum": 10}); "numeric range")]
+    #[test_case(json!({"type": "number", "exclusiveMinimum": 1}), json!({"minimum": 1}); "exclusive bound")]
+    #[test_case(json!({"enum": ["a", "b"]}), json!({"type": "string", "maxLength": 1}); "enum values")]
+    #[test_case(json!({"const": 1}), json!({"enum": [1, 2]}); "constant")]
+    #[test_case(json!({"type": "string", "minLength": 2, "maxLength": 5}), json!({"type": "string", "maxLength": 10}); "length")]
+    #[test_case(
+        json!({"type": "object", "properties": {"a": {"type": "integer"}}, "required": ["a"], "additionalProperties": false}),
+        json!({"type": "object", "properties": {"a": {"type": "number"}}, "required": ["a"]});
+        "properties"
+    )]
+    #[test_case(json!({"type": "object", "additionalProperties": {"type": "integer"}}), json!({"additionalProperties": {"type": "number"}}); "additional properties")]
+    #[test_case(
+        json!({"patternProperties": {"^x-": {"type": "string"}}, "additionalProperties": false}),
+        json!({"patternProperties": {"^x-": {}}, "additionalProperties": false});
+        "pattern properties"
+    )]
+    #[test_case(json!({"type": "array", "items": {"type": "integer"}, "maxItems": 3}), json!({"type": "array", "items": {"type": "number"}, "maxItems": 5}); "items")]
+    #[test_case(json!({"items": [{"type": "integer"}], "additionalItems": false}), json!({"items": {"type": "integer"}}); "tuple")]
+    #[test_case(json!({"anyOf": [{"type": "integer"}, {"type": "string"}]}), json!({"type": ["integer", "string"]}); "any of in a")]
+    #[test_case(json!({"type": "integer"}), json!({"anyOf": [{"type": "string"}, {"type": "number"}]}); "any of in b")]
+    #[test_case(json!({"type": "integer"}), json!({"oneOf": [{"type": "string"}, {"type": "number"}]}); "one of in b")]
+    #[test_case(json!({"allOf": [{"type": "string"}, {"maxLength": 3}]}), json!({"type": "string", "maxLength": 5}); "all of")]
+    #[test_case(json!({"definitions": {"i": {"type": "integer"}}, "$ref": "#/definitions/i"}), json!({"type": "number"}); "reference")]
+    #[test_case(
+        json!({"type": "object", "properties": {"next": {"$ref": "#"}}, "additionalProperties": false}),
+        json!({"type": "object", "properties": {"next": {"$ref": "#"}}});
+        "recursion"
+    )]
+    #[test_case(json!({"type": "integer", "multipleOf": 4}), json!({"multipleOf": 2}); "multiple of")]
+    #[test_case(json!(false), json!({"type": "string"}); "false schema")]
+    #[test_case(json!({"type": "integer"}), json!({"not": {"type": "string"}}); "not")]
+    #[test_case(json!({"type": "array", "maxItems": 1}), json!({"uniqueItems": true}); "unique items")]
+    #[test_case(json!({"type": "string", "pattern": "^a"}), json!({"pattern": "^a"}); "same pattern")]
+    #[test_case(json!({"type": "array", "items": {"const": 1}, "minItems": 1}), json!({"contains": {"type": "integer"}}); "contains")]
+    #[test_case(json!({"properties": {"ab": {}}, "additionalProperties": false}), json!({"propertyNames": {"maxLength": 2}}); "property names")]
+    #[test_case(json!({"type": "integer", "minimum": 5}), json!({"if": {"minimum": 0}, "then": {"minimum": 1}}); "condition")]
+    fn holds(a: Value, b: Value) {
+        let check = is_subschema(&a, &b).expect("Valid schemas");
+        assert_eq!(check.counterexamples, vec![]);
+        assert_eq!(check.instance, None);
+    }
+
+    #[test_case(json!({"type": ["integer", "string"]}), json!({"type": "integer"}), "", "/type"; "wrong type")]
+    #[test_case(json!({"type": "integer", "maximum": 10}), json!({"maximum": 5}), "", "/maximum"; "maximum")]
+    #[test_case(json!({"type": "number", "exclusiveMinimum": 0}), json!({"exclusiveMinimum": 1}), "", "/exclusiveMinimum"; "exclusive minimum")]
+    #[test_case(json!({"enum": ["a", "bc"]}), json!({"maxLength": 1}), "", ""; "enum values")]
+    #[test_case(json!({"type": "object"}), json!({"required": ["a"]}), "/a", "/required"; "required")]
+    #[test_case(
+        json!({"properties": {"a": {"type": "string"}}}),
+        json!({"properties": {"a": {"type": "integer"}}}),
+        "/a", "/properties/a/type";
+        "property"
+    )]
+    #[test_case(json!({"type": "object"}), json!({"additionalProperties": false}), "/*", "/additionalProperties"; "additional properties")]
+    #[test_case(json!({"type": "array", "items": {"type": "string"}}), json!({"items": {"type": "integer"}}), "/0", "/items/type"; "items")]
+    #[test_case(json!({"type": "boolean"}), json!({"anyOf": [{"type": "string"}, {"type": "number"}]}), "", "/anyOf"; "any of")]
+    #[test_case(json!({"anyOf": [{"type": "integer"}, {"type": "string"}]}), json!({"type": "integer"}), "", "/type"; "branch of a")]
+    #[test_case(json!({"type": "integer"}), json!({"oneOf": [{"type": "number"}, {"minimum": 0}]}), "", "/oneOf"; "overlapping one of")]
+    #[test_case(json!({"type": "string", "pattern": "^ab"}), json!({"pattern": "^a"}), "", "/pattern"; "different pattern")]
+    #[test_case(
+        json!({"type": "string"}),
+        json!({"definitions": {"i": {"type": "integer"}}, "$ref": "#/definitions/i"}),
+        "", "/type";
+        "reference"
+    )]
+    fn fails(a: Value, b: Value, instance_path: &str, schema_path: &str) {
+        let check = is_subschema(&a, &b).expect("Valid schemas");
+        let paths: Vec<_> = check
+            .counterexamples
+            .iter()
+            .map(|counterexample| {
+                (
+                    counterexample.instance_path.to_string(),
+                    counterexample.schema_path.to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            paths,
+            vec![(instance_path.to_string(), schema_path.to_string())]
+        );
+        if let Some(instance) = check.instance {
+            assert!(JSONSchema::compile(&a)
+                .expect("Valid schema")
+                .is_valid(&instance));
+            assert!(!JSONSchema::compile(&b)
+                .expect("Valid schema")
+                .is_valid(&instance));
+        }
+    }
+
+    #[test]
+    fn instance() {
+        let a = json!({"type": "object", "properties": {"age": {"type": "integer", "minimum": 0}}, "required": ["age"]});
+        let b =
+            json!({"type": "object", "properties": {"age": {"type": "integer", "minimum": 18}}});
+        let check = is_subschema(&a, &b).expect("Valid schemas");
+        assert!(!check.holds());
+        assert_eq!(
+            check.to_string(),
+            "`minimum` = 18 is not guaranteed by `a` at '/age' (see '/properties/age/minimum')"
+        );
+        let instance = check.instance.expect("Counterexample is found");
+        assert!(instance["age"].as_i64().expect("Integer") < 18);
+    }
+
+    #[test]
+    fn openapi() {
+        let mut options = JSONSchema::options();
+        options.with_draft(Draft::OpenAPI30);
+        let (a, b) = (
+            json!({"type": "string"}),
+            json!({"type": "string", "nullable": true}),
+        );
+        assert!(options.is_subschema(&a, &b).expect("Valid schemas").holds());
+        assert!(!options.is_subschema(&b, &a).expect("Valid schemas").holds());
+    }
+}
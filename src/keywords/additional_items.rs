@@ -3,8 +3,9 @@ use crate::{
     error::{no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::{
         boolean::{FalseValidator, TrueValidator},
-        format_validators, CompilationResult, Validators,
+        format_validators, CompilationResult, Subschemas, Validators,
     },
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -28,6 +29,13 @@ impl AdditionalItemsObjectValidator {
     }
 }
 impl Validate for AdditionalItemsObjectValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        vec![(
+            JSONPointer::from(&["additionalItems"][..]),
+            &self.validators,
+        )]
+    }
+
     #[inline]
     fn is_valid_array(&self, schema: &JSONSchema, _: &Value, instance_array: &[Value]) -> bool {
         instance_array.iter().skip(self.items_count).all(|item| {
@@ -47,28 +55,35 @@ impl Validate for AdditionalItemsObjectValidator {
 
     #[inline]
     fn validate_array<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_array: &'a [Value],
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
+        let instance_path = instance_path.clone();
         Box::new(
             instance_array
                 .iter()
+                .enumerate()
                 .skip(self.items_count)
-                .flat_map(|item| {
+                .flat_map(move |(idx, item)| {
+                    let item_path = instance_path.push(idx);
                     self.validators
                         .iter()
-                        .flat_map(move |validator| validator.validate(schema, item))
-                })
-                .collect::<Vec<_>>()
-                .into_iter(),
+                        .flat_map(move |validator| validator.validate(schema, item, &item_path))
+                }),
         )
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Array(instance_value) = instance {
-            self.validate_array(schema, instance, instance_value)
+            self.validate_array(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -91,8 +106,12 @@ impl AdditionalItemsBooleanValidator {
 }
 impl Validate for AdditionalItemsBooleanValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::additional_items(instance, self.items_count)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::additional_items(instance_path.into(), instance, self.items_count)
     }
 
     #[inline]
@@ -1,7 +1,8 @@
 use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
-    keywords::{format_validators, CompilationResult, Validators},
+    keywords::{format_validators, CompilationResult, Subschemas, Validators},
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use regex::Regex;
@@ -20,6 +21,13 @@ impl AdditionalPropertiesValidator {
     }
 }
 impl Validate for AdditionalPropertiesValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        vec![(
+            JSONPointer::from(&["additionalProperties"][..]),
+            &self.validators,
+        )]
+    }
+
     #[inline]
     fn is_valid_object(
         &self,
@@ -44,27 +52,29 @@ impl Validate for AdditionalPropertiesValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
-        Box::new(
+        let instance_path = instance_path.clone();
+        Box::new(instance_value.iter().flat_map(move |(property, value)| {
+            let property_path = instance_path.push(property.clone());
             self.validators
                 .iter()
-                .flat_map(move |validator| {
-                    instance_value
-                        .values()
-                        .flat_map(move |value| validator.validate(schema, value))
-                })
-                .collect::<Vec<_>>()
-                .into_iter(),
-        )
+                .flat_map(move |validator| validator.validate(schema, value, &property_path))
+        }))
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
-            self.validate_object(schema, instance, instance_value)
+            self.validate_object(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -88,8 +98,12 @@ impl AdditionalPropertiesFalseValidator {
 }
 impl Validate for AdditionalPropertiesFalseValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::false_schema(instance)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::false_schema(instance_path.into(), instance)
     }
 
     #[inline]
@@ -154,10 +168,11 @@ impl Validate for AdditionalPropertiesNotEmptyFalseValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         _: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
         instance_value
             .keys()
@@ -168,7 +183,11 @@ impl Validate for AdditionalPropertiesNotEmptyFalseValidator {
                     // No extra properties are allowed
                     let property_value = Value::String(property.to_string());
                     Some(error(
-                        ValidationError::false_schema(&property_value).into_owned(),
+                        ValidationError::false_schema(
+                            instance_path.push(property.clone()).into(),
+                            &property_value,
+                        )
+                        .into_owned(),
                     ))
                 }
             })
@@ -176,9 +195,14 @@ impl Validate for AdditionalPropertiesNotEmptyFalseValidator {
             .unwrap_or_else(no_error)
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
-            self.validate_object(schema, instance, instance_value)
+            self.validate_object(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -212,6 +236,13 @@ impl AdditionalPropertiesNotEmptyValidator {
     }
 }
 impl Validate for AdditionalPropertiesNotEmptyValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        vec![(
+            JSONPointer::from(&["additionalProperties"][..]),
+            &self.validators,
+        )]
+    }
+
     #[inline]
     fn is_valid_object(
         &self,
@@ -237,28 +268,34 @@ impl Validate for AdditionalPropertiesNotEmptyValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
+        let instance_path = instance_path.clone();
         Box::new(
-            self.validators
+            instance_value
                 .iter()
-                .flat_map(move |validator| {
-                    instance_value
-                        .iter()
-                        .filter(move |(property, _)| !self.properties.contains(*property))
-                        .flat_map(move |(_, value)| validator.validate(schema, value))
-                })
-                .collect::<Vec<_>>()
-                .into_iter(),
+                .filter(move |(property, _)| !self.properties.contains(*property))
+                .flat_map(move |(property, value)| {
+                    let property_path = instance_path.push(property.clone());
+                    self.validators.iter().flat_map(move |validator| {
+                        validator.validate(schema, value, &property_path)
+                    })
+                }),
         )
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
-            self.validate_object(schema, instance, instance_value)
+            self.validate_object(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -291,6 +328,13 @@ impl AdditionalPropertiesWithPatternsValidator {
     }
 }
 impl Validate for AdditionalPropertiesWithPatternsValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        vec![(
+            JSONPointer::from(&["additionalProperties"][..]),
+            &self.validators,
+        )]
+    }
+
     #[inline]
     fn is_valid_object(
         &self,
@@ -316,28 +360,34 @@ impl Validate for AdditionalPropertiesWithPatternsValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
+        let instance_path = instance_path.clone();
         Box::new(
-            self.validators
+            instance_value
                 .iter()
-                .flat_map(move |validator| {
-                    instance_value
-                        .iter()
-                        .filter(move |(property, _)| !self.pattern.is_match(property))
-                        .flat_map(move |(_, value)| validator.validate(schema, value))
-                })
-                .collect::<Vec<_>>()
-                .into_iter(),
+                .filter(move |(property, _)| !self.pattern.is_match(property))
+                .flat_map(move |(property, value)| {
+                    let property_path = instance_path.push(property.clone());
+                    self.validators.iter().flat_map(move |validator| {
+                        validator.validate(schema, value, &property_path)
+                    })
+                }),
         )
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
-            self.validate_object(schema, instance, instance_value)
+            self.validate_object(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -386,25 +436,34 @@ impl Validate for AdditionalPropertiesWithPatternsFalseValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         _: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
         instance_value
             .keys()
             .find(|property| !self.pattern.is_match(property))
             .map_or_else(no_error, |property| {
                 error(
-                    ValidationError::false_schema(&Value::String(property.to_string()))
-                        .into_owned(),
+                    ValidationError::false_schema(
+                        instance_path.push(property.clone()).into(),
+                        &Value::String(property.to_string()),
+                    )
+                    .into_owned(),
                 )
             })
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
-            self.validate_object(schema, instance, instance_value)
+            self.validate_object(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -443,6 +502,13 @@ impl AdditionalPropertiesWithPatternsNotEmptyValidator {
     }
 }
 impl Validate for AdditionalPropertiesWithPatternsNotEmptyValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        vec![(
+            JSONPointer::from(&["additionalProperties"][..]),
+            &self.validators,
+        )]
+    }
+
     #[inline]
     fn is_valid_object(
         &self,
@@ -470,31 +536,36 @@ impl Validate for AdditionalPropertiesWithPatternsNotEmptyValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
+        let instance_path = instance_path.clone();
         Box::new(
-            self.validators
+            instance_value
                 .iter()
-                .flat_map(move |validator| {
-                    instance_value
-                        .iter()
-                        .filter(move |(property, _)| {
-                            !(self.properties.contains(*property)
-                                || self.pattern.is_match(property))
-                        })
-                        .flat_map(move |(_, value)| validator.validate(schema, value))
+                .filter(move |(property, _)| {
+                    !(self.properties.contains(*property) || self.pattern.is_match(property))
                 })
-                .collect::<Vec<_>>()
-                .into_iter(),
+                .flat_map(move |(property, value)| {
+                    let property_path = instance_path.push(property.clone());
+                    self.validators.iter().flat_map(move |validator| {
+                        validator.validate(schema, value, &property_path)
+                    })
+                }),
         )
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
-            self.validate_object(schema, instance, instance_value)
+            self.validate_object(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -551,10 +622,11 @@ impl Validate for AdditionalPropertiesWithPatternsNotEmptyFalseValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         _: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
         instance_value
             .keys()
@@ -563,15 +635,23 @@ impl Validate for AdditionalPropertiesWithPatternsNotEmptyFalseValidator {
             })
             .map_or_else(no_error, |property| {
                 error(
-                    ValidationError::false_schema(&Value::String(property.to_string()))
-                        .into_owned(),
+                    ValidationError::false_schema(
+                        instance_path.push(property.clone()).into(),
+                        &Value::String(property.to_string()),
+                    )
+                    .into_owned(),
                 )
             })
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
-            self.validate_object(schema, instance, instance_value)
+            self.validate_object(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -1,7 +1,8 @@
 use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{CompilationError, ErrorIterator},
-    keywords::{format_vec_of_validators, CompilationResult, Validators},
+    keywords::{format_vec_of_validators, CompilationResult, Subschemas, Validators},
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -15,8 +16,8 @@ impl AllOfValidator {
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Array(items) = schema {
             let mut schemas = Vec::with_capacity(items.len());
-            for item in items {
-                let validators = compile_validators(item, context)?;
+            for (idx, item) in items.iter().enumerate() {
+                let validators = compile_validators(item, &context.with_path(idx))?;
                 schemas.push(validators)
             }
             Ok(Box::new(AllOfValidator { schemas }))
@@ -50,21 +51,20 @@ macro_rules! all_of_impl_validate {
         paste::item! {
             #[inline]
             fn [<validate_ $method_suffix>]<'a>(
-                &self,
+                &'a self,
                 schema: &'a JSONSchema,
                 instance: &'a Value,
                 instance_value: $instance_type,
+                instance_path: &InstancePath,
             ) -> ErrorIterator<'a> {
+                let instance_path = instance_path.clone();
                 Box::new(
                     self.schemas
                         .iter()
-                        .flat_map(move |validators| {
-                            validators.iter().flat_map(move |validator| {
-                                validator.[<validate_ $method_suffix>](schema, instance, instance_value)
-                            })
-                        })
-                        .collect::<Vec<_>>()
-                        .into_iter(),
+                        .flatten()
+                        .flat_map(move |validator| {
+                            validator.[<validate_ $method_suffix>](schema, instance, instance_value, &instance_path)
+                        }),
                 )
             }
         }
@@ -72,6 +72,19 @@ macro_rules! all_of_impl_validate {
 }
 
 impl Validate for AllOfValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        self.schemas
+            .iter()
+            .enumerate()
+            .map(|(idx, validators)| {
+                (
+                    JSONPointer::default().join("allOf").join(idx),
+                    &validators[..],
+                )
+            })
+            .collect()
+    }
+
     all_of_impl_is_valid!(array, &[Value]);
     all_of_impl_is_valid!(boolean, bool);
     all_of_impl_is_valid!(null, ());
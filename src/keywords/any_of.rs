@@ -1,7 +1,8 @@
 use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
-    error::{CompilationError, ValidationError},
-    keywords::{format_vec_of_validators, CompilationResult, Validators},
+    error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
+    keywords::{format_vec_of_validators, CompilationResult, Subschemas, Validators},
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -15,8 +16,8 @@ impl AnyOfValidator {
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Array(items) = schema {
             let mut schemas = Vec::with_capacity(items.len());
-            for item in items {
-                let validators = compile_validators(item, context)?;
+            for (idx, item) in items.iter().enumerate() {
+                let validators = compile_validators(item, &context.with_path(idx))?;
                 schemas.push(validators)
             }
             Ok(Box::new(AnyOfValidator { schemas }))
@@ -46,10 +47,53 @@ macro_rules! any_of_impl_is_valid {
     };
 }
 
+macro_rules! any_of_impl_validate {
+    ($method_suffix:tt, $instance_type: ty) => {
+        paste::item! {
+            #[inline]
+            fn [<validate_ $method_suffix>]<'a>(
+                &'a self,
+                schema: &'a JSONSchema,
+                instance: &'a Value,
+                instance_value: $instance_type,
+                instance_path: &InstancePath,
+            ) -> ErrorIterator<'a> {
+                if self.[<is_valid_ $method_suffix>](schema, instance, instance_value) {
+                    no_error()
+                } else {
+                    // Errors from all branches are kept, so users could find out why each of them failed
+                    let context = self
+                        .schemas
+                        .iter()
+                        .map(|validators| {
+                            validators
+                                .iter()
+                                .flat_map(|validator| {
+                                    validator.[<validate_ $method_suffix>](schema, instance, instance_value, instance_path)
+                                })
+                                .map(ValidationError::into_owned)
+                                .collect()
+                        })
+                        .collect();
+                    error(ValidationError::any_of(instance_path.into(), instance, context))
+                }
+            }
+        }
+    };
+}
+
 impl Validate for AnyOfValidator {
-    #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::any_of(instance)
+    fn subschemas(&self) -> Subschemas<'_> {
+        self.schemas
+            .iter()
+            .enumerate()
+            .map(|(idx, validators)| {
+                (
+                    JSONPointer::default().join("anyOf").join(idx),
+                    &validators[..],
+                )
+            })
+            .collect()
     }
 
     any_of_impl_is_valid!(array, &[Value]);
@@ -60,6 +104,15 @@ impl Validate for AnyOfValidator {
     any_of_impl_is_valid!(signed_integer, i64);
     any_of_impl_is_valid!(string, &str);
     any_of_impl_is_valid!(unsigned_integer, u64);
+
+    any_of_impl_validate!(array, &'a [Value]);
+    any_of_impl_validate!(boolean, bool);
+    any_of_impl_validate!(null, ());
+    any_of_impl_validate!(number, f64);
+    any_of_impl_validate!(object, &'a Map<String, Value>);
+    any_of_impl_validate!(signed_integer, i64);
+    any_of_impl_validate!(string, &'a str);
+    any_of_impl_validate!(unsigned_integer, u64);
 }
 impl ToString for AnyOfValidator {
     fn to_string(&self) -> String {
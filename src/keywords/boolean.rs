@@ -2,6 +2,7 @@ use crate::{
     compilation::JSONSchema,
     error::{error, no_error, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::InstancePath,
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -20,7 +21,12 @@ impl Validate for TrueValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, _: &'a JSONSchema, _: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        _: &'a JSONSchema,
+        _: &'a Value,
+        _: &InstancePath,
+    ) -> ErrorIterator<'a> {
         no_error()
     }
 }
@@ -39,8 +45,12 @@ impl FalseValidator {
 }
 impl Validate for FalseValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::false_schema(instance)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::false_schema(instance_path.into(), instance)
     }
 
     #[inline]
@@ -81,8 +91,13 @@ impl Validate for FalseValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
-        error(self.build_validation_error(instance))
+    fn validate<'a>(
+        &'a self,
+        _: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        error(self.build_validation_error(instance, instance_path))
     }
 }
 impl ToString for FalseValidator {
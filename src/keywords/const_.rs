@@ -2,6 +2,7 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::InstancePath,
     validator::Validate,
 };
 use serde_json::{Map, Number, Value};
@@ -20,8 +21,12 @@ impl ConstArrayValidator {
 }
 impl Validate for ConstArrayValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::constant_array(instance, &self.value)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::constant_array(instance_path.into(), instance, &self.value)
     }
 
     #[inline]
@@ -66,11 +71,16 @@ impl Validate for ConstArrayValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Array(instance_value) = instance {
-            self.validate_array(schema, instance, instance_value)
+            self.validate_array(schema, instance, instance_value, instance_path)
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
 }
@@ -98,8 +108,12 @@ impl ConstBooleanValidator {
 }
 impl Validate for ConstBooleanValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::constant_boolean(instance, self.value)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::constant_boolean(instance_path.into(), instance, self.value)
     }
 
     #[inline]
@@ -144,11 +158,16 @@ impl Validate for ConstBooleanValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Bool(instance_value) = instance {
-            self.validate_boolean(schema, instance, *instance_value)
+            self.validate_boolean(schema, instance, *instance_value, instance_path)
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
 }
@@ -167,8 +186,12 @@ impl ConstNullValidator {
 }
 impl Validate for ConstNullValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::constant_null(instance)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::constant_null(instance_path.into(), instance)
     }
 
     #[inline]
@@ -213,11 +236,16 @@ impl Validate for ConstNullValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Null = instance {
-            self.validate_null(schema, instance, ())
+            self.validate_null(schema, instance, (), instance_path)
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
 }
@@ -245,8 +273,12 @@ impl ConstNumberValidator {
 }
 impl Validate for ConstNumberValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::constant_number(instance, &self.original_value)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::constant_number(instance_path.into(), instance, &self.original_value)
     }
 
     #[inline]
@@ -303,11 +335,16 @@ impl Validate for ConstNumberValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Some(instance_value) = instance.as_f64() {
-            self.validate_number(schema, instance, instance_value)
+            self.validate_number(schema, instance, instance_value, instance_path)
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
 }
@@ -330,8 +367,12 @@ impl ConstObjectValidator {
 }
 impl Validate for ConstObjectValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::constant_object(instance, &self.value)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::constant_object(instance_path.into(), instance, &self.value)
     }
 
     #[inline]
@@ -381,11 +422,16 @@ impl Validate for ConstObjectValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
-            self.validate_object(schema, instance, instance_value)
+            self.validate_object(schema, instance, instance_value, instance_path)
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
 }
@@ -415,8 +461,12 @@ impl ConstStringValidator {
 }
 impl Validate for ConstStringValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::constant_string(instance, &self.value)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::constant_string(instance_path.into(), instance, &self.value)
     }
 
     #[inline]
@@ -461,11 +511,16 @@ impl Validate for ConstStringValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::String(instance_value) = instance {
-            self.validate_string(schema, instance, instance_value)
+            self.validate_string(schema, instance, instance_value, instance_path)
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
 }
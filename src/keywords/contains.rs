@@ -1,7 +1,8 @@
 use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{no_error, ErrorIterator, ValidationError},
-    keywords::{format_validators, CompilationResult, Validators},
+    keywords::{format_validators, CompilationResult, Subschemas, Validators},
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -20,9 +21,17 @@ impl ContainsValidator {
 }
 
 impl Validate for ContainsValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        vec![(JSONPointer::from(&["contains"][..]), &self.validators)]
+    }
+
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::contains(instance)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::contains(instance_path.into(), instance)
     }
 
     #[inline]
@@ -43,9 +52,14 @@ impl Validate for ContainsValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Array(instance_value) = instance {
-            self.validate_array(schema, instance, instance_value)
+            self.validate_array(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -5,6 +5,7 @@ use crate::{
     content_media_type::ContentMediaTypeCheckType,
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::InstancePath,
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -42,24 +43,31 @@ impl Validate for ContentMediaTypeValidator {
 
     #[inline]
     fn validate_string<'a>(
-        &self,
+        &'a self,
         _: &'a JSONSchema,
         instance: &'a Value,
         instance_value: &'a str,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
         if (self.func)(instance_value) {
             no_error()
         } else {
             error(ValidationError::content_media_type(
+                instance_path.into(),
                 instance,
                 &self.media_type,
             ))
         }
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::String(instance_value) = instance {
-            self.validate_string(schema, instance, instance_value)
+            self.validate_string(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -103,21 +111,31 @@ impl Validate for ContentEncodingValidator {
 
     #[inline]
     fn validate_string<'a>(
-        &self,
+        &'a self,
         _: &'a JSONSchema,
         instance: &'a Value,
         instance_value: &'a str,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
         if (self.func)(instance_value) {
             no_error()
         } else {
-            error(ValidationError::content_encoding(instance, &self.encoding))
+            error(ValidationError::content_encoding(
+                instance_path.into(),
+                instance,
+                &self.encoding,
+            ))
         }
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::String(instance_value) = instance {
-            self.validate_string(schema, instance, instance_value)
+            self.validate_string(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -174,20 +192,26 @@ impl Validate for ContentMediaTypeAndEncodingValidator {
 
     #[inline]
     fn validate_string<'a>(
-        &self,
+        &'a self,
         _: &'a JSONSchema,
         instance: &'a Value,
         instance_value: &'a str,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
         // TODO. Avoid explicit `error` call. It might be done if `converter` will
         // return a proper type
         match (self.converter)(instance_value) {
-            Ok(None) => error(ValidationError::content_encoding(instance, &self.encoding)),
+            Ok(None) => error(ValidationError::content_encoding(
+                instance_path.into(),
+                instance,
+                &self.encoding,
+            )),
             Ok(Some(converted)) => {
                 if (self.func)(&converted) {
                     no_error()
                 } else {
                     error(ValidationError::content_media_type(
+                        instance_path.into(),
                         instance,
                         &self.media_type,
                     ))
@@ -197,9 +221,14 @@ impl Validate for ContentMediaTypeAndEncodingValidator {
         }
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::String(instance_value) = instance {
-            self.validate_string(schema, instance, instance_value)
+            self.validate_string(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -2,8 +2,11 @@ use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{no_error, CompilationError, ErrorIterator},
     keywords::{
-        format_key_value_validators, required::RequiredValidator, CompilationResult, Validators,
+        format_key_value_validators, required::RequiredValidator, CompilationResult, Subschemas,
+        Validators,
     },
+    location::KeywordNode,
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -19,8 +22,11 @@ impl DependenciesValidator {
             let mut dependencies = Vec::with_capacity(map.len());
             for (key, subschema) in map {
                 let s = match subschema {
-                    Value::Array(_) => vec![RequiredValidator::compile(subschema)?],
-                    _ => compile_validators(subschema, context)?,
+                    Value::Array(_) => vec![KeywordNode::new(
+                        RequiredValidator::compile(subschema)?,
+                        context.location(),
+                    )],
+                    _ => compile_validators(subschema, &context.with_path(key.as_str()))?,
                 };
                 dependencies.push((key.clone(), s))
             }
@@ -32,6 +38,20 @@ impl DependenciesValidator {
 }
 
 impl Validate for DependenciesValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        self.dependencies
+            .iter()
+            .map(|(name, validators)| {
+                (
+                    JSONPointer::default()
+                        .join("dependencies")
+                        .join(name.as_str()),
+                    &validators[..],
+                )
+            })
+            .collect()
+    }
+
     #[inline]
     fn is_valid_object(
         &self,
@@ -59,29 +79,33 @@ impl Validate for DependenciesValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         instance_value: &'a Map<String, Value>,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
         // TODO. custom error message for "required" case
+        let instance_path = instance_path.clone();
         Box::new(
             self.dependencies
                 .iter()
-                .filter(|(property, _)| instance_value.contains_key(property))
-                .flat_map(move |(_, validators)| {
-                    validators.iter().flat_map(move |validator| {
-                        validator.validate_object(schema, instance, instance_value)
-                    })
-                })
-                .collect::<Vec<_>>()
-                .into_iter(),
+                .filter(move |(property, _)| instance_value.contains_key(property))
+                .flat_map(|(_, validators)| validators)
+                .flat_map(move |validator| {
+                    validator.validate_object(schema, instance, instance_value, &instance_path)
+                }),
         )
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
-            self.validate_object(schema, instance, instance_value)
+            self.validate_object(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
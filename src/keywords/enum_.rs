@@ -2,6 +2,7 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{CompilationError, ValidationError},
     keywords::CompilationResult,
+    paths::InstancePath,
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -29,8 +30,12 @@ impl EnumValidator {
 
 impl Validate for EnumValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::enumeration(instance, &self.options)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::enumeration(instance_path.into(), instance, &self.options)
     }
 
     #[inline]
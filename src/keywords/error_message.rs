@@ -0,0 +1,199 @@
+use crate::{
+    compilation::JSONSchema,
+    error::{CompilationError, ErrorIterator},
+    keywords::{BoxedValidator, Subschemas, Validators},
+    location::KeywordNode,
+    paths::InstancePath,
+    validator::Validate,
+};
+use serde_json::{Map, Value};
+
+/// Replaces messages of errors produced by the wrapped validator with a custom one.
+/// Errors that already carry a custom message (e.g. from a nested `errorMessage`) are left intact.
+pub(crate) struct ErrorMessageValidator {
+    inner: BoxedValidator,
+    message: String,
+}
+
+macro_rules! error_message_impl {
+    ($method_suffix:tt, $instance_type: ty, $validate_type: ty) => {
+        paste::item! {
+            #[inline]
+            fn [<is_valid_ $method_suffix>](
+                &self,
+                schema: &JSONSchema,
+                instance: &Value,
+                instance_value: $instance_type,
+            ) -> bool {
+                self.inner.[<is_valid_ $method_suffix>](schema, instance, instance_value)
+            }
+
+            #[inline]
+            fn [<validate_ $method_suffix>]<'a>(
+                &'a self,
+                schema: &'a JSONSchema,
+                instance: &'a Value,
+                instance_value: $validate_type,
+                instance_path: &InstancePath,
+            ) -> ErrorIterator<'a> {
+                let message = self.message.clone();
+                Box::new(
+                    self.inner
+                        .[<validate_ $method_suffix>](schema, instance, instance_value, instance_path)
+                        .map(move |error| error.with_message(&message)),
+                )
+            }
+        }
+    };
+}
+
+impl Validate for ErrorMessageValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        self.inner.subschemas()
+    }
+
+    error_message_impl!(array, &[Value], &'a [Value]);
+    error_message_impl!(boolean, bool, bool);
+    error_message_impl!(null, (), ());
+    error_message_impl!(number, f64, f64);
+    error_message_impl!(object, &Map<String, Value>, &'a Map<String, Value>);
+    error_message_impl!(signed_integer, i64, i64);
+    error_message_impl!(string, &str, &'a str);
+    error_message_impl!(unsigned_integer, u64, u64);
+
+    #[inline]
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        self.inner.is_valid(schema, instance)
+    }
+
+    #[inline]
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        let message = self.message.clone();
+        Box::new(
+            self.inner
+                .validate(schema, instance, instance_path)
+                .map(move |error| error.with_message(&message)),
+        )
+    }
+}
+
+impl ToString for ErrorMessageValidator {
+    fn to_string(&self) -> String {
+        self.inner.to_string()
+    }
+}
+
+/// Wrap validators with custom error messages defined by the `errorMessage` keyword.
+///
+/// The keyword value is either a string, which applies to all keywords in the schema,
+/// or an object mapping keyword names to messages.
+#[inline]
+pub(crate) fn apply(
+    keywords: Vec<(&str, KeywordNode)>,
+    error_message: &Value,
+) -> Result<Validators, CompilationError> {
+    match error_message {
+        Value::String(message) => Ok(keywords
+            .into_iter()
+            .map(|(_, node)| wrap(node, message))
+            .collect()),
+        Value::Object(messages) => {
+            let mut validators = Vec::with_capacity(keywords.len());
+            for (keyword, node) in keywords {
+                match messages.get(keyword) {
+                    Some(Value::String(message)) => validators.push(wrap(node, message)),
+                    Some(_) => return Err(CompilationError::SchemaError),
+                    None => validators.push(node),
+                }
+            }
+            Ok(validators)
+        }
+        _ => Err(CompilationError::SchemaError),
+    }
+}
+
+#[inline]
+fn wrap(node: KeywordNode, message: &str) -> KeywordNode {
+    KeywordNode::new(
+        Box::new(ErrorMessageValidator {
+            inner: node.validator,
+            message: message.to_string(),
+        }),
+        node.schema_path,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{compilation::JSONSchema, JSONPointer};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn messages(schema: &Value, instance: &Value) -> Vec<String> {
+        let compiled = JSONSchema::options()
+            .with_error_messages()
+            .compile(schema)
+            .unwrap();
+        let result = compiled.validate(instance);
+        result
+            .expect_err("Should be invalid")
+            .map(|error| error.to_string())
+            .collect()
+    }
+
+    #[test_case(json!({"type": "string", "errorMessage": "Must be a string"}), json!(1), &["Must be a string"])]
+    #[test_case(json!({"minimum": 5, "maximum": 1, "errorMessage": {"minimum": "Too small"}}), json!(3), &["Too small", "3 is greater than the maximum of 1"])]
+    #[test_case(json!({"properties": {"a": {"type": "string", "errorMessage": "Inner"}}, "errorMessage": "Outer"}), json!({"a": 1}), &["Inner"])]
+    #[test_case(json!({"items": {"type": "string"}, "errorMessage": "Outer"}), json!([1, 2]), &["Outer", "Outer"])]
+    fn custom_messages(schema: Value, instance: Value, expected: &[&str]) {
+        let mut actual = messages(&schema, &instance);
+        actual.sort();
+        let mut expected: Vec<String> = expected.iter().map(|m| (*m).to_string()).collect();
+        expected.sort();
+        assert_eq!(actual, expected)
+    }
+
+    #[test]
+    fn instance_path_is_kept() {
+        let schema = json!({"items": {"type": "string", "errorMessage": "Not a string"}});
+        let compiled = JSONSchema::options()
+            .with_error_messages()
+            .compile(&schema)
+            .unwrap();
+        let instance = json!(["a", 1]);
+        let error = compiled
+            .validate(&instance)
+            .expect_err("Should be invalid")
+            .next()
+            .unwrap();
+        assert_eq!(error.to_string(), "Not a string");
+        assert_eq!(error.instance_path, JSONPointer::from(vec![1.into()]));
+    }
+
+    #[test_case(json!({"type": "string", "errorMessage": 1}))]
+    #[test_case(json!({"type": "string", "errorMessage": {"type": 1}}))]
+    fn invalid_error_message(schema: Value) {
+        assert!(JSONSchema::options()
+            .with_error_messages()
+            .compile(&schema)
+            .is_err());
+        // Without the option it is an annotation
+        assert!(JSONSchema::compile(&schema).is_ok());
+    }
+
+    #[test]
+    fn disabled() {
+        let schema = json!({"type": "string", "errorMessage": "Must be a string"});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let instance = json!(1);
+        let error = compiled
+            .validate_first(&instance)
+            .expect_err("Should be invalid");
+        assert_eq!(error.to_string(), "'1' is not of type 'string'");
+    }
+}
@@ -2,6 +2,7 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::InstancePath,
     validator::Validate,
 };
 use num_cmp::NumCmp;
@@ -21,9 +22,17 @@ macro_rules! validate {
     ($validator: ty) => {
         impl Validate for $validator {
             #[inline]
-            fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
+            fn build_validation_error<'a>(
+                &self,
+                instance: &'a Value,
+                instance_path: &InstancePath,
+            ) -> ValidationError<'a> {
                 #[allow(trivial_numeric_casts)]
-                ValidationError::exclusive_minimum(instance, self.limit as f64)
+                ValidationError::exclusive_minimum(
+                    instance_path.into(),
+                    instance,
+                    self.limit as f64,
+                )
             }
 
             #[inline]
@@ -63,15 +72,26 @@ macro_rules! validate {
 
             #[inline]
             fn validate<'a>(
-                &self,
+                &'a self,
                 schema: &'a JSONSchema,
                 instance: &'a Value,
+                instance_path: &InstancePath,
             ) -> ErrorIterator<'a> {
                 if let Value::Number(instance_number) = instance {
                     if let Some(instance_unsigned_integer) = instance_number.as_u64() {
-                        self.validate_unsigned_integer(schema, instance, instance_unsigned_integer)
+                        self.validate_unsigned_integer(
+                            schema,
+                            instance,
+                            instance_unsigned_integer,
+                            instance_path,
+                        )
                     } else if let Some(instance_signed_integer) = instance_number.as_i64() {
-                        self.validate_signed_integer(schema, instance, instance_signed_integer)
+                        self.validate_signed_integer(
+                            schema,
+                            instance,
+                            instance_signed_integer,
+                            instance_path,
+                        )
                     } else {
                         self.validate_number(
                             schema,
@@ -79,6 +99,7 @@ macro_rules! validate {
                             instance_number
                                 .as_f64()
                                 .expect("A JSON number will always be representable as f64"),
+                            instance_path,
                         )
                     }
                 } else {
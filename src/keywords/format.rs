@@ -1,8 +1,10 @@
 //! Validator for `format` keyword.
 use crate::{
     compilation::{context::CompilationContext, JSONSchema},
+    content_encoding::is_base64,
     error::{no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::InstancePath,
     validator::Validate,
     Draft,
 };
@@ -40,8 +42,12 @@ macro_rules! generic_format_validator {
         }
         impl Validate for $validator {
             #[inline]
-            fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-                ValidationError::format(instance, $format_name)
+            fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+                ValidationError::format(instance_path.into(), instance, $format_name)
             }
             $($validate_components_extra)*
         }
@@ -72,9 +78,14 @@ macro_rules! string_format_validator {
             }
 
             #[inline]
-            fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+            fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
                 if let Value::String(instance_value) = instance {
-                    self.validate_string(schema, instance, instance_value)
+                    self.validate_string(schema, instance, instance_value, instance_path)
                 } else {
                     no_error()
                 }
@@ -147,6 +158,52 @@ string_format_validator!(URIReferenceValidator, "uri-reference", |instance_value
 string_format_validator!(URITemplateValidator, "uri-template", |instance_value| {
     URI_TEMPLATE_RE.is_match(instance_value)
 });
+string_format_validator!(ByteValidator, "byte", is_base64);
+
+macro_rules! number_format_validator {
+    ($validator:ident, $format_name:tt, $check_unsigned:expr, $check_signed:expr, $check_number:expr) => {
+        generic_format_validator!(
+            $validator,
+            $format_name =>
+            #[inline]
+            fn is_valid_unsigned_integer(&self, _: &JSONSchema, _: &Value, instance_value: u64) -> bool {
+                $check_unsigned(instance_value)
+            }
+            #[inline]
+            fn is_valid_signed_integer(&self, _: &JSONSchema, _: &Value, instance_value: i64) -> bool {
+                $check_signed(instance_value)
+            }
+            #[inline]
+            fn is_valid_number(&self, _: &JSONSchema, _: &Value, instance_value: f64) -> bool {
+                $check_number(instance_value)
+            }
+        );
+    };
+}
+
+number_format_validator!(
+    Int32Validator,
+    "int32",
+    |value: u64| value <= i32::MAX as u64,
+    |value: i64| i64::from(i32::MIN) <= value && value <= i64::from(i32::MAX),
+    |value: f64| value.fract() == 0.0
+        && f64::from(i32::MIN) <= value
+        && value <= f64::from(i32::MAX)
+);
+number_format_validator!(
+    Int64Validator,
+    "int64",
+    |value: u64| value <= i64::MAX as u64,
+    |_| true,
+    |value: f64| value.fract() == 0.0 && (i64::MIN as f64) <= value && value < (i64::MAX as f64)
+);
+number_format_validator!(
+    FloatValidator,
+    "float",
+    |_| true,
+    |_| true,
+    |value: f64| value.abs() <= f64::from(f32::MAX)
+);
 
 #[inline]
 pub(crate) fn compile(
@@ -155,6 +212,9 @@ pub(crate) fn compile(
     context: &CompilationContext,
 ) -> Option<CompilationResult> {
     if let Value::String(format) = schema {
+        if !context.config.format_assertion() {
+            return None;
+        }
         let draft_version = context.config.draft();
         match format.as_str() {
             "date-time" => Some(DateTimeValidator::compile()),
@@ -186,6 +246,16 @@ pub(crate) fn compile(
                 Some(URITemplateValidator::compile())
             }
             "uri" => Some(URIValidator::compile()),
+            "int32" if draft_version == Draft::OpenAPI30 => Some(Int32Validator::compile()),
+            "int64" if draft_version == Draft::OpenAPI30 => Some(Int64Validator::compile()),
+            "float" if draft_version == Draft::OpenAPI30 => Some(FloatValidator::compile()),
+            "byte" if draft_version == Draft::OpenAPI30 => Some(ByteValidator::compile()),
+            // Any number is a valid `double` and any string is valid `binary` or `password`
+            "double" | "binary" | "password" if draft_version == Draft::OpenAPI30 => None,
+            _ if context.config.strict() => Some(Err(CompilationError::UnknownFormat {
+                format: format.clone(),
+                schema_path: context.location(),
+            })),
             _ => None,
         }
     } else {
@@ -1,7 +1,8 @@
 use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{no_error, ErrorIterator},
-    keywords::{format_validators, CompilationResult, Validators},
+    keywords::{format_validators, CompilationResult, Subschemas, Validators},
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -20,7 +21,7 @@ impl IfThenValidator {
     ) -> CompilationResult {
         Ok(Box::new(IfThenValidator {
             schema: compile_validators(schema, context)?,
-            then_schema: compile_validators(then_schema, context)?,
+            then_schema: compile_validators(then_schema, &context.with_sibling("then"))?,
         }))
     }
 }
@@ -55,23 +56,23 @@ macro_rules! if_then_impl_validate {
         paste::item! {
             #[inline]
             fn [<validate_ $method_suffix>]<'a>(
-                &self,
+                &'a self,
                 schema: &'a JSONSchema,
                 instance: &'a Value,
                 instance_value: $instance_type,
+                instance_path: &InstancePath,
             ) -> ErrorIterator<'a> {
                 if self
                     .schema
                     .iter()
                     .all(|validator| validator.[<is_valid_ $method_suffix>](schema, instance, instance_value))
                 {
+                    let instance_path = instance_path.clone();
                     Box::new(
                         self
                             .then_schema
                             .iter()
-                            .flat_map(move |validator| validator.[<validate_ $method_suffix>](schema, instance, instance_value))
-                            .collect::<Vec<_>>()
-                            .into_iter()
+                            .flat_map(move |validator| validator.[<validate_ $method_suffix>](schema, instance, instance_value, &instance_path))
                         )
                 } else {
                     no_error()
@@ -82,6 +83,13 @@ macro_rules! if_then_impl_validate {
 }
 
 impl Validate for IfThenValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        vec![
+            (JSONPointer::from(&["if"][..]), &self.schema[..]),
+            (JSONPointer::from(&["then"][..]), &self.then_schema[..]),
+        ]
+    }
+
     if_then_impl_is_valid!(array, &[Value]);
     if_then_impl_is_valid!(boolean, bool);
     if_then_impl_is_valid!(null, ());
@@ -124,7 +132,7 @@ impl IfElseValidator {
     ) -> CompilationResult {
         Ok(Box::new(IfElseValidator {
             schema: compile_validators(schema, context)?,
-            else_schema: compile_validators(else_schema, context)?,
+            else_schema: compile_validators(else_schema, &context.with_sibling("else"))?,
         }))
     }
 }
@@ -159,10 +167,11 @@ macro_rules! if_else_impl_validate {
         paste::item! {
             #[inline]
             fn [<validate_ $method_suffix>]<'a>(
-                &self,
+                &'a self,
                 schema: &'a JSONSchema,
                 instance: &'a Value,
                 instance_value: $instance_type,
+                instance_path: &InstancePath,
             ) -> ErrorIterator<'a> {
                 if self
                     .schema
@@ -171,13 +180,12 @@ macro_rules! if_else_impl_validate {
                 {
                     no_error()
                 } else {
+                    let instance_path = instance_path.clone();
                     Box::new(
                         self
                             .else_schema
                             .iter()
-                            .flat_map(move |validator| validator.[<validate_ $method_suffix>](schema, instance, instance_value))
-                            .collect::<Vec<_>>()
-                            .into_iter()
+                            .flat_map(move |validator| validator.[<validate_ $method_suffix>](schema, instance, instance_value, &instance_path))
                         )
                 }
             }
@@ -186,6 +194,13 @@ macro_rules! if_else_impl_validate {
 }
 
 impl Validate for IfElseValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        vec![
+            (JSONPointer::from(&["if"][..]), &self.schema[..]),
+            (JSONPointer::from(&["else"][..]), &self.else_schema[..]),
+        ]
+    }
+
     if_else_impl_is_valid!(array, &[Value]);
     if_else_impl_is_valid!(boolean, bool);
     if_else_impl_is_valid!(null, ());
@@ -230,8 +245,8 @@ impl IfThenElseValidator {
     ) -> CompilationResult {
         Ok(Box::new(IfThenElseValidator {
             schema: compile_validators(schema, context)?,
-            then_schema: compile_validators(then_schema, context)?,
-            else_schema: compile_validators(else_schema, context)?,
+            then_schema: compile_validators(then_schema, &context.with_sibling("then"))?,
+            else_schema: compile_validators(else_schema, &context.with_sibling("else"))?,
         }))
     }
 }
@@ -268,32 +283,31 @@ macro_rules! if_then_else_impl_validate {
         paste::item! {
             #[inline]
             fn [<validate_ $method_suffix>]<'a>(
-                &self,
+                &'a self,
                 schema: &'a JSONSchema,
                 instance: &'a Value,
                 instance_value: $instance_type,
+                instance_path: &InstancePath,
             ) -> ErrorIterator<'a> {
                 if self
                     .schema
                     .iter()
                     .all(|validator| validator.[<is_valid_ $method_suffix>](schema, instance, instance_value))
                 {
+                    let instance_path = instance_path.clone();
                     Box::new(
                         self
                             .then_schema
                             .iter()
-                            .flat_map(move |validator| validator.[<validate_ $method_suffix>](schema, instance, instance_value))
-                            .collect::<Vec<_>>()
-                            .into_iter()
+                            .flat_map(move |validator| validator.[<validate_ $method_suffix>](schema, instance, instance_value, &instance_path))
                     )
                 } else {
+                    let instance_path = instance_path.clone();
                     Box::new(
                         self
                             .else_schema
                             .iter()
-                            .flat_map(move |validator| validator.[<validate_ $method_suffix>](schema, instance, instance_value))
-                            .collect::<Vec<_>>()
-                            .into_iter()
+                            .flat_map(move |validator| validator.[<validate_ $method_suffix>](schema, instance, instance_value, &instance_path))
                         )
                 }
             }
@@ -302,6 +316,14 @@ macro_rules! if_then_else_impl_validate {
 }
 
 impl Validate for IfThenElseValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        vec![
+            (JSONPointer::from(&["if"][..]), &self.schema[..]),
+            (JSONPointer::from(&["then"][..]), &self.then_schema[..]),
+            (JSONPointer::from(&["else"][..]), &self.else_schema[..]),
+        ]
+    }
+
     if_then_else_impl_is_valid!(array, &[Value]);
     if_then_else_impl_is_valid!(boolean, bool);
     if_then_else_impl_is_valid!(null, ());
@@ -3,8 +3,9 @@ use crate::{
     error::{no_error, ErrorIterator},
     keywords::{
         boolean::TrueValidator, format_validators, format_vec_of_validators, CompilationResult,
-        Validators,
+        Subschemas, Validators,
     },
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use rayon::prelude::*;
@@ -17,14 +18,27 @@ impl ItemsArrayValidator {
     #[inline]
     pub(crate) fn compile(schemas: &[Value], context: &CompilationContext) -> CompilationResult {
         let mut items = Vec::with_capacity(schemas.len());
-        for item in schemas {
-            let validators = compile_validators(item, context)?;
+        for (idx, item) in schemas.iter().enumerate() {
+            let validators = compile_validators(item, &context.with_path(idx))?;
             items.push(validators)
         }
         Ok(Box::new(ItemsArrayValidator { items }))
     }
 }
 impl Validate for ItemsArrayValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        self.items
+            .iter()
+            .enumerate()
+            .map(|(idx, validators)| {
+                (
+                    JSONPointer::default().join("items").join(idx),
+                    &validators[..],
+                )
+            })
+            .collect()
+    }
+
     #[inline]
     fn is_valid_array(&self, schema: &JSONSchema, _: &Value, instance_value: &[Value]) -> bool {
         instance_value
@@ -47,28 +61,35 @@ impl Validate for ItemsArrayValidator {
 
     #[inline]
     fn validate_array<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a [Value],
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
+        let instance_path = instance_path.clone();
         Box::new(
             instance_value
                 .iter()
                 .zip(self.items.iter())
-                .flat_map(move |(item, validators)| {
+                .enumerate()
+                .flat_map(move |(idx, (item, validators))| {
+                    let item_path = instance_path.push(idx);
                     validators
                         .iter()
-                        .flat_map(move |validator| validator.validate(schema, item))
-                })
-                .collect::<Vec<_>>()
-                .into_iter(),
+                        .flat_map(move |validator| validator.validate(schema, item, &item_path))
+                }),
         )
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Array(instance_value) = instance {
-            self.validate_array(schema, instance, instance_value)
+            self.validate_array(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -91,6 +112,10 @@ impl ItemsObjectValidator {
     }
 }
 impl Validate for ItemsObjectValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        vec![(JSONPointer::from(&["items"][..]), &self.validators)]
+    }
+
     #[inline]
     fn is_valid_array(&self, schema: &JSONSchema, _: &Value, instance_value: &[Value]) -> bool {
         if instance_value.len() > 8 {
@@ -118,37 +143,51 @@ impl Validate for ItemsObjectValidator {
 
     #[inline]
     fn validate_array<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a [Value],
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
-        let errors: Vec<_> = if instance_value.len() > 8 {
-            instance_value
+        // Without a limit all errors are needed anyway, so large arrays are validated in parallel.
+        // Traces are recorded per thread, therefore tracing needs sequential validation
+        let config = &schema.context.config;
+        if instance_value.len() > 8 && config.max_errors().is_none() && !config.tracing() {
+            let errors: Vec<_> = instance_value
                 .par_iter()
-                .flat_map(|item| {
+                .enumerate()
+                .flat_map(|(idx, item)| {
+                    let item_path = instance_path.push(idx);
                     self.validators
                         .iter()
-                        .flat_map(|validator| validator.validate(schema, item))
+                        .flat_map(|validator| validator.validate(schema, item, &item_path))
                         .collect::<Vec<_>>()
                 })
-                .collect()
-        } else {
-            self.validators
+                .collect();
+            return Box::new(errors.into_iter());
+        }
+        let instance_path = instance_path.clone();
+        Box::new(
+            instance_value
                 .iter()
-                .flat_map(move |validator| {
-                    instance_value
+                .enumerate()
+                .flat_map(move |(idx, item)| {
+                    let item_path = instance_path.push(idx);
+                    self.validators
                         .iter()
-                        .flat_map(move |item| validator.validate(schema, item))
-                })
-                .collect()
-        };
-        Box::new(errors.into_iter())
+                        .flat_map(move |validator| validator.validate(schema, item, &item_path))
+                }),
+        )
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Array(instance_value) = instance {
-            self.validate_array(schema, instance, instance_value)
+            self.validate_array(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
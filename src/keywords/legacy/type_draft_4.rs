@@ -2,6 +2,7 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::{type_, CompilationResult},
+    paths::InstancePath,
     primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
     validator::Validate,
 };
@@ -34,8 +35,12 @@ impl MultipleTypesValidator {
 
 impl Validate for MultipleTypesValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::multiple_type_error(instance, self.types)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::multiple_type_error(instance_path.into(), instance, self.types)
     }
 
     #[inline]
@@ -94,8 +99,12 @@ impl IntegerTypeValidator {
 
 impl Validate for IntegerTypeValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::single_type_error(instance, PrimitiveType::Integer)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::single_type_error(instance_path.into(), instance, PrimitiveType::Integer)
     }
 
     #[inline]
@@ -132,15 +141,20 @@ impl Validate for IntegerTypeValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Number(_) = instance {
             if self.is_valid(schema, instance) {
                 no_error()
             } else {
-                error(self.build_validation_error(instance))
+                error(self.build_validation_error(instance, instance_path))
             }
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
 }
@@ -2,6 +2,7 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::InstancePath,
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -23,8 +24,12 @@ impl MaxLengthValidator {
 
 impl Validate for MaxLengthValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::max_length(instance, self.limit)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::max_length(instance_path.into(), instance, self.limit)
     }
 
     #[inline]
@@ -41,9 +46,14 @@ impl Validate for MaxLengthValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::String(instance_value) = instance {
-            self.validate_string(schema, instance, instance_value)
+            self.validate_string(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -8,6 +8,7 @@ pub(crate) mod contains;
 pub(crate) mod content;
 pub(crate) mod dependencies;
 pub(crate) mod enum_;
+pub(crate) mod error_message;
 pub(crate) mod exclusive_maximum;
 pub(crate) mod exclusive_minimum;
 pub(crate) mod format;
@@ -25,6 +26,7 @@ pub(crate) mod minimum;
 pub(crate) mod multiple_of;
 pub(crate) mod not;
 pub(crate) mod one_of;
+pub(crate) mod openapi;
 pub(crate) mod pattern;
 pub(crate) mod pattern_properties;
 pub(crate) mod properties;
@@ -33,13 +35,15 @@ pub(crate) mod ref_;
 pub(crate) mod required;
 pub(crate) mod type_;
 pub(crate) mod unique_items;
-use crate::{error, validator::Validate};
+use crate::{error, location::KeywordNode, paths::JSONPointer, validator::Validate};
 
 pub(crate) type CompilationResult = Result<BoxedValidator, error::CompilationError>;
 pub(crate) type BoxedValidator = Box<dyn Validate + Send + Sync>;
-pub(crate) type Validators = Vec<BoxedValidator>;
+pub(crate) type Validators = Vec<KeywordNode>;
+/// Validators of subschemas together with their locations relative to the schema that contains the keyword.
+pub(crate) type Subschemas<'a> = Vec<(JSONPointer, &'a [KeywordNode])>;
 
-fn format_validators(validators: &[BoxedValidator]) -> String {
+fn format_validators(validators: &[KeywordNode]) -> String {
     match validators.len() {
         0 => "{}".to_string(),
         1 => {
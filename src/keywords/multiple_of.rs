@@ -2,6 +2,7 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::InstancePath,
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -20,8 +21,12 @@ impl MultipleOfFloatValidator {
 
 impl Validate for MultipleOfFloatValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::multiple_of(instance, self.multiple_of)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::multiple_of(instance_path.into(), instance, self.multiple_of)
     }
 
     #[inline]
@@ -59,9 +64,14 @@ impl Validate for MultipleOfFloatValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Some(instance_value) = instance.as_f64() {
-            self.validate_number(schema, instance, instance_value)
+            self.validate_number(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -86,8 +96,12 @@ impl MultipleOfIntegerValidator {
 
 impl Validate for MultipleOfIntegerValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::multiple_of(instance, self.multiple_of)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::multiple_of(instance_path.into(), instance, self.multiple_of)
     }
 
     #[inline]
@@ -129,9 +143,14 @@ impl Validate for MultipleOfIntegerValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Some(instance_value) = instance.as_f64() {
-            self.validate_number(schema, instance, instance_value)
+            self.validate_number(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -1,7 +1,8 @@
 use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::ValidationError,
-    keywords::{format_validators, CompilationResult, Validators},
+    keywords::{format_validators, CompilationResult, Subschemas, Validators},
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -41,9 +42,17 @@ macro_rules! not_impl_is_valid {
     };
 }
 impl Validate for NotValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        vec![(JSONPointer::from(&["not"][..]), &self.validators)]
+    }
+
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::not(instance, self.original.clone())
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::not(instance_path.into(), instance, self.original.clone())
     }
 
     not_impl_is_valid!(array, &[Value]);
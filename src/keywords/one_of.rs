@@ -1,7 +1,8 @@
 use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
-    keywords::{format_vec_of_validators, CompilationResult, Validators},
+    keywords::{format_vec_of_validators, CompilationResult, Subschemas, Validators},
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -15,8 +16,8 @@ impl OneOfValidator {
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Array(items) = schema {
             let mut schemas = Vec::with_capacity(items.len());
-            for item in items {
-                schemas.push(compile_validators(item, context)?)
+            for (idx, item) in items.iter().enumerate() {
+                schemas.push(compile_validators(item, &context.with_path(idx))?)
             }
             Ok(Box::new(OneOfValidator { schemas }))
         } else {
@@ -58,34 +59,61 @@ macro_rules! one_of_impl_validate {
         paste::item! {
             #[inline]
             fn [<validate_ $method_suffix>]<'a>(
-                &self,
+                &'a self,
                 schema: &'a JSONSchema,
                 instance: &'a Value,
                 instance_value: $instance_type,
+                instance_path: &InstancePath,
             ) -> ErrorIterator<'a> {
-                let mut valid_schema_iterator = self.schemas
+                let matched: Vec<usize> = self.schemas
                     .iter()
-                    .filter(|validators| {
+                    .enumerate()
+                    .filter(|(_, validators)| {
                         validators
                             .iter()
                             .all(|validator| validator.[<is_valid_ $method_suffix>](schema, instance, instance_value))
-                    });
-
-                if valid_schema_iterator.next().is_some() {
-                    // If one schema is valid we need to ensure that there are no other valid schemas
-                    if valid_schema_iterator.next().is_none() {
-                        no_error()
-                    } else {
-                        error(ValidationError::one_of_multiple_valid(instance))
+                    })
+                    .map(|(idx, _)| idx)
+                    .collect();
+                match matched.len() {
+                    1 => no_error(),
+                    0 => {
+                        // Errors from all branches are kept, so users could find out why each of them failed
+                        let context = self
+                            .schemas
+                            .iter()
+                            .map(|validators| {
+                                validators
+                                    .iter()
+                                    .flat_map(|validator| {
+                                        validator.[<validate_ $method_suffix>](schema, instance, instance_value, instance_path)
+                                    })
+                                    .map(ValidationError::into_owned)
+                                    .collect()
+                            })
+                            .collect();
+                        error(ValidationError::one_of_not_valid(instance_path.into(), instance, context))
                     }
-                } else {
-                    error(ValidationError::one_of_not_valid(instance))
+                    _ => error(ValidationError::one_of_multiple_valid(instance_path.into(), instance, matched)),
                 }
             }
         }
     };
 }
 impl Validate for OneOfValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        self.schemas
+            .iter()
+            .enumerate()
+            .map(|(idx, validators)| {
+                (
+                    JSONPointer::default().join("oneOf").join(idx),
+                    &validators[..],
+                )
+            })
+            .collect()
+    }
+
     one_of_impl_is_valid!(array, &[Value]);
     one_of_impl_is_valid!(boolean, bool);
     one_of_impl_is_valid!(null, ());
@@ -95,13 +123,13 @@ impl Validate for OneOfValidator {
     one_of_impl_is_valid!(string, &str);
     one_of_impl_is_valid!(unsigned_integer, u64);
 
-    one_of_impl_validate!(array, &[Value]);
+    one_of_impl_validate!(array, &'a [Value]);
     one_of_impl_validate!(boolean, bool);
     one_of_impl_validate!(null, ());
     one_of_impl_validate!(number, f64);
-    one_of_impl_validate!(object, &Map<String, Value>);
+    one_of_impl_validate!(object, &'a Map<String, Value>);
     one_of_impl_validate!(signed_integer, i64);
-    one_of_impl_validate!(string, &str);
+    one_of_impl_validate!(string, &'a str);
     one_of_impl_validate!(unsigned_integer, u64);
 }
 impl ToString for OneOfValidator {
@@ -0,0 +1,226 @@
+use crate::{
+    compilation::{compile_validators, context::CompilationContext, JSONSchema},
+    error::{error, CompilationError, ErrorIterator, ValidationError},
+    keywords::{any_of, one_of, BoxedValidator, CompilationResult, Subschemas, Validators},
+    paths::InstancePath,
+    validator::Validate,
+};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+type CompileFunc =
+    fn(&Map<String, Value>, &Value, &CompilationContext) -> Option<CompilationResult>;
+
+/// `oneOf` / `anyOf` with a `discriminator`.
+///
+/// If the discriminator property value selects one of the subschemas, then the instance is validated
+/// only against it. Values are mapped to subschemas via `discriminator.mapping` or implicitly via the
+/// last component of subschemas' `$ref`, e.g. `Dog` for `#/components/schemas/Dog`.
+/// Otherwise, the regular `oneOf` / `anyOf` validation is used.
+pub(crate) struct DiscriminatorValidator {
+    property_name: String,
+    mapping: HashMap<String, usize>,
+    branches: Vec<Validators>,
+    fallback: BoxedValidator,
+}
+
+impl DiscriminatorValidator {
+    #[inline]
+    pub(crate) fn compile(
+        discriminator: &Value,
+        schema: &Value,
+        context: &CompilationContext,
+        fallback: BoxedValidator,
+    ) -> CompilationResult {
+        let property_name = discriminator
+            .get("propertyName")
+            .and_then(Value::as_str)
+            .ok_or(CompilationError::SchemaError)?;
+        let items = schema.as_array().ok_or(CompilationError::SchemaError)?;
+        let mut branches = Vec::with_capacity(items.len());
+        for (idx, item) in items.iter().enumerate() {
+            branches.push(compile_validators(item, &context.with_path(idx))?);
+        }
+        let references: Vec<Option<&str>> = items
+            .iter()
+            .map(|item| item.get("$ref").and_then(Value::as_str))
+            .collect();
+        let mut mapping = HashMap::new();
+        match discriminator.get("mapping") {
+            Some(Value::Object(explicit)) => {
+                for (value, target) in explicit {
+                    let target = target.as_str().ok_or(CompilationError::SchemaError)?;
+                    if let Some(idx) = references.iter().position(|reference| {
+                        matches!(reference, Some(reference) if *reference == target || schema_name(reference) == target)
+                    }) {
+                        mapping.insert(value.clone(), idx);
+                    }
+                }
+            }
+            Some(_) => return Err(CompilationError::SchemaError),
+            None => {}
+        }
+        for (idx, reference) in references.iter().enumerate() {
+            if let Some(reference) = reference {
+                mapping
+                    .entry(schema_name(reference).to_string())
+                    .or_insert(idx);
+            }
+        }
+        Ok(Box::new(DiscriminatorValidator {
+            property_name: property_name.to_string(),
+            mapping,
+            branches,
+            fallback,
+        }))
+    }
+
+    #[inline]
+    fn selected_branch(&self, instance_value: &Map<String, Value>) -> Option<&Validators> {
+        instance_value
+            .get(&self.property_name)
+            .and_then(Value::as_str)
+            .and_then(|value| self.mapping.get(value))
+            .map(|idx| &self.branches[*idx])
+    }
+}
+
+#[inline]
+fn schema_name(reference: &str) -> &str {
+    reference.rsplit('/').next().unwrap_or(reference)
+}
+
+macro_rules! discriminator_delegate {
+    ($method_suffix:tt, $instance_type: ty, $validate_type: ty) => {
+        paste::item! {
+            #[inline]
+            fn [<is_valid_ $method_suffix>](
+                &self,
+                schema: &JSONSchema,
+                instance: &Value,
+                instance_value: $instance_type,
+            ) -> bool {
+                self.fallback.[<is_valid_ $method_suffix>](schema, instance, instance_value)
+            }
+
+            #[inline]
+            fn [<validate_ $method_suffix>]<'a>(
+                &'a self,
+                schema: &'a JSONSchema,
+                instance: &'a Value,
+                instance_value: $validate_type,
+                instance_path: &InstancePath,
+            ) -> ErrorIterator<'a> {
+                self.fallback.[<validate_ $method_suffix>](schema, instance, instance_value, instance_path)
+            }
+        }
+    };
+}
+
+impl Validate for DiscriminatorValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        // Branches are the same as subschemas of the wrapped `oneOf` / `anyOf`
+        self.fallback.subschemas()
+    }
+
+    discriminator_delegate!(array, &[Value], &'a [Value]);
+    discriminator_delegate!(boolean, bool, bool);
+    discriminator_delegate!(null, (), ());
+    discriminator_delegate!(number, f64, f64);
+    discriminator_delegate!(signed_integer, i64, i64);
+    discriminator_delegate!(string, &str, &'a str);
+    discriminator_delegate!(unsigned_integer, u64, u64);
+
+    #[inline]
+    fn is_valid_object(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_value: &Map<String, Value>,
+    ) -> bool {
+        if !instance_value.contains_key(&self.property_name) {
+            false
+        } else if let Some(validators) = self.selected_branch(instance_value) {
+            validators
+                .iter()
+                .all(|validator| validator.is_valid_object(schema, instance, instance_value))
+        } else {
+            self.fallback
+                .is_valid_object(schema, instance, instance_value)
+        }
+    }
+
+    #[inline]
+    fn validate_object<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_value: &'a Map<String, Value>,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        if !instance_value.contains_key(&self.property_name) {
+            error(ValidationError::required(
+                instance_path.into(),
+                instance,
+                self.property_name.clone(),
+            ))
+        } else if let Some(validators) = self.selected_branch(instance_value) {
+            let instance_path = instance_path.clone();
+            Box::new(validators.iter().flat_map(move |validator| {
+                validator.validate_object(schema, instance, instance_value, &instance_path)
+            }))
+        } else {
+            self.fallback
+                .validate_object(schema, instance, instance_value, instance_path)
+        }
+    }
+}
+impl ToString for DiscriminatorValidator {
+    fn to_string(&self) -> String {
+        format!(
+            "{} (discriminator: {})",
+            self.fallback.to_string(),
+            self.property_name
+        )
+    }
+}
+
+#[inline]
+fn compile_with(
+    parent: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+    compile_fallback: CompileFunc,
+) -> Option<CompilationResult> {
+    let fallback = match compile_fallback(parent, schema, context)? {
+        Ok(fallback) => fallback,
+        Err(error) => return Some(Err(error)),
+    };
+    match parent.get("discriminator") {
+        Some(discriminator) => Some(DiscriminatorValidator::compile(
+            discriminator,
+            schema,
+            context,
+            fallback,
+        )),
+        None => Some(Ok(fallback)),
+    }
+}
+
+#[inline]
+pub(crate) fn compile_one_of(
+    parent: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    compile_with(parent, schema, context, one_of::compile)
+}
+
+#[inline]
+pub(crate) fn compile_any_of(
+    parent: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    compile_with(parent, schema, context, any_of::compile)
+}
@@ -0,0 +1,160 @@
+pub(crate) mod discriminator;
+pub(crate) mod read_write_only;
+pub(crate) mod type_;
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        compilation::JSONSchema,
+        error::ValidationErrorKind,
+        schemas::{Draft, OpenAPIDirection},
+    };
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn is_valid(schema: &Value, instance: &Value) -> bool {
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::OpenAPI30)
+            .compile(schema)
+            .unwrap();
+        let result = compiled.validate(instance).is_ok();
+        assert_eq!(compiled.is_valid(instance), result);
+        result
+    }
+
+    #[test_case(json!({"type": "string", "nullable": true}), json!(null), true)]
+    #[test_case(json!({"type": "string", "nullable": true}), json!("a"), true)]
+    #[test_case(json!({"type": "string", "nullable": true}), json!(1), false)]
+    #[test_case(json!({"type": "string"}), json!(null), false)]
+    #[test_case(json!({"type": "string", "nullable": false}), json!(null), false)]
+    #[test_case(json!({"type": "integer", "nullable": true}), json!(1.5), false)]
+    #[test_case(json!({"minimum": 5, "exclusiveMinimum": true}), json!(5), false)]
+    #[test_case(json!({"minimum": 5, "exclusiveMinimum": false}), json!(5), true)]
+    #[test_case(json!({"maximum": 5, "exclusiveMaximum": true}), json!(5), false)]
+    #[test_case(json!({"format": "int32"}), json!(2_147_483_647), true)]
+    #[test_case(json!({"format": "int32"}), json!(2_147_483_648_u64), false)]
+    #[test_case(json!({"format": "int32"}), json!(-2_147_483_649_i64), false)]
+    #[test_case(json!({"format": "int32"}), json!(1.5), false)]
+    #[test_case(json!({"format": "int64"}), json!(-9_223_372_036_854_775_808_i64), true)]
+    #[test_case(json!({"format": "int64"}), json!(18_446_744_073_709_551_615_u64), false)]
+    #[test_case(json!({"format": "float"}), json!(1.5), true)]
+    #[test_case(json!({"format": "float"}), json!(1e300), false)]
+    #[test_case(json!({"format": "double"}), json!(1e300), true)]
+    #[test_case(json!({"format": "byte"}), json!("Zm9v"), true)]
+    #[test_case(json!({"format": "byte"}), json!("not base64!"), false)]
+    #[test_case(json!({"format": "binary"}), json!("\u{0}\u{1}"), true)]
+    #[test_case(json!({"format": "int32"}), json!("a"), true)]
+    // Keywords that are not a part of OpenAPI 3.0 are ignored
+    #[test_case(json!({"const": 1}), json!(2), true)]
+    #[test_case(json!({"patternProperties": {"a": {"type": "string"}}}), json!({"a": 1}), true)]
+    fn keywords(schema: Value, instance: Value, expected: bool) {
+        assert_eq!(is_valid(&schema, &instance), expected)
+    }
+
+    #[test]
+    fn multiple_types_are_not_allowed() {
+        let schema = json!({"type": ["string", "null"]});
+        assert!(JSONSchema::options()
+            .with_draft(Draft::OpenAPI30)
+            .compile(&schema)
+            .is_err())
+    }
+
+    fn pets() -> Value {
+        json!({
+            "oneOf": [
+                {"$ref": "#/components/schemas/Cat"},
+                {"$ref": "#/components/schemas/Dog"}
+            ],
+            "discriminator": {
+                "propertyName": "petType",
+                "mapping": {"kitty": "#/components/schemas/Cat"}
+            },
+            "components": {
+                "schemas": {
+                    "Cat": {
+                        "type": "object",
+                        "properties": {"petType": {"type": "string"}, "lives": {"type": "integer"}},
+                        "required": ["petType"]
+                    },
+                    "Dog": {
+                        "type": "object",
+                        "properties": {"petType": {"type": "string"}, "bark": {"type": "string"}},
+                        "required": ["petType"]
+                    }
+                }
+            }
+        })
+    }
+
+    #[test_case(json!({"petType": "kitty", "lives": 9}), true)]
+    #[test_case(json!({"petType": "Cat", "lives": 9}), true)]
+    #[test_case(json!({"petType": "Dog", "bark": "woof"}), true)]
+    #[test_case(json!({"petType": "Dog", "lives": "9"}), true)]
+    #[test_case(json!({"petType": "Cat", "lives": "9"}), false)]
+    #[test_case(json!({"lives": 9}), false)]
+    fn discriminator(instance: Value, expected: bool) {
+        assert_eq!(is_valid(&pets(), &instance), expected)
+    }
+
+    #[test]
+    fn discriminator_selects_branch() {
+        let schema = pets();
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::OpenAPI30)
+            .compile(&schema)
+            .unwrap();
+        let instance = json!({"petType": "kitty", "lives": "9"});
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].instance_path.to_string(), "/lives");
+    }
+
+    fn user() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {"type": "integer", "readOnly": true},
+                "password": {"type": "string", "writeOnly": true}
+            },
+            "required": ["id", "password"]
+        })
+    }
+
+    #[test_case(None, json!({"id": 1, "password": "secret"}), true)]
+    #[test_case(None, json!({"password": "secret"}), false)]
+    #[test_case(Some(OpenAPIDirection::Request), json!({"password": "secret"}), true)]
+    #[test_case(Some(OpenAPIDirection::Request), json!({"id": 1, "password": "secret"}), false)]
+    #[test_case(Some(OpenAPIDirection::Request), json!({}), false)]
+    #[test_case(Some(OpenAPIDirection::Response), json!({"id": 1}), true)]
+    #[test_case(Some(OpenAPIDirection::Response), json!({"id": 1, "password": "secret"}), false)]
+    fn read_write_only(direction: Option<OpenAPIDirection>, instance: Value, expected: bool) {
+        let schema = user();
+        let mut options = JSONSchema::options();
+        options.with_draft(Draft::OpenAPI30);
+        if let Some(direction) = direction {
+            options.with_openapi_direction(direction);
+        }
+        let compiled = options.compile(&schema).unwrap();
+        assert_eq!(compiled.is_valid(&instance), expected);
+        assert_eq!(compiled.validate(&instance).is_ok(), expected);
+    }
+
+    #[test]
+    fn read_only_error() {
+        let schema = user();
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::OpenAPI30)
+            .with_openapi_direction(OpenAPIDirection::Request)
+            .compile(&schema)
+            .unwrap();
+        let instance = json!({"id": 1, "password": "secret"});
+        let error = compiled.validate(&instance).unwrap_err().next().unwrap();
+        assert!(matches!(*error.kind, ValidationErrorKind::ReadOnly));
+        assert_eq!(error.instance_path.to_string(), "/id");
+        assert_eq!(
+            error.to_string(),
+            "'1' is read-only and can't be sent in a request"
+        );
+    }
+}
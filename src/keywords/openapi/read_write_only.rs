@@ -0,0 +1,143 @@
+use crate::{
+    compilation::{context::CompilationContext, JSONSchema},
+    error::{CompilationError, ValidationError},
+    keywords::{required, CompilationResult},
+    paths::InstancePath,
+    schemas::OpenAPIDirection,
+    validator::Validate,
+};
+use serde_json::{Map, Value};
+
+/// Forbids any value. It is compiled only if the value is not allowed in the current
+/// `OpenAPIDirection`, e.g. for `readOnly: true` in requests.
+pub(crate) struct ReadWriteOnlyValidator {
+    read_only: bool,
+}
+
+impl ReadWriteOnlyValidator {
+    #[inline]
+    pub(crate) fn compile(read_only: bool) -> CompilationResult {
+        Ok(Box::new(ReadWriteOnlyValidator { read_only }))
+    }
+}
+
+impl Validate for ReadWriteOnlyValidator {
+    #[inline]
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        if self.read_only {
+            ValidationError::read_only(instance_path.into(), instance)
+        } else {
+            ValidationError::write_only(instance_path.into(), instance)
+        }
+    }
+
+    #[inline]
+    fn is_valid_array(&self, _: &JSONSchema, _: &Value, _: &[Value]) -> bool {
+        false
+    }
+    #[inline]
+    fn is_valid_boolean(&self, _: &JSONSchema, _: &Value, _: bool) -> bool {
+        false
+    }
+    #[inline]
+    fn is_valid_null(&self, _: &JSONSchema, _: &Value, _: ()) -> bool {
+        false
+    }
+    #[inline]
+    fn is_valid_number(&self, _: &JSONSchema, _: &Value, _: f64) -> bool {
+        false
+    }
+    #[inline]
+    fn is_valid_object(&self, _: &JSONSchema, _: &Value, _: &Map<String, Value>) -> bool {
+        false
+    }
+    #[inline]
+    fn is_valid_signed_integer(&self, _: &JSONSchema, _: &Value, _: i64) -> bool {
+        false
+    }
+    #[inline]
+    fn is_valid_string(&self, _: &JSONSchema, _: &Value, _: &str) -> bool {
+        false
+    }
+    #[inline]
+    fn is_valid_unsigned_integer(&self, _: &JSONSchema, _: &Value, _: u64) -> bool {
+        false
+    }
+}
+impl ToString for ReadWriteOnlyValidator {
+    fn to_string(&self) -> String {
+        if self.read_only {
+            "readOnly: true".to_string()
+        } else {
+            "writeOnly: true".to_string()
+        }
+    }
+}
+
+#[inline]
+fn compile_impl(
+    schema: &Value,
+    context: &CompilationContext,
+    forbidden_in: OpenAPIDirection,
+) -> Option<CompilationResult> {
+    match schema {
+        Value::Bool(true) if context.config.openapi_direction() == Some(forbidden_in) => Some(
+            ReadWriteOnlyValidator::compile(forbidden_in == OpenAPIDirection::Request),
+        ),
+        Value::Bool(_) => None,
+        _ => Some(Err(CompilationError::SchemaError)),
+    }
+}
+
+#[inline]
+pub(crate) fn compile_read_only(
+    _: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    compile_impl(schema, context, OpenAPIDirection::Request)
+}
+
+#[inline]
+pub(crate) fn compile_write_only(
+    _: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    compile_impl(schema, context, OpenAPIDirection::Response)
+}
+
+/// `required` that skips properties which are not allowed in the current `OpenAPIDirection`.
+#[inline]
+pub(crate) fn compile_required(
+    parent: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    let keyword = match context.config.openapi_direction() {
+        Some(OpenAPIDirection::Request) => "readOnly",
+        Some(OpenAPIDirection::Response) => "writeOnly",
+        None => return required::compile(parent, schema, context),
+    };
+    if let (Value::Array(items), Some(Value::Object(properties))) =
+        (schema, parent.get("properties"))
+    {
+        let items = items
+            .iter()
+            .filter(|item| {
+                item.as_str()
+                    .and_then(|name| properties.get(name))
+                    .and_then(|property| property.get(keyword))
+                    != Some(&Value::Bool(true))
+            })
+            .cloned()
+            .collect();
+        required::compile(parent, &Value::Array(items), context)
+    } else {
+        required::compile(parent, schema, context)
+    }
+}
@@ -0,0 +1,26 @@
+use crate::{
+    compilation::context::CompilationContext,
+    error::CompilationError,
+    keywords::{legacy::type_draft_4, CompilationResult},
+};
+use serde_json::{Map, Value};
+
+/// `OpenAPI` 3.0 allows only a single type, and `nullable: true` extends it with `null`.
+#[inline]
+pub(crate) fn compile(
+    parent: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    match schema {
+        Value::String(_) => {
+            if let Some(Value::Bool(true)) = parent.get("nullable") {
+                let types = Value::Array(vec![schema.clone(), Value::String("null".to_string())]);
+                type_draft_4::compile(parent, &types, context)
+            } else {
+                type_draft_4::compile(parent, schema, context)
+            }
+        }
+        _ => Some(Err(CompilationError::SchemaError)),
+    }
+}
@@ -2,6 +2,7 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::InstancePath,
     validator::Validate,
 };
 use regex::{Captures, Regex};
@@ -23,7 +24,7 @@ impl PatternValidator {
     pub(crate) fn compile(pattern: &Value) -> CompilationResult {
         match pattern {
             Value::String(item) => {
-                let pattern = convert_regex(item)?;
+                let pattern = Regex::new(&convert_regex(item))?;
                 Ok(Box::new(PatternValidator {
                     original: item.clone(),
                     pattern,
@@ -36,8 +37,12 @@ impl PatternValidator {
 
 impl Validate for PatternValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::pattern(instance, self.original.clone())
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::pattern(instance_path.into(), instance, self.original.clone())
     }
 
     #[inline]
@@ -54,9 +59,14 @@ impl Validate for PatternValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::String(instance_value) = instance {
-            self.validate_string(schema, instance, instance_value)
+            self.validate_string(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -69,24 +79,22 @@ impl ToString for PatternValidator {
 }
 
 // ECMA 262 has differences
-fn convert_regex(pattern: &str) -> Result<Regex, regex::Error> {
+pub(crate) fn convert_regex(pattern: &str) -> String {
     // replace control chars
     let new_pattern = CONTROL_GROUPS_RE.replace_all(pattern, replace_control_group);
-    Regex::new(
-        &new_pattern
-            .replace(r"\d", "[0-9]")
-            .replace(r"\D", "[^0-9]")
-            .replace(r"\w", "[A-Za-z]")
-            .replace(r"\W", "[^A-Za-z]")
-            .replace(
-                r"\s",
-                "[ \t\n\r\u{000b}\u{000c}\u{2003}\u{feff}\u{2029}\u{00a0}]",
-            )
-            .replace(
-                r"\S",
-                "[^ \t\n\r\u{000b}\u{000c}\u{2003}\u{feff}\u{2029}\u{00a0}]",
-            ),
-    )
+    new_pattern
+        .replace(r"\d", "[0-9]")
+        .replace(r"\D", "[^0-9]")
+        .replace(r"\w", "[A-Za-z]")
+        .replace(r"\W", "[^A-Za-z]")
+        .replace(
+            r"\s",
+            "[ \t\n\r\u{000b}\u{000c}\u{2003}\u{feff}\u{2029}\u{00a0}]",
+        )
+        .replace(
+            r"\S",
+            "[^ \t\n\r\u{000b}\u{000c}\u{2003}\u{feff}\u{2029}\u{00a0}]",
+        )
 }
 
 #[allow(clippy::integer_arithmetic)]
@@ -1,7 +1,8 @@
 use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{no_error, CompilationError, ErrorIterator},
-    keywords::{format_validators, CompilationResult, Validators},
+    keywords::{format_validators, CompilationResult, Subschemas, Validators},
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use regex::Regex;
@@ -19,7 +20,7 @@ impl PatternPropertiesValidator {
             for (pattern, subschema) in map {
                 patterns.push((
                     Regex::new(pattern)?,
-                    compile_validators(subschema, context)?,
+                    compile_validators(subschema, &context.with_path(pattern.as_str()))?,
                 ));
             }
             Ok(Box::new(PatternPropertiesValidator { patterns }))
@@ -30,6 +31,20 @@ impl PatternPropertiesValidator {
 }
 
 impl Validate for PatternPropertiesValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        self.patterns
+            .iter()
+            .map(|(pattern, validators)| {
+                (
+                    JSONPointer::default()
+                        .join("patternProperties")
+                        .join(pattern.as_str()),
+                    &validators[..],
+                )
+            })
+            .collect()
+    }
+
     #[inline]
     fn is_valid_object(
         &self,
@@ -59,32 +74,31 @@ impl Validate for PatternPropertiesValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
-        Box::new(
+        let instance_path = instance_path.clone();
+        Box::new(instance_value.iter().flat_map(move |(key, value)| {
+            let property_path = instance_path.push(key.clone());
             self.patterns
                 .iter()
-                .flat_map(|(re, validators)| {
-                    instance_value
-                        .iter()
-                        .filter(move |(key, _)| re.is_match(key))
-                        .flat_map(move |(_key, value)| {
-                            validators
-                                .iter()
-                                .flat_map(move |validator| validator.validate(schema, value))
-                        })
-                })
-                .collect::<Vec<_>>()
-                .into_iter(),
-        )
+                .filter(move |(re, _)| re.is_match(key))
+                .flat_map(|(_, validators)| validators)
+                .flat_map(move |validator| validator.validate(schema, value, &property_path))
+        }))
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
-            self.validate_object(schema, instance, instance_value)
+            self.validate_object(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -1,7 +1,8 @@
 use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{no_error, CompilationError, ErrorIterator},
-    keywords::{format_key_value_validators, CompilationResult, Validators},
+    keywords::{format_key_value_validators, CompilationResult, Subschemas, Validators},
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -17,7 +18,10 @@ impl PropertiesValidator {
             Value::Object(map) => {
                 let mut properties = Vec::with_capacity(map.len());
                 for (key, subschema) in map {
-                    properties.push((key.clone(), compile_validators(subschema, context)?));
+                    properties.push((
+                        key.clone(),
+                        compile_validators(subschema, &context.with_path(key.as_str()))?,
+                    ));
                 }
                 Ok(Box::new(PropertiesValidator { properties }))
             }
@@ -27,6 +31,20 @@ impl PropertiesValidator {
 }
 
 impl Validate for PropertiesValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        self.properties
+            .iter()
+            .map(|(name, validators)| {
+                (
+                    JSONPointer::default()
+                        .join("properties")
+                        .join(name.as_str()),
+                    &validators[..],
+                )
+            })
+            .collect()
+    }
+
     #[inline]
     fn is_valid_object(
         &self,
@@ -53,32 +71,38 @@ impl Validate for PropertiesValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
         instance_value: &'a Map<String, Value>,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
+        let instance_path = instance_path.clone();
         Box::new(
             self.properties
                 .iter()
-                .flat_map(move |(name, validators)| {
+                .filter_map(move |(name, validators)| {
                     instance_value
                         .get(name)
-                        .into_iter()
-                        .flat_map(move |sub_value| {
-                            validators
-                                .iter()
-                                .flat_map(move |validator| validator.validate(schema, sub_value))
-                        })
+                        .map(|sub_value| (name, sub_value, validators))
                 })
-                .collect::<Vec<_>>()
-                .into_iter(),
+                .flat_map(move |(name, sub_value, validators)| {
+                    let property_path = instance_path.push(name.clone());
+                    validators.iter().flat_map(move |validator| {
+                        validator.validate(schema, sub_value, &property_path)
+                    })
+                }),
         )
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
-            self.validate_object(schema, instance, instance_value)
+            self.validate_object(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
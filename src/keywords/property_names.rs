@@ -1,7 +1,8 @@
 use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{no_error, ErrorIterator, ValidationError},
-    keywords::{format_validators, CompilationResult, Validators},
+    keywords::{format_validators, CompilationResult, Subschemas, Validators},
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -20,6 +21,10 @@ impl PropertyNamesObjectValidator {
 }
 
 impl Validate for PropertyNamesObjectValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        vec![(JSONPointer::from(&["propertyNames"][..]), &self.validators)]
+    }
+
     #[inline]
     fn is_valid_object(
         &self,
@@ -44,32 +49,37 @@ impl Validate for PropertyNamesObjectValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         _: &'a Value,
-        instance_value: &Map<String, Value>,
+        instance_value: &'a Map<String, Value>,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
-        Box::new(
-            self.validators
+        let instance_path = instance_path.clone();
+        Box::new(instance_value.keys().flat_map(move |key| {
+            // Errors are collected only for a single key, because `wrapper` is a temporary value
+            let wrapper = Value::String(key.to_string());
+            let errors: Vec<_> = self
+                .validators
                 .iter()
                 .flat_map(|validator| {
-                    instance_value.keys().flat_map(move |key| {
-                        let wrapper = Value::String(key.to_string());
-                        let errors: Vec<_> = validator
-                            .validate_string(schema, &wrapper, key)
-                            .map(ValidationError::into_owned)
-                            .collect();
-                        errors.into_iter()
-                    })
+                    validator
+                        .validate_string(schema, &wrapper, key, &instance_path)
+                        .map(ValidationError::into_owned)
                 })
-                .collect::<Vec<_>>()
-                .into_iter(),
-        )
+                .collect();
+            errors.into_iter()
+        }))
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
-            self.validate_object(schema, instance, instance_value)
+            self.validate_object(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
@@ -92,8 +102,12 @@ impl PropertyNamesBooleanValidator {
 
 impl Validate for PropertyNamesBooleanValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::false_schema(instance)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::false_schema(instance_path.into(), instance)
     }
 
     #[inline]
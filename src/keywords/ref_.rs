@@ -2,21 +2,55 @@ use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{error, ErrorIterator, ValidationError},
     keywords::{CompilationResult, Validators},
+    location::KeywordNode,
+    paths::{InstancePath, SchemaPath},
     validator::Validate,
 };
-use parking_lot::RwLock;
+use once_cell::sync::OnceCell;
 use serde_json::{Map, Value};
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    sync::{Arc, Weak},
+};
 use url::Url;
 
 pub(crate) struct RefValidator {
     reference: Url,
+    /// Location of this `$ref` within the root schema.
+    /// Validators of the referenced schema are located under it.
+    schema_path: SchemaPath,
     /// Precomputed validators.
-    /// They are behind a RwLock as is not possible to compute them
+    /// They are behind a `OnceCell` as is not possible to compute them
     /// at compile time without risking infinite loops of references
     /// and at the same time during validation we iterate over shared
     /// references (&self) and not owned references (&mut self).
-    validators: RwLock<Option<Validators>>,
+    /// Once initialized, they live as long as `self`, therefore validation errors could be
+    /// produced lazily.
+    target: OnceCell<RefTarget>,
+    /// Validators compiled locally once the shared target is dropped.
+    /// They are used from then on, so the cache is not locked on every evaluation.
+    fallback: OnceCell<Validators>,
+}
+
+enum RefTarget {
+    /// Validators compiled as a part of the current root schema.
+    Local(Validators),
+    /// Validators of another schema from the same `SchemaCache`.
+    /// They are shared with that schema and should be applied with it as the root.
+    /// References are weak, so schemas that refer to each other don't keep each other alive.
+    Shared {
+        root: Weak<JSONSchema<'static>>,
+        validators: Weak<Validators>,
+    },
+}
+
+/// A schema from the same `SchemaCache` together with validators of the referenced subschema.
+type SharedTarget = (Arc<JSONSchema<'static>>, Arc<Validators>);
+
+/// Validators to apply for a reference and the root schema to apply them with.
+enum Resolved<'a> {
+    Local(&'a JSONSchema<'a>, &'a [KeywordNode]),
+    Shared(Arc<JSONSchema<'static>>, Arc<Validators>),
 }
 
 impl RefValidator {
@@ -25,26 +59,140 @@ impl RefValidator {
         let reference = context.build_url(reference)?;
         Ok(Box::new(RefValidator {
             reference,
-            validators: RwLock::new(None),
+            schema_path: context.schema_path.clone(),
+            target: OnceCell::new(),
+            fallback: OnceCell::new(),
         }))
     }
 
     /// Ensure that validators are built and built once.
+    /// Returns validators together with the root schema to validate against.
+    #[inline]
+    fn ensure_validators<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+    ) -> Result<Resolved<'a>, ValidationError<'a>> {
+        let target = self
+            .target
+            .get_or_try_init(|| -> Result<_, ValidationError<'a>> {
+                if let Some((root, validators)) = self.shared_target(schema)? {
+                    return Ok(RefTarget::Shared {
+                        root: Arc::downgrade(&root),
+                        validators: Arc::downgrade(&validators),
+                    });
+                }
+                Ok(RefTarget::Local(self.compile_target(schema)?))
+            })?;
+        match target {
+            RefTarget::Local(validators) => Ok(Resolved::Local(schema, validators)),
+            RefTarget::Shared { root, validators } => {
+                if let Some(validators) = self.fallback.get() {
+                    return Ok(Resolved::Local(schema, validators));
+                }
+                if let (Some(root), Some(validators)) = (root.upgrade(), validators.upgrade()) {
+                    return Ok(Resolved::Shared(root, validators));
+                }
+                let validators = self
+                    .fallback
+                    .get_or_try_init(|| self.compile_fallback(schema))?;
+                Ok(Resolved::Local(schema, validators))
+            }
+        }
+    }
+
+    /// Compile the referenced subschema as a part of the current root schema.
+    fn compile_target<'a>(
+        &self,
+        schema: &'a JSONSchema,
+    ) -> Result<Validators, ValidationError<'a>> {
+        let (scope, resolved) = schema.resolver.resolve_fragment(
+            schema.context.config.draft(),
+            &self.reference,
+            &schema.schema,
+        )?;
+        let context = CompilationContext::new(scope, Cow::Borrowed(&schema.context.config))
+            .with_schema_path(self.schema_path.clone());
+        Ok(compile_validators(&resolved, &context)?)
+    }
+
+    /// Compile the referenced subschema after the shared target is dropped.
+    /// The same document could be cached again, then it is taken from the cache instead of being resolved.
+    fn compile_fallback<'a>(
+        &self,
+        schema: &'a JSONSchema,
+    ) -> Result<Validators, ValidationError<'a>> {
+        if let Some(root) = self.cached_root(schema) {
+            let (scope, resolved) = root
+                .resolver
+                .resolve_fragment(root.context.config.draft(), &self.reference, &root.schema)
+                .map_err(ValidationError::into_owned)?;
+            let context = CompilationContext::new(scope, Cow::Borrowed(&schema.context.config))
+                .with_schema_path(self.schema_path.clone());
+            return Ok(compile_validators(&resolved, &context)?);
+        }
+        self.compile_target(schema)
+    }
+
+    /// Find the referenced document among schemas from the same `SchemaCache`, if any.
     #[inline]
-    fn ensure_validators<'a>(&self, schema: &'a JSONSchema) -> Result<(), ValidationError<'a>> {
-        if self.validators.read().is_none() {
-            let (scope, resolved) = schema.resolver.resolve_fragment(
-                schema.context.config.draft(),
-                &self.reference,
-                schema.schema,
-            )?;
-            let context = CompilationContext::new(scope, Cow::Borrowed(&schema.context.config));
-            let validators = compile_validators(&resolved, &context)?;
-
-            // Inject the validators into self.validators
-            *self.validators.write() = Some(validators);
+    fn shared_target(
+        &self,
+        schema: &JSONSchema,
+    ) -> Result<Option<SharedTarget>, ValidationError<'static>> {
+        match self.cached_root(schema) {
+            Some(root) => {
+                let validators = root
+                    .subschema_validators(&self.reference)
+                    .map_err(ValidationError::into_owned)?;
+                Ok(Some((root, validators)))
+            }
+            None => Ok(None),
         }
-        Ok(())
+    }
+
+    fn cached_root(&self, schema: &JSONSchema) -> Option<Arc<JSONSchema<'static>>> {
+        if schema.resolver.is_local(&self.reference) {
+            return None;
+        }
+        let link = schema.context.config.cache_link()?;
+        let mut resource = self.reference.clone();
+        resource.set_fragment(None);
+        link.lookup(resource.as_str())
+    }
+}
+
+/// Errors of validators shared with another schema.
+/// The iterator owns that schema, so it is kept alive until all errors are produced.
+struct SharedErrors<'a> {
+    // Declared first, so it is dropped before the schema it borrows
+    errors: ErrorIterator<'a>,
+    _root: Arc<JSONSchema<'static>>,
+    _validators: Arc<Validators>,
+}
+
+impl<'a> SharedErrors<'a> {
+    fn new(
+        root: Arc<JSONSchema<'static>>,
+        validators: Arc<Validators>,
+        validate: impl FnOnce(&'a JSONSchema<'static>, &'a Validators) -> ErrorIterator<'a>,
+    ) -> Self {
+        // SAFETY: Both values are behind `Arc`s owned by the iterator, so their addresses are stable and they
+        // outlive `errors`. Errors are converted to owned ones, so they don't borrow from the shared schema.
+        let (root_ref, validators_ref) =
+            unsafe { (&*Arc::as_ptr(&root), &*Arc::as_ptr(&validators)) };
+        SharedErrors {
+            errors: validate(root_ref, validators_ref),
+            _root: root,
+            _validators: validators,
+        }
+    }
+}
+
+impl<'a> Iterator for SharedErrors<'a> {
+    type Item = ValidationError<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.errors.next().map(ValidationError::into_owned)
     }
 }
 
@@ -58,17 +206,14 @@ macro_rules! ref_impl_is_valid {
                 instance: &Value,
                 instance_value: $instance_type,
             ) -> bool {
-                if self.ensure_validators(schema).is_err() {
-                    false
-                } else {
-                    self.validators
-                        .read()
-                        .as_ref()
-                        .expect("ensure_validators guarantees the presence of the validators")
-                        .iter()
-                        .all(move |validator| {
-                            validator.[<is_valid_ $method_suffix>](schema, instance, instance_value)
-                        })
+                match self.ensure_validators(schema) {
+                    Ok(Resolved::Local(root, validators)) => validators.iter().all(|validator| {
+                        validator.[<is_valid_ $method_suffix>](root, instance, instance_value)
+                    }),
+                    Ok(Resolved::Shared(root, validators)) => validators.iter().all(|validator| {
+                        validator.[<is_valid_ $method_suffix>](&root, instance, instance_value)
+                    }),
+                    Err(_) => false,
                 }
             }
         }
@@ -79,26 +224,28 @@ macro_rules! ref_impl_validate {
         paste::item! {
             #[inline]
             fn [<validate_ $method_suffix>]<'a>(
-                &self,
+                &'a self,
                 schema: &'a JSONSchema,
                 instance: &'a Value,
                 instance_value: $instance_type,
+                instance_path: &InstancePath,
             ) -> ErrorIterator<'a> {
-                if let Err(err) = self.ensure_validators(schema) {
-                    error(err)
-                } else {
-                    Box::new(
-                        self.validators
-                            .read()
-                            .as_ref()
-                            .expect("ensure_validators guarantees the presence of the validators")
-                            .iter()
-                            .flat_map(move |validator| {
-                                validator.[<validate_ $method_suffix>](schema, instance, instance_value)
-                            })
-                            .collect::<Vec<_>>()
-                            .into_iter(),
-                    )
+                match self.ensure_validators(schema) {
+                    Ok(Resolved::Local(root, validators)) => {
+                        let instance_path = instance_path.clone();
+                        Box::new(validators.iter().flat_map(move |validator| {
+                            validator.[<validate_ $method_suffix>](root, instance, instance_value, &instance_path)
+                        }))
+                    }
+                    Ok(Resolved::Shared(root, validators)) => {
+                        let instance_path = instance_path.clone();
+                        Box::new(SharedErrors::new(root, validators, move |root, validators| {
+                            Box::new(validators.iter().flat_map(move |validator| {
+                                validator.[<validate_ $method_suffix>](root, instance, instance_value, &instance_path)
+                            }))
+                        }))
+                    }
+                    Err(err) => error(err),
                 }
             }
         }
@@ -2,6 +2,7 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::InstancePath,
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -52,16 +53,18 @@ impl Validate for RequiredValidator {
 
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         _: &'a JSONSchema,
         instance: &'a Value,
         instance_value: &'a Map<String, Value>,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
         self.required
             .iter()
             .filter_map(|property_name| {
                 if !instance_value.contains_key(property_name) {
                     Some(error(ValidationError::required(
+                        instance_path.into(),
                         instance,
                         property_name.clone(),
                     )))
@@ -73,9 +76,14 @@ impl Validate for RequiredValidator {
             .unwrap_or_else(no_error)
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
-            self.validate_object(schema, instance, instance_value)
+            self.validate_object(schema, instance, instance_value, instance_path)
         } else {
             no_error()
         }
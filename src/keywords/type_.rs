@@ -2,6 +2,7 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::InstancePath,
     primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
     validator::Validate,
 };
@@ -34,8 +35,12 @@ impl MultipleTypesValidator {
 
 impl Validate for MultipleTypesValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::multiple_type_error(instance, self.types)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::multiple_type_error(instance_path.into(), instance, self.types)
     }
 
     #[inline]
@@ -96,8 +101,12 @@ impl NullTypeValidator {
 
 impl Validate for NullTypeValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::single_type_error(instance, PrimitiveType::Null)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::single_type_error(instance_path.into(), instance, PrimitiveType::Null)
     }
 
     #[inline]
@@ -134,11 +143,16 @@ impl Validate for NullTypeValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Null = instance {
-            self.validate_null(schema, instance, ())
+            self.validate_null(schema, instance, (), instance_path)
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
 }
@@ -159,8 +173,12 @@ impl BooleanTypeValidator {
 
 impl Validate for BooleanTypeValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::single_type_error(instance, PrimitiveType::Boolean)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::single_type_error(instance_path.into(), instance, PrimitiveType::Boolean)
     }
 
     #[inline]
@@ -197,11 +215,16 @@ impl Validate for BooleanTypeValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Bool(instance_value) = instance {
-            self.validate_boolean(schema, instance, *instance_value)
+            self.validate_boolean(schema, instance, *instance_value, instance_path)
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
 }
@@ -222,8 +245,12 @@ impl StringTypeValidator {
 
 impl Validate for StringTypeValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::single_type_error(instance, PrimitiveType::String)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::single_type_error(instance_path.into(), instance, PrimitiveType::String)
     }
 
     #[inline]
@@ -260,11 +287,16 @@ impl Validate for StringTypeValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::String(instance_value) = instance {
-            self.validate_string(schema, instance, instance_value)
+            self.validate_string(schema, instance, instance_value, instance_path)
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
 }
@@ -285,8 +317,12 @@ impl ArrayTypeValidator {
 
 impl Validate for ArrayTypeValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::single_type_error(instance, PrimitiveType::Array)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::single_type_error(instance_path.into(), instance, PrimitiveType::Array)
     }
 
     #[inline]
@@ -323,11 +359,16 @@ impl Validate for ArrayTypeValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Array(instance_value) = instance {
-            self.validate_array(schema, instance, instance_value)
+            self.validate_array(schema, instance, instance_value, instance_path)
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
 }
@@ -348,8 +389,12 @@ impl ObjectTypeValidator {
 
 impl Validate for ObjectTypeValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::single_type_error(instance, PrimitiveType::Object)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::single_type_error(instance_path.into(), instance, PrimitiveType::Object)
     }
 
     #[inline]
@@ -386,11 +431,16 @@ impl Validate for ObjectTypeValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(instance_value) = instance {
-            self.validate_object(schema, instance, instance_value)
+            self.validate_object(schema, instance, instance_value, instance_path)
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
 }
@@ -411,8 +461,12 @@ impl NumberTypeValidator {
 
 impl Validate for NumberTypeValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::single_type_error(instance, PrimitiveType::Number)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::single_type_error(instance_path.into(), instance, PrimitiveType::Number)
     }
 
     #[inline]
@@ -441,11 +495,16 @@ impl Validate for NumberTypeValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        _: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Number(_) = instance {
             no_error()
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
 }
@@ -465,8 +524,12 @@ impl IntegerTypeValidator {
 
 impl Validate for IntegerTypeValidator {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::single_type_error(instance, PrimitiveType::Integer)
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::single_type_error(instance_path.into(), instance, PrimitiveType::Integer)
     }
 
     #[inline]
@@ -506,15 +569,20 @@ impl Validate for IntegerTypeValidator {
     }
 
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Number(_) = instance {
             if self.is_valid(schema, instance) {
                 no_error()
             } else {
-                error(self.build_validation_error(instance))
+                error(self.build_validation_error(instance, instance_path))
             }
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
 }
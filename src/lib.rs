@@ -74,19 +74,54 @@
     variant_size_differences
 )]
 #![cfg_attr(not(test), allow(clippy::integer_arithmetic, clippy::unwrap_used))]
+mod best_match;
+mod bundler;
+mod compatibility;
 mod compilation;
 mod content_encoding;
 mod content_media_type;
+mod dereference;
 mod error;
+mod generator;
+mod inclusion;
 mod keywords;
+mod lint;
+mod location;
+mod normalizer;
+mod paths;
 mod primitive_type;
+mod profile;
 mod resolver;
+mod satisfiability;
+mod schema_check;
 mod schemas;
+mod trace;
 mod validator;
-pub use compilation::{options::CompilationOptions, JSONSchema};
-pub use error::{CompilationError, ErrorIterator, ValidationError};
-pub use schemas::Draft;
+pub use best_match::best_match;
+pub use compatibility::{ChangeKind, Compatibility, CompatibilityReport, SchemaChange};
+pub use compilation::{
+    cache::SchemaCache,
+    introspection::{KeywordIter, KeywordNode, SchemaNode},
+    options::CompilationOptions,
+    subschema::SubSchema,
+    JSONSchema,
+};
+pub use dereference::{DereferenceError, RecursiveReference};
+pub use error::{
+    CompilationError, DefaultMessageFormatter, ErrorIterator, MessageFormatter, TypeKind,
+    ValidationError, ValidationErrorKind,
+};
+pub use generator::{InstanceGenerator, InvalidInstance};
+pub use inclusion::{is_subschema, Counterexample, SubschemaCheck};
+pub use lint::{Diagnostic, LintLevel, LintRule, Linter};
+pub use paths::{JSONPointer, PathChunk};
+pub use primitive_type::{PrimitiveType, PrimitiveTypesBitMap, PrimitiveTypesBitMapIterator};
+pub use profile::{Profile, ProfileEntry};
+pub use satisfiability::{UnsatisfiableKind, UnsatisfiableSchema};
+pub use schema_check::{SchemaProblem, SchemaProblemKind};
+pub use schemas::{Draft, OpenAPIDirection};
 use serde_json::Value;
+pub use trace::{Trace, TraceNode};
 
 /// A shortcut for validating `instance` against `schema`. Draft version is detected automatically.
 /// ```rust
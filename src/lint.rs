@@ -0,0 +1,585 @@
+//! Warnings about risky patterns in valid schemas.
+//!
+//! Every rule is checked against each schema object independently, references are not followed.
+//! The only exception is `unsatisfiable`, which relies on the satisfiability analysis of the whole document.
+use crate::{
+    compilation::DEFAULT_SCOPE,
+    keywords::pattern::convert_regex,
+    normalizer::{COMBINATORS, SUBSCHEMA_KEYWORDS, SUBSCHEMA_MAP_KEYWORDS},
+    paths::JSONPointer,
+    resolver::Resolver,
+    satisfiability::{self, UnsatisfiableKind, UnsatisfiableSchema},
+    schemas::{draft_from_schema, id_of, Draft},
+};
+use regex::Regex;
+use regex_syntax::{
+    hir::{Hir, HirKind, Look},
+    Parser,
+};
+use serde_json::{Map, Value};
+use std::{collections::HashMap, fmt, str::FromStr};
+use url::Url;
+
+/// A lint rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintRule {
+    /// An object schema doesn't set `additionalProperties`, so any extra property is accepted.
+    MissingAdditionalProperties,
+    /// A `required` property is not declared in `properties` of the same schema.
+    UndeclaredRequired,
+    /// A lower limit is greater than the corresponding upper limit, e.g. `minimum` > `maximum`.
+    InvalidRange,
+    /// A `pattern` is not anchored with `^` and `$`, so it matches substrings.
+    UnanchoredPattern,
+    /// Keywords next to `$ref` are ignored during validation.
+    IgnoredRefSiblings,
+    /// No instance can satisfy the schema.
+    Unsatisfiable,
+    /// The root `$id` is not an absolute URI, so references can't be resolved against it.
+    InvalidId,
+}
+
+/// All rules in the order of their declaration.
+const ALL_RULES: &[LintRule] = &[
+    LintRule::MissingAdditionalProperties,
+    LintRule::UndeclaredRequired,
+    LintRule::InvalidRange,
+    LintRule::UnanchoredPattern,
+    LintRule::IgnoredRefSiblings,
+    LintRule::Unsatisfiable,
+    LintRule::InvalidId,
+];
+
+impl LintRule {
+    /// The rule name, e.g. `invalid-range`.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            LintRule::MissingAdditionalProperties => "missing-additional-properties",
+            LintRule::UndeclaredRequired => "undeclared-required",
+            LintRule::InvalidRange => "invalid-range",
+            LintRule::UnanchoredPattern => "unanchored-pattern",
+            LintRule::IgnoredRefSiblings => "ignored-ref-siblings",
+            LintRule::Unsatisfiable => "unsatisfiable",
+            LintRule::InvalidId => "invalid-id",
+        }
+    }
+
+    const fn default_level(self) -> LintLevel {
+        match self {
+            LintRule::InvalidRange | LintRule::Unsatisfiable | LintRule::InvalidId => {
+                LintLevel::Deny
+            }
+            _ => LintLevel::Warn,
+        }
+    }
+}
+
+impl fmt::Display for LintRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for LintRule {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        ALL_RULES
+            .iter()
+            .copied()
+            .find(|rule| rule.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<_> = ALL_RULES.iter().map(|rule| rule.name()).collect();
+                format!(
+                    "Unknown lint rule '{}', expected one of: {}",
+                    name,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// How a rule violation is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    /// The rule is not checked.
+    Allow,
+    /// Violations are reported as warnings.
+    Warn,
+    /// Violations are reported as errors.
+    Deny,
+}
+
+/// A single rule violation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// The violated rule.
+    pub rule: LintRule,
+    /// Either `LintLevel::Warn` or `LintLevel::Deny`.
+    pub level: LintLevel,
+    /// Location of the offending keyword.
+    pub schema_path: JSONPointer,
+    /// Human-readable description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = if self.level == LintLevel::Deny {
+            "error"
+        } else {
+            "warning"
+        };
+        write!(
+            f,
+            "{}[{}] at '{}': {}",
+            level, self.rule, self.schema_path, self.message
+        )
+    }
+}
+
+/// Checks schemas against a configurable set of rules.
+///
+/// ```rust
+/// # use jsonschema::{LintLevel, LintRule, Linter};
+/// # use serde_json::json;
+/// let schema = json!({
+///     "type": "object",
+///     "properties": {"code": {"type": "string", "pattern": "[A-Z]{3}"}},
+///     "required": ["code", "name"]
+/// });
+/// let diagnostics = Linter::default()
+///     .with_level(LintRule::MissingAdditionalProperties, LintLevel::Allow)
+///     .lint(&schema);
+/// let messages: Vec<_> = diagnostics.iter().map(ToString::to_string).collect();
+/// assert_eq!(
+///     messages,
+///     vec![
+///         "warning[undeclared-required] at '/required/1': Required property 'name' is not declared in `properties`",
+///         "warning[unanchored-pattern] at '/properties/code/pattern': Pattern '[A-Z]{3}' is not anchored with `^` and `$`, so it matches substrings",
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Linter {
+    draft: Option<Draft>,
+    levels: HashMap<LintRule, LintLevel>,
+}
+
+impl Linter {
+    /// Set the draft of linted schemas. By default it is detected from `$schema`.
+    pub fn with_draft(&mut self, draft: Draft) -> &mut Self {
+        self.draft = Some(draft);
+        self
+    }
+
+    /// Change how violations of `rule` are reported.
+    pub fn with_level(&mut self, rule: LintRule, level: LintLevel) -> &mut Self {
+        self.levels.insert(rule, level);
+        self
+    }
+
+    fn level(&self, rule: LintRule) -> LintLevel {
+        self.levels
+            .get(&rule)
+            .copied()
+            .unwrap_or_else(|| rule.default_level())
+    }
+
+    /// Check `schema` and return all rule violations in the order of their appearance.
+    #[must_use]
+    pub fn lint(&self, schema: &Value) -> Vec<Diagnostic> {
+        let draft = self
+            .draft
+            .or_else(|| draft_from_schema(schema))
+            .unwrap_or_default();
+        let (scope, invalid_id) = match id_of(draft, schema).map(|id| (id, Url::parse(id))) {
+            Some((_, Ok(scope))) => (Some(scope), None),
+            Some((id, Err(error))) => (None, Some((id, error))),
+            None => (Some(DEFAULT_SCOPE.clone()), None),
+        };
+        // The satisfiability analysis resolves references, which is not possible without a valid scope
+        let unsatisfiable = match scope {
+            Some(scope) if self.level(LintRule::Unsatisfiable) != LintLevel::Allow => {
+                Resolver::new(draft, &scope, schema)
+                    .map(|resolver| satisfiability::analyze(draft, schema, &scope, &resolver))
+                    .unwrap_or_default()
+            }
+            _ => Vec::new(),
+        };
+        let mut lint = Lint {
+            linter: self,
+            draft,
+            unsatisfiable,
+            diagnostics: Vec::new(),
+        };
+        if let Some((id, error)) = invalid_id {
+            let keyword = if draft == Draft::Draft4 { "id" } else { "$id" };
+            lint.report(
+                LintRule::InvalidId,
+                JSONPointer::default().join(keyword),
+                format!(
+                    "Root `{}` '{}' is not an absolute URI ({}), references can't be resolved",
+                    keyword, id, error
+                ),
+            );
+        }
+        lint.walk(schema, &JSONPointer::default());
+        lint.diagnostics
+    }
+}
+
+struct Lint<'l> {
+    linter: &'l Linter,
+    draft: Draft,
+    /// Findings of the satisfiability analysis, reported at their locations.
+    unsatisfiable: Vec<UnsatisfiableSchema>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'l> Lint<'l> {
+    fn report(&mut self, rule: LintRule, schema_path: JSONPointer, message: String) {
+        let level = self.linter.level(rule);
+        if level != LintLevel::Allow {
+            self.diagnostics.push(Diagnostic {
+                rule,
+                level,
+                schema_path,
+                message,
+            })
+        }
+    }
+
+    fn walk(&mut self, schema: &Value, path: &JSONPointer) {
+        let object = match schema {
+            Value::Object(object) => object,
+            _ => return,
+        };
+        if object.contains_key("$ref") {
+            self.ignored_siblings(object, path);
+        } else {
+            self.additional_properties(object, path);
+            self.required(object, path);
+            self.ranges(object, path);
+            self.pattern(object, path);
+            self.unsatisfiable(path);
+        }
+        for (keyword, value) in object {
+            let keyword_path = path.join(keyword.as_str());
+            let keyword = keyword.as_str();
+            match value {
+                Value::Array(items) if COMBINATORS.contains(&keyword) || keyword == "items" => {
+                    for (idx, item) in items.iter().enumerate() {
+                        self.walk(item, &keyword_path.join(idx));
+                    }
+                }
+                Value::Object(entries) if SUBSCHEMA_MAP_KEYWORDS.contains(&keyword) => {
+                    for (name, value) in entries {
+                        self.walk(value, &keyword_path.join(name.as_str()));
+                    }
+                }
+                _ if SUBSCHEMA_KEYWORDS.contains(&keyword) => self.walk(value, &keyword_path),
+                _ => {}
+            }
+        }
+    }
+
+    fn ignored_siblings(&mut self, object: &Map<String, Value>, path: &JSONPointer) {
+        for keyword in object.keys() {
+            if keyword != "$ref" && self.draft.get_validator(keyword).is_some() {
+                self.report(
+                    LintRule::IgnoredRefSiblings,
+                    path.join(keyword.as_str()),
+                    format!("`{}` is ignored next to `$ref`", keyword),
+                );
+            }
+        }
+    }
+
+    fn additional_properties(&mut self, object: &Map<String, Value>, path: &JSONPointer) {
+        let is_object = match object.get("type") {
+            Some(Value::String(name)) => name == "object",
+            Some(Value::Array(names)) => names.iter().any(|name| name == "object"),
+            _ => object.contains_key("properties"),
+        };
+        if is_object && !object.contains_key("additionalProperties") {
+            self.report(
+                LintRule::MissingAdditionalProperties,
+                path.clone(),
+                "Object schema accepts any additional properties, consider setting `additionalProperties`"
+                    .to_string(),
+            );
+        }
+    }
+
+    fn required(&mut self, object: &Map<String, Value>, path: &JSONPointer) {
+        let (properties, required) = match (object.get("properties"), object.get("required")) {
+            (Some(Value::Object(properties)), Some(Value::Array(required))) => {
+                (properties, required)
+            }
+            _ => return,
+        };
+        let patterns: Vec<Regex> = match object.get("patternProperties") {
+            Some(Value::Object(patterns)) => patterns
+                .keys()
+                .filter_map(|pattern| Regex::new(&convert_regex(pattern)).ok())
+                .collect(),
+            _ => Vec::new(),
+        };
+        for (idx, name) in required.iter().enumerate() {
+            if let Value::String(name) = name {
+                if !properties.contains_key(name)
+                    && !patterns.iter().any(|pattern| pattern.is_match(name))
+                {
+                    self.report(
+                        LintRule::UndeclaredRequired,
+                        path.join("required").join(idx),
+                        format!(
+                            "Required property '{}' is not declared in `properties`",
+                            name
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn ranges(&mut self, object: &Map<String, Value>, path: &JSONPointer) {
+        for (subject, low, high) in &[
+            ("string length", "minLength", "maxLength"),
+            ("array length", "minItems", "maxItems"),
+            ("number of properties", "minProperties", "maxProperties"),
+        ] {
+            if let (Some(low_value), Some(high_value)) = (
+                object.get(*low).and_then(Value::as_f64),
+                object.get(*high).and_then(Value::as_f64),
+            ) {
+                if low_value > high_value {
+                    self.report(
+                        LintRule::InvalidRange,
+                        path.join(*low),
+                        format!(
+                            "No {} satisfies `{}` = {} and `{}` = {}",
+                            subject, low, object[*low], high, object[*high]
+                        ),
+                    );
+                }
+            }
+        }
+        let legacy = matches!(self.draft, Draft::Draft4 | Draft::OpenAPI30);
+        let mut lows = Vec::new();
+        let mut highs = Vec::new();
+        if let Some(minimum) = object.get("minimum").and_then(Value::as_f64) {
+            let exclusive =
+                legacy && matches!(object.get("exclusiveMinimum"), Some(Value::Bool(true)));
+            lows.push(("minimum", minimum, exclusive));
+        }
+        if let Some(maximum) = object.get("maximum").and_then(Value::as_f64) {
+            let exclusive =
+                legacy && matches!(object.get("exclusiveMaximum"), Some(Value::Bool(true)));
+            highs.push(("maximum", maximum, exclusive));
+        }
+        if !legacy {
+            if let Some(minimum) = object.get("exclusiveMinimum").and_then(Value::as_f64) {
+                lows.push(("exclusiveMinimum", minimum, true));
+            }
+            if let Some(maximum) = object.get("exclusiveMaximum").and_then(Value::as_f64) {
+                highs.push(("exclusiveMaximum", maximum, true));
+            }
+        }
+        for (low, low_value, low_exclusive) in &lows {
+            for (high, high_value, high_exclusive) in &highs {
+                if low_value > high_value
+                    || (low_value == high_value && (*low_exclusive || *high_exclusive))
+                {
+                    self.report(
+                        LintRule::InvalidRange,
+                        path.join(*low),
+                        format!(
+                            "No number satisfies `{}` = {} and `{}` = {}",
+                            low, object[*low], high, object[*high]
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn pattern(&mut self, object: &Map<String, Value>, path: &JSONPointer) {
+        if let Some(Value::String(pattern)) = object.get("pattern") {
+            if !is_anchored(pattern) {
+                self.report(
+                    LintRule::UnanchoredPattern,
+                    path.join("pattern"),
+                    format!(
+                        "Pattern '{}' is not anchored with `^` and `$`, so it matches substrings",
+                        pattern
+                    ),
+                );
+            }
+        }
+    }
+
+    fn unsatisfiable(&mut self, path: &JSONPointer) {
+        let found: Vec<_> = self
+            .unsatisfiable
+            .iter()
+            .filter(|finding| &finding.schema_path == path)
+            .map(|finding| match finding.kind {
+                UnsatisfiableKind::Schema => format!("No instance is valid: {}", finding.reason),
+                UnsatisfiableKind::DeadBranch => {
+                    format!("Branch never matches: {}", finding.reason)
+                }
+                UnsatisfiableKind::ImpossibleProperty => finding.reason.clone(),
+            })
+            .collect();
+        for message in found {
+            self.report(LintRule::Unsatisfiable, path.clone(), message);
+        }
+    }
+}
+
+/// Whether every top-level alternative of `pattern` starts with `^` and ends with `$`.
+fn is_anchored(pattern: &str) -> bool {
+    /// Whether every alternative at this level has the `look` assertion at its start or at its end.
+    fn is_bounded(hir: &Hir, look: Look) -> bool {
+        match hir.kind() {
+            HirKind::Look(found) => *found == look,
+            HirKind::Alternation(alternatives) => {
+                alternatives.iter().all(|hir| is_bounded(hir, look))
+            }
+            HirKind::Capture(capture) => is_bounded(&capture.sub, look),
+            HirKind::Concat(items) => {
+                let item = if look == Look::Start {
+                    items.first()
+                } else {
+                    items.last()
+                };
+                item.is_some_and(|item| is_bounded(item, look))
+            }
+            _ => false,
+        }
+    }
+    match Parser::new().parse(&convert_regex(pattern)) {
+        // Alternatives could be regrouped by the parser, e.g. `^a$|^b$` becomes `^(?:a$|b$)`
+        Ok(hir) => is_bounded(&hir, Look::Start) && is_bounded(&hir, Look::End),
+        // Invalid patterns are rejected during compilation
+        Err(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LintLevel, LintRule, Linter};
+    use crate::schemas::Draft;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn lint(schema: &Value) -> Vec<(LintRule, String)> {
+        Linter::default()
+            .lint(schema)
+            .into_iter()
+            .map(|diagnostic| (diagnostic.rule, diagnostic.schema_path.to_string()))
+            .collect()
+    }
+
+    #[test_case(json!({"type": "object"}), LintRule::MissingAdditionalProperties, ""; "missing additional properties")]
+    #[test_case(json!({"items": {"properties": {}}}), LintRule::MissingAdditionalProperties, "/items"; "implied object")]
+    #[test_case(
+        json!({"properties": {"a": {}}, "required": ["a", "b"], "additionalProperties": false}),
+        LintRule::UndeclaredRequired, "/required/1"; "undeclared required"
+    )]
+    #[test_case(json!({"minimum": 5, "maximum": 3}), LintRule::InvalidRange, "/minimum"; "minimum")]
+    #[test_case(json!({"exclusiveMinimum": 3, "maximum": 3}), LintRule::InvalidRange, "/exclusiveMinimum"; "exclusive minimum")]
+    #[test_case(json!({"minLength": 5, "maxLength": 3}), LintRule::InvalidRange, "/minLength"; "length")]
+    #[test_case(json!({"anyOf": [{"minItems": 2, "maxItems": 1}]}), LintRule::InvalidRange, "/anyOf/0/minItems"; "nested")]
+    #[test_case(json!({"pattern": "^a"}), LintRule::UnanchoredPattern, "/pattern"; "unanchored pattern")]
+    #[test_case(json!({"pattern": "^a\\$"}), LintRule::UnanchoredPattern, "/pattern"; "escaped dollar")]
+    #[test_case(json!({"pattern": "^a|b$"}), LintRule::UnanchoredPattern, "/pattern"; "alternatives")]
+    #[test_case(json!({"pattern": "^a$|b"}), LintRule::UnanchoredPattern, "/pattern"; "last alternative")]
+    #[test_case(json!({"$ref": "#/definitions/a", "minimum": 1, "definitions": {"a": {}}}), LintRule::IgnoredRefSiblings, "/minimum"; "ignored ref siblings")]
+    #[test_case(json!({"type": "string", "const": 1}), LintRule::Unsatisfiable, ""; "constant")]
+    #[test_case(json!({"type": "integer", "enum": [1.5, "a"]}), LintRule::Unsatisfiable, ""; "enumeration")]
+    #[test_case(json!({"type": "string", "anyOf": [{"minLength": 1}, {"type": "integer"}]}), LintRule::Unsatisfiable, "/anyOf/1"; "dead branch")]
+    fn diagnostic(schema: Value, rule: LintRule, path: &str) {
+        assert_eq!(lint(&schema), vec![(rule, path.to_string())]);
+    }
+
+    #[test_case(json!({"type": "object", "additionalProperties": {"type": "string"}}); "additional properties")]
+    #[test_case(json!({"properties": {"a": {}}, "patternProperties": {"^x-": {}}, "required": ["a", "x-b"], "additionalProperties": false}); "pattern properties")]
+    #[test_case(json!({"minimum": 3, "maximum": 3}); "equal limits")]
+    #[test_case(json!({"pattern": "^a$"}); "anchored pattern")]
+    #[test_case(json!({"pattern": "^(a|b)$"}); "anchored group")]
+    #[test_case(json!({"pattern": "^a$|^b$"}); "anchored alternatives")]
+    #[test_case(json!({"pattern": "^$"}); "empty pattern")]
+    #[test_case(json!({"$ref": "#/definitions/a", "description": "A", "definitions": {"a": {}}}); "annotations next to ref")]
+    #[test_case(json!({"type": "integer", "enum": [1.0]}); "integer enum")]
+    #[test_case(json!({"const": {"minimum": 5, "maximum": 3}}); "data keywords")]
+    fn no_diagnostics(schema: Value) {
+        assert_eq!(lint(&schema), vec![]);
+    }
+
+    #[test]
+    fn levels() {
+        let schema = json!({"type": "object", "pattern": "a", "minLength": 2, "maxLength": 1});
+        let diagnostics = Linter::default()
+            .with_level(LintRule::MissingAdditionalProperties, LintLevel::Allow)
+            .with_level(LintRule::UnanchoredPattern, LintLevel::Deny)
+            .with_level(LintRule::InvalidRange, LintLevel::Warn)
+            .lint(&schema);
+        let levels: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.rule, diagnostic.level))
+            .collect();
+        assert_eq!(
+            levels,
+            vec![
+                (LintRule::InvalidRange, LintLevel::Warn),
+                (LintRule::UnanchoredPattern, LintLevel::Deny)
+            ]
+        );
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "warning[invalid-range] at '/minLength': No string length satisfies `minLength` = 2 and `maxLength` = 1"
+        );
+    }
+
+    #[test]
+    fn draft4() {
+        let schema = json!({"minimum": 3, "maximum": 3, "exclusiveMaximum": true});
+        assert!(Linter::default().lint(&schema).is_empty());
+        let diagnostics = Linter::default().with_draft(Draft::Draft4).lint(&schema);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule, LintRule::InvalidRange);
+    }
+
+    #[test]
+    fn invalid_id() {
+        let schema =
+            json!({"$id": "not a uri", "minimum": 5, "maximum": 3, "type": "string", "const": 1});
+        let diagnostics = Linter::default().lint(&schema);
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.rule, diagnostic.schema_path.to_string()))
+            .collect();
+        // Only the reference-dependent analysis is skipped
+        assert_eq!(
+            found,
+            vec![
+                (LintRule::InvalidId, "/$id".to_string()),
+                (LintRule::InvalidRange, "/minimum".to_string())
+            ]
+        );
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "error[invalid-id] at '/$id': Root `$id` 'not a uri' is not an absolute URI (relative URL without a base), references can't be resolved"
+        );
+    }
+
+    #[test]
+    fn rule_names() {
+        assert_eq!("invalid-range".parse(), Ok(LintRule::InvalidRange));
+        assert!("unknown".parse::<LintRule>().is_err());
+    }
+}
@@ -0,0 +1,122 @@
+//! Locations of failed keywords within the schema.
+//!
+//! Every compiled keyword is stored in a `KeywordNode` together with its location, which is attached to the errors
+//! produced by the keyword. Nested keywords attach their locations first, therefore the innermost location wins.
+//! Locations are not needed to check whether an instance is valid, so `is_valid` goes straight to the validator.
+use crate::{
+    compilation::JSONSchema,
+    error::ValidationError,
+    keywords::BoxedValidator,
+    paths::{InstancePath, JSONPointer},
+};
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// A compiled keyword together with its location within the schema.
+pub(crate) struct KeywordNode {
+    pub(crate) validator: BoxedValidator,
+    pub(crate) schema_path: JSONPointer,
+}
+
+macro_rules! location_impl {
+    ($method_suffix:tt, $instance_type: ty, $validate_type: ty) => {
+        paste::item! {
+            #[inline]
+            pub(crate) fn [<is_valid_ $method_suffix>](
+                &self,
+                schema: &JSONSchema,
+                instance: &Value,
+                instance_value: $instance_type,
+            ) -> bool {
+                self.validator.[<is_valid_ $method_suffix>](schema, instance, instance_value)
+            }
+
+            #[inline]
+            pub(crate) fn [<validate_ $method_suffix>]<'a>(
+                &'a self,
+                schema: &'a JSONSchema,
+                instance: &'a Value,
+                instance_value: $validate_type,
+                instance_path: &InstancePath,
+            ) -> impl Iterator<Item = ValidationError<'a>> + Send + Sync + 'a {
+                self.validator
+                    .[<validate_ $method_suffix>](schema, instance, instance_value, instance_path)
+                    .map(move |error| error.with_schema_path(&self.schema_path))
+            }
+        }
+    };
+}
+
+impl KeywordNode {
+    #[inline]
+    pub(crate) fn new(validator: BoxedValidator, schema_path: JSONPointer) -> Self {
+        KeywordNode {
+            validator,
+            schema_path,
+        }
+    }
+
+    location_impl!(array, &[Value], &'a [Value]);
+    location_impl!(boolean, bool, bool);
+    location_impl!(null, (), ());
+    location_impl!(number, f64, f64);
+    location_impl!(object, &Map<String, Value>, &'a Map<String, Value>);
+    location_impl!(signed_integer, i64, i64);
+    location_impl!(string, &str, &'a str);
+    location_impl!(unsigned_integer, u64, u64);
+
+    #[inline]
+    pub(crate) fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        self.validator.is_valid(schema, instance)
+    }
+
+    #[inline]
+    pub(crate) fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> impl Iterator<Item = ValidationError<'a>> + Send + Sync + 'a {
+        self.validator
+            .validate(schema, instance, instance_path)
+            .map(move |error| error.with_schema_path(&self.schema_path))
+    }
+}
+
+impl ToString for KeywordNode {
+    fn to_string(&self) -> String {
+        self.validator.to_string()
+    }
+}
+
+impl fmt::Debug for KeywordNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.validator.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compilation::JSONSchema;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(&json!({"type": "string"}), &json!(1), &["/type"]; "keyword")]
+    #[test_case(&json!({"properties": {"a": {"minimum": 5}}}), &json!({"a": 1}), &["/properties/a/minimum"]; "nested keyword")]
+    #[test_case(&json!({"properties": {"a": false}}), &json!({"a": 1}), &["/properties/a"]; "false schema")]
+    #[test_case(&json!({"items": [{"type": "string"}, {"type": "null"}]}), &json!([1, 2]), &["/items/0/type", "/items/1/type"]; "items")]
+    #[test_case(&json!({"anyOf": [{"type": "string"}, {"type": "null"}]}), &json!(1), &["/anyOf"]; "any of")]
+    #[test_case(&json!({"not": {"type": "integer"}}), &json!(1), &["/not"]; "not")]
+    #[test_case(&json!({"if": {"type": "integer"}, "then": {"minimum": 5}}), &json!(1), &["/then/minimum"]; "then")]
+    #[test_case(&json!({"definitions": {"a": {"maxLength": 1}}, "properties": {"a": {"$ref": "#/definitions/a"}}}), &json!({"a": "abc"}), &["/properties/a/$ref/maxLength"]; "reference")]
+    #[test_case(&json!({"type": "object", "errorMessage": "Wrong"}), &json!(1), &["/type"]; "error message")]
+    fn schema_path(schema: &Value, instance: &Value, expected: &[&str]) {
+        let compiled = JSONSchema::options()
+            .with_error_messages()
+            .compile(schema)
+            .unwrap();
+        let errors = compiled.validate(instance).expect_err("Should fail");
+        let paths: Vec<_> = errors.map(|error| error.schema_path.to_string()).collect();
+        assert_eq!(paths, expected);
+    }
+}
@@ -1,79 +1,1058 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::error::Error;
-use std::path::PathBuf;
-use std::{fs, process};
+use std::io::Read;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{mpsc, Arc};
+use std::{fs, io, process, thread};
 
-use jsonschema::JSONSchema;
-use structopt::StructOpt;
+use jsonschema::{
+    CompilationOptions, Draft, JSONPointer, JSONSchema, LintLevel, LintRule, Linter, PathChunk,
+    SchemaProblemKind, ValidationError, ValidationErrorKind,
+};
+use once_cell::sync::OnceCell;
+use rayon::prelude::*;
+use serde_json::{json, Value};
+use structopt::{clap::AppSettings, StructOpt};
+use url::Url;
+use walkdir::WalkDir;
 
 type BoxErrorResult<T> = Result<T, Box<dyn Error>>;
 
+/// Exit code when at least one instance is invalid.
+const EXIT_INVALID: i32 = 1;
+/// Exit code when the schema or an instance can't be read or parsed, or the arguments are wrong.
+const EXIT_ERROR: i32 = 2;
+
+/// How validation results are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Output {
+    /// A human-readable list of errors for every instance.
+    Text,
+    /// A JSON array of errors.
+    Json,
+    /// A JSON object with an error per line.
+    Ndjson,
+    /// The "basic" output format from the JSON Schema specification for every instance.
+    Basic,
+    /// The "flag" output format from the JSON Schema specification for every instance.
+    Flag,
+}
+
+impl FromStr for Output {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "text" => Ok(Output::Text),
+            "json" => Ok(Output::Json),
+            "ndjson" => Ok(Output::Ndjson),
+            "basic" => Ok(Output::Basic),
+            "flag" => Ok(Output::Flag),
+            _ => Err(format!("Unknown output format '{}'", name)),
+        }
+    }
+}
+
+/// A format of schema and instance files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Format {
+    #[default]
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "json" => Ok(Format::Json),
+            "yaml" => Ok(Format::Yaml),
+            "toml" => Ok(Format::Toml),
+            _ => Err(format!("Unknown format '{}'", name)),
+        }
+    }
+}
+
+impl Format {
+    /// Detect the format by the file extension.
+    fn detect(path: &Path) -> Option<Format> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") | Some("ndjson") | Some("jsonl") => Some(Format::Json),
+            Some("yaml") | Some("yml") => Some(Format::Yaml),
+            Some("toml") => Some(Format::Toml),
+            _ => None,
+        }
+    }
+
+    /// Parse all documents from `content`.
+    ///
+    /// JSON input is a single document unless `stream` is set, e.g. for stdin or newline-delimited JSON,
+    /// YAML documents are separated by `---`, and TOML input is always a single document. Parsing errors
+    /// contain line and column numbers.
+    fn parse(self, content: &str, stream: bool) -> BoxErrorResult<Vec<Value>> {
+        let documents = match self {
+            Format::Json if stream => serde_json::Deserializer::from_str(content)
+                .into_iter::<Value>()
+                .collect::<Result<Vec<_>, _>>()?,
+            Format::Json => vec![serde_json::from_str(content)?],
+            Format::Yaml => parse_yaml(content)?,
+            Format::Toml => vec![parse_toml(content)?],
+        };
+        if documents.is_empty() {
+            return Err("No documents found".into());
+        }
+        Ok(documents)
+    }
+
+    /// Parse all documents from `content` along with their sources to locate invalid values.
+    ///
+    /// Sources are available only for JSON documents.
+    fn parse_sources(
+        self,
+        name: &str,
+        content: &str,
+        stream: bool,
+    ) -> BoxErrorResult<Vec<(Value, Option<Source>)>> {
+        if self != Format::Json {
+            return Ok(self
+                .parse(content, stream)?
+                .into_iter()
+                .map(|document| (document, None))
+                .collect());
+        }
+        let content: Arc<str> = Arc::from(content);
+        if !stream {
+            let document = serde_json::from_str(&content)?;
+            return Ok(vec![(document, Some(Source::new(name, content, 0)))]);
+        }
+        let mut stream = serde_json::Deserializer::from_str(&content).into_iter::<Value>();
+        let mut documents = Vec::new();
+        let mut start = 0;
+        while let Some(document) = stream.next() {
+            let end = stream.byte_offset();
+            let source = Source::new(name, Arc::clone(&content), start);
+            documents.push((document?, Some(source)));
+            start = end;
+        }
+        if documents.is_empty() {
+            return Err("No documents found".into());
+        }
+        Ok(documents)
+    }
+}
+
+/// Whether the file is a stream of JSON documents, i.e. newline-delimited JSON.
+fn is_stream(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|extension| extension.to_str()),
+        Some("ndjson") | Some("jsonl")
+    )
+}
+
+/// A JSON document in its source file.
+struct Source {
+    name: String,
+    content: Arc<str>,
+    /// The byte offset of the document in `content`.
+    start: usize,
+    /// Byte spans of all values in the document, keyed by their JSON Pointers.
+    /// They are recorded only when the first invalid value is located.
+    spans: OnceCell<HashMap<String, Range<usize>>>,
+}
+
+impl Source {
+    /// A document starting at the `start` byte of `content`.
+    fn new(name: &str, content: Arc<str>, start: usize) -> Self {
+        Source {
+            name: name.to_string(),
+            content,
+            start,
+            spans: OnceCell::new(),
+        }
+    }
+
+    /// Find the value at the given path in the source file.
+    fn locate(&self, path: &JSONPointer) -> Option<Location> {
+        let spans = self.spans.get_or_init(|| {
+            let mut scanner = Scanner {
+                content: &self.content,
+                position: self.start,
+                spans: HashMap::new(),
+            };
+            scanner.value(&mut String::new());
+            scanner.spans
+        });
+        let span = spans.get(&path.to_string())?;
+        let content = &*self.content;
+        let line_start = content[..span.start].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = content[span.start..]
+            .find('\n')
+            .map_or(content.len(), |idx| span.start + idx);
+        let text = content[line_start..line_end].trim_end_matches('\r');
+        let prefix = &content[line_start..span.start];
+        Some(Location {
+            file: self.name.clone(),
+            line: content[..span.start].matches('\n').count() + 1,
+            column: prefix.chars().count() + 1,
+            text: text.to_string(),
+            // Tabs are kept to align the marker with the source line
+            indent: prefix
+                .chars()
+                .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+                .collect(),
+            width: content[span.start..span.end.min(line_start + text.len())]
+                .chars()
+                .count()
+                .max(1),
+        })
+    }
+}
+
+/// Records byte spans of values in a syntactically valid JSON document.
+struct Scanner<'a> {
+    content: &'a str,
+    position: usize,
+    spans: HashMap<String, Range<usize>>,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.content.as_bytes().get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn value(&mut self, pointer: &mut String) {
+        self.skip_whitespace();
+        let start = self.position;
+        match self.peek() {
+            Some(b'{') => {
+                self.position += 1;
+                loop {
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b'"') => {
+                            let key_start = self.position;
+                            self.string();
+                            let key: String =
+                                serde_json::from_str(&self.content[key_start..self.position])
+                                    .unwrap_or_default();
+                            self.skip_whitespace();
+                            // Skip `:`
+                            self.position += 1;
+                            let length = pointer.len();
+                            pointer.push('/');
+                            pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                            self.value(pointer);
+                            pointer.truncate(length);
+                        }
+                        Some(b',') => self.position += 1,
+                        Some(b'}') => {
+                            self.position += 1;
+                            break;
+                        }
+                        _ => break,
+                    }
+                }
+            }
+            Some(b'[') => {
+                self.position += 1;
+                let mut idx = 0;
+                loop {
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.position += 1,
+                        Some(b']') => {
+                            self.position += 1;
+                            break;
+                        }
+                        Some(b'}') | None => break,
+                        Some(_) => {
+                            let length = pointer.len();
+                            pointer.push('/');
+                            pointer.push_str(&idx.to_string());
+                            self.value(pointer);
+                            pointer.truncate(length);
+                            idx += 1;
+                        }
+                    }
+                }
+            }
+            Some(b'"') => self.string(),
+            Some(_) => {
+                while matches!(self.peek(), Some(byte) if !b",]} \t\n\r".contains(&byte)) {
+                    self.position += 1;
+                }
+            }
+            None => return,
+        }
+        self.spans.insert(pointer.clone(), start..self.position);
+    }
+
+    fn string(&mut self) {
+        // Skip the opening quote
+        self.position += 1;
+        while let Some(byte) = self.peek() {
+            self.position += 1;
+            match byte {
+                b'\\' => self.position += 1,
+                b'"' => break,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// A position of an invalid value in its source file.
+struct Location {
+    file: String,
+    line: usize,
+    column: usize,
+    /// The source line with the value.
+    text: String,
+    /// Whitespace before the value in its source line.
+    indent: String,
+    /// The number of characters to mark.
+    width: usize,
+}
+
+impl Location {
+    /// A compiler-style diagnostic that marks the value in its source line.
+    fn diagnostic(&self, message: &str) -> String {
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        format!(
+            "{message}\n{gutter}--> {}:{}:{}\n{gutter} |\n{number} | {}\n{gutter} | {}{}",
+            self.file,
+            self.line,
+            self.column,
+            self.text,
+            self.indent,
+            "^".repeat(self.width),
+            message = message,
+            gutter = gutter,
+            number = number,
+        )
+    }
+}
+
+#[cfg(feature = "yaml")]
+fn parse_yaml(content: &str) -> BoxErrorResult<Vec<Value>> {
+    use serde::Deserialize;
+
+    let mut documents = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(content) {
+        documents.push(Value::deserialize(document)?);
+    }
+    Ok(documents)
+}
+
+#[cfg(not(feature = "yaml"))]
+fn parse_yaml(_: &str) -> BoxErrorResult<Vec<Value>> {
+    Err("YAML support requires the `yaml` feature".into())
+}
+
+#[cfg(feature = "toml")]
+fn parse_toml(content: &str) -> BoxErrorResult<Value> {
+    /// TOML date-times are represented as strings in RFC 3339 format.
+    fn convert(value: toml::Value) -> BoxErrorResult<Value> {
+        Ok(match value {
+            toml::Value::String(value) => Value::String(value),
+            toml::Value::Integer(value) => Value::from(value),
+            toml::Value::Float(value) => serde_json::Number::from_f64(value)
+                .map(Value::Number)
+                .ok_or_else(|| format!("Number '{}' is not supported", value))?,
+            toml::Value::Boolean(value) => Value::Bool(value),
+            toml::Value::Datetime(value) => Value::String(value.to_string()),
+            toml::Value::Array(items) => Value::Array(
+                items
+                    .into_iter()
+                    .map(convert)
+                    .collect::<BoxErrorResult<_>>()?,
+            ),
+            toml::Value::Table(table) => Value::Object(
+                table
+                    .into_iter()
+                    .map(|(key, value)| Ok((key, convert(value)?)))
+                    .collect::<BoxErrorResult<_>>()?,
+            ),
+        })
+    }
+
+    convert(toml::Value::Table(toml::from_str(content)?))
+}
+
+#[cfg(not(feature = "toml"))]
+fn parse_toml(_: &str) -> BoxErrorResult<Value> {
+    Err("TOML support requires the `toml` feature".into())
+}
+
+/// Read all documents from a file.
+fn read_documents(path: &Path, format: Format) -> BoxErrorResult<Vec<Value>> {
+    format.parse(&fs::read_to_string(path)?, is_stream(path))
+}
+
+/// Read all documents from a file along with their sources.
+fn read_sources(
+    path: &Path,
+    name: &str,
+    format: Format,
+) -> BoxErrorResult<Vec<(Value, Option<Source>)>> {
+    format.parse_sources(name, &fs::read_to_string(path)?, is_stream(path))
+}
+
+/// Read a schema in the given format, or the one detected by its extension.
+fn read_schema(path: &Path, format: Option<Format>) -> BoxErrorResult<Value> {
+    let format = format.or_else(|| Format::detect(path)).unwrap_or_default();
+    let mut documents = read_documents(path, format)?;
+    if documents.len() > 1 {
+        return Err(format!("{} contains multiple documents", path.display()).into());
+    }
+    Ok(documents.remove(0))
+}
+
 #[derive(Debug, StructOpt)]
-#[structopt(name = "jsonschema")]
+#[structopt(name = "jsonschema", setting = AppSettings::SubcommandsNegateReqs)]
 struct Cli {
     /// A path to a JSON instance (i.e. filename.json) to validate (may be specified multiple times).
-    #[structopt(short = "i", long = "instance")]
-    instances: Option<Vec<PathBuf>>,
+    /// Accepts the same values as positional instances.
+    #[structopt(
+        short = "i",
+        long = "instance",
+        parse(from_os_str),
+        number_of_values = 1
+    )]
+    instances: Vec<PathBuf>,
+
+    /// Extensions of files to validate when walking directories (may be specified multiple times).
+    #[structopt(long = "extension", default_value = "json", number_of_values = 1)]
+    extensions: Vec<String>,
 
-    /// The fully qualified object name of a validator to use, or, for validators that are registered
-    /// with jsonschema, simply the name of the class.
-    #[structopt(short = "V", long = "validator")]
-    validator: Option<String>,
+    /// The format of instances: `json`, `yaml` (requires the `yaml` feature), or `toml` (requires
+    /// the `toml` feature). By default it is detected by the file extension, falling back to JSON.
+    /// Lines and columns of invalid values are reported only for JSON instances.
+    #[structopt(long = "format", possible_values = &["json", "yaml", "toml"])]
+    format: Option<Format>,
+
+    /// The format of schemas, including those of subcommands: `json`, `yaml`, or `toml`. By default
+    /// it is detected by the file extension, falling back to JSON.
+    #[structopt(long = "schema-format", possible_values = &["json", "yaml", "toml"])]
+    schema_format: Option<Format>,
+
+    /// The draft to validate with: `4`, `6`, or `7`. By default it is detected from `$schema`,
+    /// falling back to Draft 7.
+    #[structopt(long = "draft", possible_values = &["4", "6", "7"], parse(try_from_str = parse_draft))]
+    draft: Option<Draft>,
+
+    /// Reject schemas with keywords and formats that are unknown in the used draft.
+    #[structopt(long = "strict")]
+    strict: bool,
+
+    /// Treat `format` as an annotation that doesn't affect validation.
+    #[structopt(long = "no-format-assertion")]
+    no_format_assertion: bool,
+
+    /// Don't validate strings against the given `contentMediaType` (may be specified multiple times).
+    #[structopt(long = "without-content-media-type", number_of_values = 1)]
+    without_content_media_types: Vec<String>,
+
+    /// Don't validate strings against the given `contentEncoding` (may be specified multiple times).
+    #[structopt(long = "without-content-encoding", number_of_values = 1)]
+    without_content_encodings: Vec<String>,
+
+    /// How to print validation results: `text`, `json` (an array of errors), `ndjson` (an error
+    /// per line), or `basic` / `flag` output formats from the JSON Schema specification, keyed by
+    /// instance path. Exits with 1 if any instance is invalid and with 2 if an input can't be read.
+    #[structopt(
+        long = "output",
+        default_value = "text",
+        possible_values = &["text", "json", "ndjson", "basic", "flag"]
+    )]
+    output: Output,
 
     /// The JSON Schema to validate with (i.e. schema.json).
     #[structopt(parse(from_os_str), required_unless("version"))]
     schema: Option<PathBuf>,
 
+    /// Instances to validate: files, directories that are walked recursively, glob patterns
+    /// (i.e. 'fixtures/**/*.json'), or `-` to read a stream of JSON documents from stdin. `.ndjson`
+    /// and `.jsonl` files are read as streams too. Instances are validated in parallel, results are
+    /// printed in the given order as soon as they are ready.
+    #[structopt(parse(from_os_str))]
+    paths: Vec<PathBuf>,
+
     /// Show program's version number and exit.
     #[structopt(short = "v", long = "version")]
     version: bool,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Inline all external `$ref`s into a single self-contained schema.
+    Bundle {
+        /// The JSON Schema to bundle (i.e. schema.json).
+        #[structopt(parse(from_os_str))]
+        schema: PathBuf,
+
+        /// A path to write the bundled schema to. By default it is written to stdout.
+        #[structopt(short = "o", long = "output", parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
+    /// Check schemas against their meta-schemas, resolve all their `$ref`s and compile them, reporting
+    /// every problem with its location. Exits with 1 if there are problems.
+    CheckSchema {
+        /// Schemas to check (i.e. schema.json).
+        #[structopt(parse(from_os_str), required = true)]
+        schemas: Vec<PathBuf>,
+    },
+    /// Report risky patterns in a schema. Exits with a non-zero code if there are errors.
+    Lint {
+        /// The JSON Schema to check (i.e. schema.json).
+        #[structopt(parse(from_os_str))]
+        schema: PathBuf,
+
+        /// Don't check the given rule (may be specified multiple times).
+        #[structopt(short = "A", long = "allow", number_of_values = 1)]
+        allow: Vec<LintRule>,
+
+        /// Report violations of the given rule as warnings (may be specified multiple times).
+        #[structopt(short = "W", long = "warn", number_of_values = 1)]
+        warn: Vec<LintRule>,
+
+        /// Report violations of the given rule as errors (may be specified multiple times).
+        #[structopt(short = "D", long = "deny", number_of_values = 1)]
+        deny: Vec<LintRule>,
+    },
 }
 
-pub fn main() -> BoxErrorResult<()> {
-    let config = Cli::from_args();
+pub fn main() {
+    let config = match Cli::from_args_safe() {
+        Ok(config) => config,
+        Err(error) if error.use_stderr() => {
+            eprintln!("{}", error.message);
+            process::exit(EXIT_ERROR);
+        }
+        Err(error) => error.exit(),
+    };
+    match run(config) {
+        Ok(0) => {}
+        Ok(code) => process::exit(code),
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            process::exit(EXIT_ERROR);
+        }
+    }
+}
 
+/// Run the command and return the exit code.
+fn run(config: Cli) -> BoxErrorResult<i32> {
     if config.version {
         println!("Version: {}", env!("CARGO_PKG_VERSION"));
-        return Ok(());
+        return Ok(0);
+    }
+
+    match config.command {
+        Some(Command::Bundle { schema, output }) => {
+            bundle(schema, output, config.schema_format)?;
+            return Ok(0);
+        }
+        Some(Command::CheckSchema { ref schemas }) => return check_schemas(schemas, &config),
+        Some(Command::Lint {
+            schema,
+            allow,
+            warn,
+            deny,
+        }) => {
+            if !lint(schema, config.schema_format, &allow, &warn, &deny)? {
+                return Ok(EXIT_INVALID);
+            }
+            return Ok(0);
+        }
+        None => {}
     }
 
-    let mut success = true;
-    if let Some(schema) = config.schema {
-        if let Some(instances) = config.instances {
-            success = validate_instances(&instances, schema)?;
+    if let Some(schema) = &config.schema {
+        let mut inputs = config.instances.clone();
+        inputs.extend(config.paths.iter().cloned());
+        if inputs.is_empty() {
+            return check_schemas(std::slice::from_ref(schema), &config);
         }
+        return validate_instances(schema, &inputs, &config);
     }
 
-    if !success {
-        process::exit(1);
+    Ok(0)
+}
+
+/// An instance to validate.
+enum Instance {
+    /// A file that is read during validation. It may contain multiple YAML documents.
+    File(PathBuf),
+    /// A document that is already parsed, e.g. one read from stdin.
+    Document(String, Value, Option<Source>),
+}
+
+/// The outcome of validating a single document.
+struct Report {
+    name: String,
+    errors: Result<Vec<(ValidationError<'static>, Option<Location>)>, String>,
+    /// Whether errors could be located in the source, which is possible only for JSON documents.
+    located: bool,
+}
+
+fn parse_draft(draft: &str) -> Result<Draft, String> {
+    match draft {
+        "4" => Ok(Draft::Draft4),
+        "6" => Ok(Draft::Draft6),
+        "7" => Ok(Draft::Draft7),
+        _ => Err(format!("Unknown draft '{}'", draft)),
     }
+}
 
-    Ok(())
+/// Compilation options set via command line arguments.
+fn compilation_options(config: &Cli) -> CompilationOptions {
+    let mut options = JSONSchema::options();
+    if let Some(draft) = config.draft {
+        options.with_draft(draft);
+    }
+    if config.strict {
+        options.with_strict_mode();
+    }
+    if config.no_format_assertion {
+        options.without_format_assertion();
+    }
+    for media_type in &config.without_content_media_types {
+        options.without_content_media_type_support(media_type.clone());
+    }
+    for encoding in &config.without_content_encodings {
+        options.without_content_encoding_support(encoding.clone());
+    }
+    options
 }
 
-fn validate_instances(instances: &[PathBuf], schema: PathBuf) -> BoxErrorResult<bool> {
-    let mut success = true;
+fn validate_instances(schema: &Path, inputs: &[PathBuf], config: &Cli) -> BoxErrorResult<i32> {
+    let mut code = 0;
 
-    let schema_json = fs::read_to_string(schema)?;
-    let schema_json = serde_json::from_str(&schema_json)?;
-    let schema = JSONSchema::compile(&schema_json)?;
+    let schema_json = read_schema(schema, config.schema_format)?;
+    let schema = compilation_options(config).compile(&schema_json)?;
 
-    for instance in instances {
-        let instance_path_name = instance.to_str().unwrap();
-        let instance_json = fs::read_to_string(&instance)?;
-        let instance_json = serde_json::from_str(&instance_json)?;
-        let validation = schema.validate(&instance_json);
-        match validation {
-            Ok(_) => println!("{} - VALID", instance_path_name),
-            Err(errors) => {
-                success = false;
+    let mut instances = Vec::new();
+    let mut seen = HashSet::new();
+    for input in inputs {
+        if let Err(error) = expand(input, config, &mut seen, &mut instances) {
+            eprintln!("{} - ERROR: {}", input.display(), error);
+            code = EXIT_ERROR;
+        }
+    }
 
-                println!("{} - INVALID. Errors:", instance_path_name);
-                for (i, e) in errors.enumerate() {
-                    println!("{}. {}", i + 1, e);
+    let mut printer = Printer::new(config.output);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| -> BoxErrorResult<()> {
+        let (schema, instances) = (&schema, &instances);
+        scope.spawn(move || {
+            instances
+                .par_iter()
+                .enumerate()
+                .for_each_with(sender, |sender, (idx, instance)| {
+                    // The receiver is gone only if printing failed, then the rest is not needed
+                    let _ = sender.send((idx, check(schema, instance, config.format)));
+                })
+        });
+        // Reports are printed as soon as all reports before them are ready to keep the given order
+        let mut pending = BTreeMap::new();
+        // Whether it is already noted that some errors are not located
+        let mut unlocated = false;
+        let mut next = 0;
+        for (idx, reports) in receiver {
+            pending.insert(idx, reports);
+            while let Some(reports) = pending.remove(&next) {
+                next += 1;
+                for report in reports {
+                    let errors = match &report.errors {
+                        Ok(errors) => errors,
+                        Err(error) => {
+                            eprintln!("{} - ERROR: {}", report.name, error);
+                            code = EXIT_ERROR;
+                            continue;
+                        }
+                    };
+                    if !errors.is_empty() && code == 0 {
+                        code = EXIT_INVALID;
+                    }
+                    if !errors.is_empty() && !report.located && !unlocated {
+                        eprintln!(
+                            "note: source locations of errors are reported only for JSON instances"
+                        );
+                        unlocated = true;
+                    }
+                    let failures = errors
+                        .iter()
+                        .map(|(error, location)| Failure {
+                            text: match location {
+                                Some(location) => location.diagnostic(&error.to_string()),
+                                None => error.to_string(),
+                            },
+                            entry: error_entry(&report.name, error, location.as_ref()),
+                            unit: json!({
+                                "keywordLocation": error.schema_path.to_string(),
+                                "instanceLocation": error.instance_path.to_string(),
+                                "error": error.to_string(),
+                            }),
+                        })
+                        .collect();
+                    printer.print(report.name, "Errors", failures)?;
                 }
             }
         }
+        Ok(())
+    })?;
+    printer.finish()?;
+
+    Ok(code)
+}
+
+/// Check schemas against their meta-schemas, resolve their references and compile them.
+fn check_schemas(schemas: &[PathBuf], config: &Cli) -> BoxErrorResult<i32> {
+    let mut code = 0;
+    let options = compilation_options(config);
+    let mut printer = Printer::new(config.output);
+    for path in schemas {
+        let name = path.display().to_string();
+        let schema = match read_schema(path, config.schema_format) {
+            Ok(schema) => schema,
+            Err(error) => {
+                eprintln!("{} - ERROR: {}", name, error);
+                code = EXIT_ERROR;
+                continue;
+            }
+        };
+        let problems = options.check_schema(&schema);
+        if !problems.is_empty() && code == 0 {
+            code = EXIT_INVALID;
+        }
+        let failures = problems
+            .iter()
+            .map(|problem| Failure {
+                text: problem.to_string(),
+                entry: json!({
+                    "file": name,
+                    "schemaPath": problem.schema_path.to_string(),
+                    "kind": match problem.kind {
+                        SchemaProblemKind::MetaSchema => "meta-schema",
+                        SchemaProblemKind::Reference => "reference",
+                        SchemaProblemKind::Compilation => "compilation",
+                    },
+                    "message": problem.message,
+                }),
+                // The schema is the validated instance here
+                unit: json!({
+                    "instanceLocation": problem.schema_path.to_string(),
+                    "error": problem.to_string(),
+                }),
+            })
+            .collect();
+        printer.print(name, "Problems", failures)?;
+    }
+    printer.finish()?;
+
+    Ok(code)
+}
+
+/// A validation error or a problem in a schema.
+struct Failure {
+    /// A message for the `text` output.
+    text: String,
+    /// An entry for `json` and `ndjson` outputs.
+    entry: Value,
+    /// An output unit for the `basic` output.
+    unit: Value,
+}
+
+/// Prints results for every checked file in the requested output format.
+///
+/// Results are printed as they come, even for outputs that are a single JSON document.
+struct Printer {
+    output: Output,
+    /// Whether the enclosing JSON document already has items.
+    started: bool,
+}
+
+impl Printer {
+    fn new(output: Output) -> Self {
+        Printer {
+            output,
+            started: false,
+        }
     }
 
-    Ok(success)
+    fn print(&mut self, name: String, heading: &str, failures: Vec<Failure>) -> BoxErrorResult<()> {
+        match self.output {
+            Output::Text => {
+                if failures.is_empty() {
+                    println!("{} - VALID", name);
+                } else {
+                    println!("{} - INVALID. {}:", name, heading);
+                    for (i, failure) in failures.iter().enumerate() {
+                        println!("{}. {}", i + 1, failure.text);
+                    }
+                }
+            }
+            Output::Json => {
+                for failure in failures {
+                    self.item(None, &failure.entry)?;
+                }
+            }
+            Output::Ndjson => {
+                for failure in failures {
+                    println!("{}", failure.entry);
+                }
+            }
+            Output::Basic => {
+                let result = if failures.is_empty() {
+                    json!({"valid": true})
+                } else {
+                    let units: Vec<_> = failures.into_iter().map(|failure| failure.unit).collect();
+                    json!({"valid": false, "errors": units})
+                };
+                self.item(Some(&name), &result)?;
+            }
+            Output::Flag => self.item(Some(&name), &json!({"valid": failures.is_empty()}))?,
+        }
+        Ok(())
+    }
+
+    /// Print an item of the enclosing array, or a member of the enclosing object if `key` is given.
+    ///
+    /// The output is the same as if the whole document were pretty-printed at once.
+    fn item(&mut self, key: Option<&str>, value: &Value) -> BoxErrorResult<()> {
+        let separator = match (self.started, key) {
+            (true, _) => ",",
+            (false, None) => "[",
+            (false, Some(_)) => "{",
+        };
+        self.started = true;
+        let value = serde_json::to_string_pretty(value)?.replace('\n', "\n  ");
+        match key {
+            Some(key) => print!(
+                "{}\n  {}: {}",
+                separator,
+                serde_json::to_string(key)?,
+                value
+            ),
+            None => print!("{}\n  {}", separator, value),
+        }
+        Ok(())
+    }
+
+    /// Close the document that encloses printed items.
+    fn finish(self) -> BoxErrorResult<()> {
+        let (open, close) = match self.output {
+            Output::Json => ("[", "]"),
+            Output::Basic | Output::Flag => ("{", "}"),
+            Output::Text | Output::Ndjson => return Ok(()),
+        };
+        if self.started {
+            println!("\n{}", close);
+        } else {
+            println!("{}{}", open, close);
+        }
+        Ok(())
+    }
+}
+
+/// Validate all documents of the instance.
+fn check(schema: &JSONSchema, instance: &Instance, format: Option<Format>) -> Vec<Report> {
+    let report = |name: String, document: &Value, source: Option<&Source>| Report {
+        name,
+        located: source.is_some(),
+        errors: match schema.validate(document) {
+            Ok(_) => Ok(Vec::new()),
+            Err(errors) => Ok(errors
+                .map(|error| {
+                    let location = source.and_then(|source| source.locate(&error.instance_path));
+                    (error.into_owned(), location)
+                })
+                .collect()),
+        },
+    };
+    match instance {
+        Instance::File(path) => {
+            let name = path.display().to_string();
+            let format = format.or_else(|| Format::detect(path)).unwrap_or_default();
+            match read_sources(path, &name, format) {
+                Ok(documents) => documents
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, (document, source))| {
+                        report(
+                            document_name(&name, idx, documents.len()),
+                            document,
+                            source.as_ref(),
+                        )
+                    })
+                    .collect(),
+                Err(error) => vec![Report {
+                    name,
+                    located: true,
+                    errors: Err(error.to_string()),
+                }],
+            }
+        }
+        Instance::Document(name, document, source) => {
+            vec![report(name.clone(), document, source.as_ref())]
+        }
+    }
+}
+
+/// A name of a document within a file that may contain multiple documents.
+fn document_name(name: &str, idx: usize, count: usize) -> String {
+    if count == 1 {
+        name.to_string()
+    } else {
+        format!("{}[{}]", name, idx)
+    }
+}
+
+/// Collect instances from a file, a directory, a glob pattern, or stdin (`-`).
+///
+/// Directories are walked recursively, and only files with the given extensions are included.
+/// Files that are already collected are skipped.
+fn expand(
+    input: &Path,
+    config: &Cli,
+    seen: &mut HashSet<PathBuf>,
+    instances: &mut Vec<Instance>,
+) -> BoxErrorResult<()> {
+    let mut push = |path: PathBuf| {
+        if seen.insert(path.clone()) {
+            instances.push(Instance::File(path))
+        }
+    };
+    if input == Path::new("-") {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content)?;
+        let documents = config
+            .format
+            .unwrap_or_default()
+            .parse_sources("<stdin>", &content, true)?;
+        let count = documents.len();
+        for (idx, (document, source)) in documents.into_iter().enumerate() {
+            instances.push(Instance::Document(
+                document_name("<stdin>", idx, count),
+                document,
+                source,
+            ));
+        }
+    } else if input.is_dir() {
+        for entry in WalkDir::new(input).sort_by(|a, b| a.file_name().cmp(b.file_name())) {
+            let entry = entry?;
+            let has_extension = match entry.path().extension().and_then(|ext| ext.to_str()) {
+                Some(extension) => config
+                    .extensions
+                    .iter()
+                    .any(|expected| expected == extension),
+                None => false,
+            };
+            if entry.file_type().is_file() && has_extension {
+                push(entry.into_path())
+            }
+        }
+    } else if is_pattern(input) {
+        let pattern = input.to_str().ok_or("Glob pattern is not valid UTF-8")?;
+        let mut matched = false;
+        for path in glob::glob(pattern)? {
+            let path = path?;
+            if path.is_file() {
+                matched = true;
+                push(path)
+            }
+        }
+        if !matched {
+            return Err("No files match the pattern".into());
+        }
+    } else {
+        push(input.to_path_buf())
+    }
+    Ok(())
+}
+
+/// Whether the path contains glob metacharacters and doesn't exist as is.
+fn is_pattern(path: &Path) -> bool {
+    !path.exists() && matches!(path.to_str(), Some(path) if path.contains(&['*', '?', '['][..]))
+}
+
+/// A machine-readable representation of a validation error.
+fn error_entry(file: &str, error: &ValidationError, location: Option<&Location>) -> Value {
+    json!({
+        "file": file,
+        "line": location.map(|location| location.line),
+        "column": location.map(|location| location.column),
+        "instancePath": error.instance_path.to_string(),
+        "schemaPath": error.schema_path.to_string(),
+        "keyword": keyword(error),
+        "message": error.to_string(),
+    })
+}
+
+/// The name of the failed keyword, which is the last component of the error's schema path.
+fn keyword(error: &ValidationError) -> Option<String> {
+    if let ValidationErrorKind::FalseSchema = *error.kind {
+        return Some("false".to_string());
+    }
+    match error.schema_path.iter().last() {
+        Some(PathChunk::Property(keyword)) => Some(keyword.clone()),
+        _ => None,
+    }
+}
+
+fn bundle(schema: PathBuf, output: Option<PathBuf>, format: Option<Format>) -> BoxErrorResult<()> {
+    let base = Url::from_file_path(fs::canonicalize(&schema)?)
+        .map_err(|_| format!("Invalid schema path: {}", schema.display()))?;
+    let schema_json = read_schema(&schema, format)?;
+    let bundled = JSONSchema::options()
+        .bundle_with_base(&schema_json, &base)
+        .map_err(|error| error.to_string())?;
+    let bundled = serde_json::to_string_pretty(&bundled)?;
+    match output {
+        Some(output) => fs::write(output, bundled)?,
+        None => println!("{}", bundled),
+    }
+    Ok(())
+}
+
+fn lint(
+    schema: PathBuf,
+    format: Option<Format>,
+    allow: &[LintRule],
+    warn: &[LintRule],
+    deny: &[LintRule],
+) -> BoxErrorResult<bool> {
+    let schema_json = read_schema(&schema, format)?;
+    let mut linter = Linter::default();
+    for (rules, level) in &[
+        (allow, LintLevel::Allow),
+        (warn, LintLevel::Warn),
+        (deny, LintLevel::Deny),
+    ] {
+        for rule in rules.iter() {
+            linter.with_level(*rule, *level);
+        }
+    }
+    let diagnostics = linter.lint(&schema_json);
+    for diagnostic in &diagnostics {
+        println!("{}", diagnostic);
+    }
+    Ok(!diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.level == LintLevel::Deny))
 }
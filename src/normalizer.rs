@@ -0,0 +1,653 @@
+//! Rewriting schemas into a simpler form that accepts exactly the same instances.
+//!
+//! Subschemas are normalized bottom-up and then a few rewrites are applied to every schema object until
+//! nothing changes:
+//!   - `type` lists are deduplicated, `integer` is dropped if `number` is present;
+//!   - `enum` values are deduplicated, a single-value `enum` becomes `const`;
+//!   - `anyOf` / `oneOf` of `const` / `enum` schemas become `enum`;
+//!   - always passing `allOf` members and never passing `anyOf` / `oneOf` members are removed;
+//!   - members of single-element combinators and non-conflicting `allOf` members are merged into the parent;
+//!   - keywords with no effect (e.g. `minLength: 0` or `additionalProperties: true`) are removed.
+//!
+//! Schemas that are targets of JSON pointer references (or contain such targets) keep their structure,
+//! so references still resolve to the same subschemas.
+use crate::{
+    bundler::DATA_KEYWORDS,
+    compilation::options::CompilationOptions,
+    keywords::unique_items::is_unique,
+    schemas::{id_of, Draft},
+};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+/// How many times rewrites are applied to a single schema object.
+const MAX_PASSES: usize = 16;
+/// Keywords with a single subschema.
+pub(crate) const SUBSCHEMA_KEYWORDS: &[&str] = &[
+    "additionalItems",
+    "additionalProperties",
+    "contains",
+    "else",
+    "if",
+    "items",
+    "not",
+    "propertyNames",
+    "then",
+];
+/// Keywords with an object of subschemas.
+pub(crate) const SUBSCHEMA_MAP_KEYWORDS: &[&str] = &[
+    "definitions",
+    "dependencies",
+    "patternProperties",
+    "properties",
+];
+pub(crate) const COMBINATORS: &[&str] = &["allOf", "anyOf", "oneOf"];
+/// Schemas with these keywords are never merged into other schemas.
+/// Siblings of `$ref` are ignored and others depend on the schema location.
+const BARRIERS: &[&str] = &["$ref", "$id", "id", "$schema", "errorMessage"];
+/// Keywords that are validated together, so they can't come from different schemas.
+const GROUPS: &[&[&str]] = &[
+    &["properties", "patternProperties", "additionalProperties"],
+    &["items", "additionalItems"],
+    &["if", "then", "else"],
+    &["minimum", "exclusiveMinimum"],
+    &["maximum", "exclusiveMaximum"],
+    &["type", "nullable"],
+    &["contentMediaType", "contentEncoding"],
+    &["anyOf", "oneOf", "discriminator"],
+    // `OpenAPI` 3.0 `required` skips `readOnly` / `writeOnly` properties depending on the direction
+    &["properties", "required"],
+];
+type Predicate = fn(&Value) -> bool;
+
+/// Keywords that have no effect with these values.
+const NO_OP_VALUES: &[(&str, Predicate)] = &[
+    ("additionalItems", is_true),
+    ("additionalProperties", is_true),
+    ("items", is_true),
+    ("propertyNames", is_true),
+    ("properties", is_empty_object),
+    ("patternProperties", is_empty_object),
+    ("dependencies", is_empty_object),
+    ("required", is_empty_array),
+    ("allOf", is_empty_array),
+    ("minLength", is_zero),
+    ("minItems", is_zero),
+    ("minProperties", is_zero),
+    ("uniqueItems", is_false),
+];
+
+struct Normalizer {
+    draft: Draft,
+    // Locations of subschemas that are referenced via JSON pointers
+    targets: HashSet<String>,
+}
+
+impl Normalizer {
+    fn new(draft: Draft, schema: &Value) -> Self {
+        let mut targets = HashSet::new();
+        collect_targets(schema, &mut targets);
+        Normalizer { draft, targets }
+    }
+
+    /// Whether the structure of the schema at `location` should be kept.
+    fn is_pinned(&self, location: &str) -> bool {
+        let prefix = format!("{}/", location);
+        self.targets
+            .iter()
+            .any(|target| target.starts_with(&prefix))
+    }
+
+    const fn has_boolean_schemas(&self) -> bool {
+        !matches!(self.draft, Draft::Draft4 | Draft::OpenAPI30)
+    }
+
+    /// A schema that never passes.
+    fn never(&self) -> Value {
+        if self.has_boolean_schemas() {
+            Value::Bool(false)
+        } else {
+            serde_json::json!({"not": {}})
+        }
+    }
+
+    fn is_always(&self, schema: &Value) -> bool {
+        is_true(schema)
+    }
+
+    fn is_never(&self, schema: &Value) -> bool {
+        match schema {
+            Value::Bool(false) => true,
+            Value::Object(object) => {
+                object.len() == 1 && matches!(object.get("not"), Some(not) if self.is_always(not))
+            }
+            _ => false,
+        }
+    }
+
+    fn schema(&self, schema: &Value, location: &str) -> Value {
+        match schema {
+            // Pointers in references are relative to the closest identifier, so pointers from all
+            // documents are compared to locations within all documents
+            Value::Object(object) if id_of(self.draft, schema).is_some() => self.object(object, ""),
+            Value::Object(object) => self.object(object, location),
+            _ => schema.clone(),
+        }
+    }
+
+    fn object(&self, object: &Map<String, Value>, location: &str) -> Value {
+        let mut normalized = Map::with_capacity(object.len());
+        for (keyword, value) in object {
+            let keyword_location = format!("{}/{}", location, escape(keyword));
+            let value = if DATA_KEYWORDS.contains(&keyword.as_str()) {
+                value.clone()
+            } else if SUBSCHEMA_KEYWORDS.contains(&keyword.as_str()) {
+                match value {
+                    Value::Array(items) if keyword == "items" => {
+                        Value::Array(self.elements(items, &keyword_location))
+                    }
+                    _ => self.schema(value, &keyword_location),
+                }
+            } else if COMBINATORS.contains(&keyword.as_str()) {
+                match value {
+                    Value::Array(items) => Value::Array(self.elements(items, &keyword_location)),
+                    _ => value.clone(),
+                }
+            } else if SUBSCHEMA_MAP_KEYWORDS.contains(&keyword.as_str()) {
+                match value {
+                    Value::Object(entries) => Value::Object(
+                        entries
+                            .iter()
+                            .map(|(name, value)| {
+                                let value = if value.is_array() {
+                                    // Property dependencies
+                                    value.clone()
+                                } else {
+                                    self.schema(
+                                        value,
+                                        &format!("{}/{}", keyword_location, escape(name)),
+                                    )
+                                };
+                                (name.clone(), value)
+                            })
+                            .collect(),
+                    ),
+                    _ => value.clone(),
+                }
+            } else {
+                value.clone()
+            };
+            normalized.insert(keyword.clone(), value);
+        }
+        if normalized.contains_key("$ref") || self.is_pinned(location) {
+            return Value::Object(normalized);
+        }
+        let mut schema = Value::Object(normalized);
+        for _ in 0..MAX_PASSES {
+            let simplified = match &schema {
+                Value::Object(object) => self.simplify(object),
+                _ => break,
+            };
+            if simplified == schema {
+                break;
+            }
+            schema = simplified;
+        }
+        schema
+    }
+
+    fn elements(&self, items: &[Value], location: &str) -> Vec<Value> {
+        items
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| self.schema(item, &format!("{}/{}", location, idx)))
+            .collect()
+    }
+
+    /// A single pass of rewrites.
+    fn simplify(&self, object: &Map<String, Value>) -> Value {
+        let mut object = object.clone();
+        if let Some(value) = object.get_mut("type") {
+            normalize_type(value);
+        }
+        self.simplify_enum(&mut object);
+        if self.draft == Draft::Draft7 {
+            self.simplify_condition(&mut object);
+        }
+        for keyword in COMBINATORS {
+            if let Some(Value::Array(members)) = object.get(*keyword) {
+                let mut members = members.clone();
+                if *keyword == "allOf" {
+                    if members.iter().any(|member| self.is_never(member)) {
+                        return self.never_unless_barriers(&object);
+                    }
+                    members.retain(|member| !self.is_always(member));
+                    dedup(&mut members);
+                } else {
+                    members.retain(|member| !self.is_never(member));
+                    if members.is_empty() {
+                        return self.never_unless_barriers(&object);
+                    }
+                    if *keyword == "anyOf" {
+                        if members.iter().any(|member| self.is_always(member)) {
+                            object.remove(*keyword);
+                            continue;
+                        }
+                        dedup(&mut members);
+                    }
+                }
+                object.insert((*keyword).to_string(), Value::Array(members));
+            }
+        }
+        self.to_enum(&mut object, "anyOf");
+        self.to_enum(&mut object, "oneOf");
+        self.merge_members(&mut object);
+        for (keyword, is_no_op) in NO_OP_VALUES {
+            if matches!(object.get(*keyword), Some(value) if is_no_op(value)) {
+                object.remove(*keyword);
+            }
+        }
+        Value::Object(object)
+    }
+
+    fn never_unless_barriers(&self, object: &Map<String, Value>) -> Value {
+        if BARRIERS.iter().any(|keyword| object.contains_key(*keyword)) {
+            // E.g. the custom message should still be reported
+            Value::Object(object.clone())
+        } else {
+            self.never()
+        }
+    }
+
+    fn simplify_enum(&self, object: &mut Map<String, Value>) {
+        if let Some(Value::Array(options)) = object.get_mut("enum") {
+            dedup(options);
+            if options.len() == 1
+                && self.has_boolean_schemas()
+                && is_comparable(&options[0])
+                && !object.contains_key("const")
+            {
+                if let Some(Value::Array(mut options)) = object.remove("enum") {
+                    object.insert("const".to_string(), options.remove(0));
+                }
+            }
+        }
+    }
+
+    /// Replace conditions that always or never pass with the branch that is taken.
+    fn simplify_condition(&self, object: &mut Map<String, Value>) {
+        let branch = match object.get("if") {
+            None => {
+                // Without `if` both branches are ignored
+                object.remove("then");
+                object.remove("else");
+                return;
+            }
+            Some(condition) if self.is_always(condition) => "then",
+            Some(condition) if self.is_never(condition) => "else",
+            Some(_) => {
+                if !object.contains_key("then") && !object.contains_key("else") {
+                    object.remove("if");
+                }
+                return;
+            }
+        };
+        let taken = object.get(branch).cloned();
+        for keyword in &["if", "then", "else"] {
+            object.remove(*keyword);
+        }
+        if let Some(taken) = taken {
+            push_member(object, taken);
+        }
+    }
+
+    /// Replace `anyOf` / `oneOf` of `const` and `enum` schemas with a single `enum`.
+    fn to_enum(&self, object: &mut Map<String, Value>, keyword: &str) {
+        if object.contains_key("enum") || object.contains_key("const") {
+            return;
+        }
+        let members = match object.get(keyword) {
+            Some(Value::Array(members)) if members.len() > 1 => members,
+            _ => return,
+        };
+        let mut options = Vec::new();
+        for member in members {
+            match member.as_object() {
+                Some(member) if member.len() == 1 => match member.iter().next() {
+                    Some((name, value)) if name == "const" && self.has_boolean_schemas() => {
+                        options.push(value.clone())
+                    }
+                    Some((name, Value::Array(values))) if name == "enum" => {
+                        options.extend(values.iter().cloned())
+                    }
+                    _ => return,
+                },
+                _ => return,
+            }
+        }
+        // Numbers are compared differently by `const` and `enum`
+        if !options.iter().all(is_comparable) {
+            return;
+        }
+        if keyword == "oneOf" && !is_unique(&options) {
+            // A value that matches multiple members is invalid
+            return;
+        }
+        dedup(&mut options);
+        object.remove(keyword);
+        object.insert("enum".to_string(), Value::Array(options));
+    }
+
+    /// Move members of single-element combinators and non-conflicting `allOf` members into the parent.
+    fn merge_members(&self, object: &mut Map<String, Value>) {
+        for keyword in &["anyOf", "oneOf"] {
+            let is_single =
+                matches!(object.get(*keyword), Some(Value::Array(members)) if members.len() == 1);
+            if is_single && !object.contains_key("discriminator") {
+                if let Some(Value::Array(mut members)) = object.remove(*keyword) {
+                    push_member(object, members.remove(0));
+                }
+            }
+        }
+        let members = match object.remove("allOf") {
+            Some(Value::Array(members)) => members,
+            Some(other) => {
+                object.insert("allOf".to_string(), other);
+                return;
+            }
+            None => return,
+        };
+        let mut remaining = Vec::new();
+        for member in members {
+            match member {
+                Value::Object(member) if can_merge(object, &member) => {
+                    for (keyword, value) in member {
+                        if keyword == "allOf" {
+                            if let Value::Array(nested) = value {
+                                remaining.extend(nested);
+                            }
+                        } else {
+                            object.insert(keyword, value);
+                        }
+                    }
+                }
+                member => remaining.push(member),
+            }
+        }
+        if !remaining.is_empty() {
+            object.insert("allOf".to_string(), Value::Array(remaining));
+        }
+    }
+}
+
+/// Add `member` to `allOf`, it might be merged later.
+fn push_member(object: &mut Map<String, Value>, member: Value) {
+    match object.get_mut("allOf") {
+        Some(Value::Array(members)) => members.push(member),
+        _ => {
+            object.insert("allOf".to_string(), Value::Array(vec![member]));
+        }
+    }
+}
+
+/// Whether all keywords of `member` can be moved to `parent` without changing what it accepts.
+fn can_merge(parent: &Map<String, Value>, member: &Map<String, Value>) -> bool {
+    if BARRIERS
+        .iter()
+        .any(|keyword| parent.contains_key(*keyword) || member.contains_key(*keyword))
+    {
+        return false;
+    }
+    // Keywords that differ between the parent and the member
+    let differs = |keyword: &str| match (parent.get(keyword), member.get(keyword)) {
+        (Some(left), Some(right)) => left != right,
+        _ => false,
+    };
+    let conflicts = member
+        .keys()
+        .any(|keyword| keyword != "allOf" && differs(keyword));
+    let splits_group = GROUPS.iter().any(|group| {
+        let in_parent = group
+            .iter()
+            .any(|keyword| parent.contains_key(*keyword) && !member.contains_key(*keyword));
+        let in_member = group
+            .iter()
+            .any(|keyword| member.contains_key(*keyword) && !parent.contains_key(*keyword));
+        (in_parent && in_member) || group.iter().any(|keyword| differs(keyword))
+    });
+    !conflicts && !splits_group
+}
+
+/// Remove duplicated `type` names and `integer` if `number` is present.
+fn normalize_type(value: &mut Value) {
+    if let Value::Array(names) = value {
+        dedup(names);
+        if names.iter().any(|name| name == "number") {
+            names.retain(|name| name != "integer");
+        }
+        if names.len() == 1 {
+            *value = names.remove(0);
+        }
+    }
+}
+
+fn dedup(values: &mut Vec<Value>) {
+    let mut unique: Vec<Value> = Vec::with_capacity(values.len());
+    for value in values.drain(..) {
+        if !unique.contains(&value) {
+            unique.push(value);
+        }
+    }
+    *values = unique;
+}
+
+/// Values that are compared the same way by `const` and `enum`.
+const fn is_comparable(value: &Value) -> bool {
+    matches!(value, Value::Null | Value::Bool(_) | Value::String(_))
+}
+
+fn is_true(value: &Value) -> bool {
+    match value {
+        Value::Bool(value) => *value,
+        Value::Object(object) => object.is_empty(),
+        _ => false,
+    }
+}
+
+fn is_false(value: &Value) -> bool {
+    matches!(value, Value::Bool(false))
+}
+
+fn is_empty_object(value: &Value) -> bool {
+    matches!(value, Value::Object(object) if object.is_empty())
+}
+
+fn is_empty_array(value: &Value) -> bool {
+    matches!(value, Value::Array(items) if items.is_empty())
+}
+
+fn is_zero(value: &Value) -> bool {
+    value.as_f64() == Some(0.0)
+}
+
+fn escape(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+/// Collect JSON pointers from local `$ref`s.
+fn collect_targets(schema: &Value, targets: &mut HashSet<String>) {
+    match schema {
+        Value::Object(object) => {
+            if let Some(Value::String(reference)) = object.get("$ref") {
+                if let Some(pointer) = reference.split('#').nth(1) {
+                    if pointer.starts_with('/') {
+                        let decoded = percent_encoding::percent_decode_str(pointer)
+                            .decode_utf8_lossy()
+                            .into_owned();
+                        targets.insert(decoded);
+                    }
+                }
+            }
+            for value in object.values() {
+                collect_targets(value, targets);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_targets(item, targets);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Normalize `schema` written for `draft`.
+pub(crate) fn normalize(draft: Draft, schema: &Value) -> Value {
+    Normalizer::new(draft, schema).schema(schema, "")
+}
+
+impl CompilationOptions {
+    /// Rewrite `schema` into a simpler form that accepts exactly the same instances - nested single-element
+    /// `allOf` / `anyOf` / `oneOf` are merged, duplicated constraints and keywords with no effect are removed,
+    /// `anyOf` of `const` schemas become a single `enum`, unreachable branches are dropped.
+    ///
+    /// Annotations (e.g. `title`) are kept, while schema paths in validation errors may change.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({
+    ///     "allOf": [{"allOf": [{"type": ["string", "string"]}]}],
+    ///     "anyOf": [{"const": "a"}, {"const": "b"}],
+    ///     "minLength": 0
+    /// });
+    /// assert_eq!(
+    ///     JSONSchema::options().normalize(&schema),
+    ///     json!({"type": "string", "enum": ["a", "b"]})
+    /// );
+    /// ```
+    #[must_use]
+    pub fn normalize(&self, schema: &Value) -> Value {
+        normalize(self.detect_draft(schema), schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        compilation::JSONSchema,
+        generator::InstanceGenerator,
+        schemas::{Draft, OpenAPIDirection},
+    };
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(json!({"type": ["string", "string"]}), json!({"type": "string"}); "duplicated types")]
+    #[test_case(json!({"type": ["integer", "number", "null"]}), json!({"type": ["number", "null"]}); "integer and number")]
+    #[test_case(json!({"enum": ["a", "a"]}), json!({"const": "a"}); "single value enum")]
+    #[test_case(json!({"enum": [1, 1]}), json!({"enum": [1]}); "numbers are kept in enum")]
+    #[test_case(json!({"allOf": [{"allOf": [{"minimum": 1}]}]}), json!({"minimum": 1}); "nested all of")]
+    #[test_case(json!({"anyOf": [{"maxLength": 3}]}), json!({"maxLength": 3}); "single any of")]
+    #[test_case(json!({"oneOf": [{"const": "a"}, {"enum": ["b", null]}]}), json!({"enum": ["a", "b", null]}); "one of constants")]
+    #[test_case(json!({"oneOf": [{"const": "a"}, {"const": "a"}]}), json!({"oneOf": [{"const": "a"}, {"const": "a"}]}); "overlapping one of")]
+    #[test_case(json!({"anyOf": [{"const": 1}, {"const": 2}]}), json!({"anyOf": [{"const": 1}, {"const": 2}]}); "numeric constants")]
+    #[test_case(json!({"anyOf": [false, {"type": "null"}, {"type": "null"}]}), json!({"type": "null"}); "unreachable branches")]
+    #[test_case(json!({"anyOf": [{}, {"type": "null"}], "minimum": 1}), json!({"minimum": 1}); "always valid any of")]
+    #[test_case(json!({"allOf": [{"type": "null"}, false]}), json!(false); "never valid all of")]
+    #[test_case(json!({"oneOf": [false, false]}), json!(false); "never valid one of")]
+    #[test_case(json!({"type": "string", "allOf": [{"type": "string", "minLength": 1}]}), json!({"type": "string", "minLength": 1}); "same keywords")]
+    #[test_case(json!({"maximum": 5, "allOf": [{"maximum": 3}]}), json!({"maximum": 5, "allOf": [{"maximum": 3}]}); "conflicting keywords")]
+    #[test_case(
+        json!({"properties": {"a": {}}, "allOf": [{"additionalProperties": false}]}),
+        json!({"properties": {"a": {}}, "allOf": [{"additionalProperties": false}]});
+        "related keywords"
+    )]
+    #[test_case(json!({"allOf": [{"$ref": "#/definitions/a"}], "definitions": {"a": {}}}), json!({"allOf": [{"$ref": "#/definitions/a"}], "definitions": {"a": {}}}); "references are not merged")]
+    #[test_case(
+        json!({"items": {"$ref": "#/allOf/0"}, "allOf": [{"type": "array"}]}),
+        json!({"items": {"$ref": "#/allOf/0"}, "allOf": [{"type": "array"}]});
+        "reference targets"
+    )]
+    #[test_case(json!({"if": {}, "then": {"minimum": 1}, "else": {"maximum": 0}}), json!({"minimum": 1}); "always true condition")]
+    #[test_case(json!({"then": {"minimum": 1}}), json!({}); "branches without condition")]
+    #[test_case(
+        json!({"minLength": 0, "additionalProperties": {}, "required": [], "uniqueItems": false, "properties": {"a": {"allOf": [true]}}}),
+        json!({"properties": {"a": {}}});
+        "no-op keywords"
+    )]
+    #[test_case(json!({"const": {"allOf": [{}]}}), json!({"const": {"allOf": [{}]}}); "data keywords")]
+    fn normalized(schema: Value, expected: Value) {
+        assert_eq!(JSONSchema::options().normalize(&schema), expected);
+    }
+
+    #[test]
+    fn draft4() {
+        let schema = json!({
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "allOf": [{"not": {}}],
+            "enum": ["a"]
+        });
+        assert_eq!(
+            JSONSchema::options().normalize(&schema),
+            json!({"$schema": "http://json-schema.org/draft-04/schema#", "allOf": [{"not": {}}], "enum": ["a"]})
+        );
+        assert_eq!(
+            super::normalize(Draft::Draft4, &json!({"anyOf": [{"not": {}}]})),
+            json!({"not": {}})
+        );
+    }
+
+    #[test]
+    fn openapi_directions() {
+        let schema =
+            json!({"properties": {"id": {"readOnly": true}}, "allOf": [{"required": ["id"]}]});
+        let normalized = JSONSchema::options()
+            .with_draft(Draft::OpenAPI30)
+            .normalize(&schema);
+        assert_eq!(normalized, schema);
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::OpenAPI30)
+            .with_openapi_direction(OpenAPIDirection::Request)
+            .with_normalization()
+            .compile(&schema)
+            .unwrap();
+        // Only `required` from the same schema as `properties` skips the read-only property
+        assert!(!compiled.is_valid(&json!({})));
+    }
+
+    #[test_case(json!({
+        "type": "object",
+        "properties": {
+            "kind": {"anyOf": [{"const": "a"}, {"const": "b"}]},
+            "size": {"allOf": [{"type": ["integer", "integer"]}, {"allOf": [{"minimum": 1, "maximum": 9}]}]},
+            "tags": {"type": "array", "items": {"oneOf": [{"type": "string", "maxLength": 3}]}, "uniqueItems": false}
+        },
+        "required": ["kind", "size"]
+    }); "object")]
+    #[test_case(json!({"anyOf": [{"type": "string", "minLength": 2}, {"type": "null"}, false], "allOf": [true]}); "combinators")]
+    fn same_instances(schema: Value) {
+        let normalized = JSONSchema::options().normalize(&schema);
+        assert_ne!(normalized, schema);
+        let original = JSONSchema::compile(&schema).unwrap();
+        let compiled = JSONSchema::compile(&normalized).unwrap();
+        let fast = JSONSchema::options()
+            .with_normalization()
+            .compile(&schema)
+            .unwrap();
+        let generator = InstanceGenerator::new(&original, 7);
+        let mut instances: Vec<Value> = InstanceGenerator::new(&original, 7).take(50).collect();
+        for instance in instances.clone() {
+            instances.extend(
+                generator
+                    .mutate(&instance)
+                    .into_iter()
+                    .map(|invalid| invalid.instance),
+            );
+        }
+        for instance in &instances {
+            let expected = original.is_valid(instance);
+            assert_eq!(compiled.is_valid(instance), expected, "{}", instance);
+            assert_eq!(fast.is_valid(instance), expected, "{}", instance);
+        }
+    }
+}
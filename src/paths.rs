@@ -0,0 +1,219 @@
+//! Facilities for working with paths within schemas or validated instances.
+use std::{fmt, fmt::Write, slice::Iter, sync::Arc};
+
+/// JSON Pointer as a wrapper around individual path components.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct JSONPointer(Vec<PathChunk>);
+
+impl JSONPointer {
+    /// JSON pointer as a vector of strings. Each component is casted to `String`. Consumes `JSONPointer`.
+    #[must_use]
+    pub fn into_vec(self) -> Vec<String> {
+        self.0
+            .into_iter()
+            .map(|item| match item {
+                PathChunk::Property(value) => value,
+                PathChunk::Index(idx) => idx.to_string(),
+            })
+            .collect()
+    }
+
+    /// Return an iterator over the underlying vector of path components.
+    pub fn iter(&self) -> Iter<'_, PathChunk> {
+        self.0.iter()
+    }
+
+    /// The number of components in the pointer.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the pointer refers to the document root.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Create a new pointer with `chunk` appended to the current one.
+    #[must_use]
+    pub fn join(&self, chunk: impl Into<PathChunk>) -> Self {
+        let mut chunks = self.0.clone();
+        chunks.push(chunk.into());
+        JSONPointer(chunks)
+    }
+}
+
+impl fmt::Display for JSONPointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in &self.0 {
+            f.write_char('/')?;
+            match chunk {
+                PathChunk::Property(value) => {
+                    for ch in value.chars() {
+                        match ch {
+                            '/' => f.write_str("~1")?,
+                            '~' => f.write_str("~0")?,
+                            _ => f.write_char(ch)?,
+                        }
+                    }
+                }
+                PathChunk::Index(idx) => write!(f, "{}", idx)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a JSONPointer {
+    type Item = &'a PathChunk;
+    type IntoIter = Iter<'a, PathChunk>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl From<Vec<PathChunk>> for JSONPointer {
+    #[inline]
+    fn from(chunks: Vec<PathChunk>) -> Self {
+        JSONPointer(chunks)
+    }
+}
+
+impl From<&[&str]> for JSONPointer {
+    #[inline]
+    fn from(path: &[&str]) -> Self {
+        JSONPointer(path.iter().map(|item| (*item).into()).collect())
+    }
+}
+
+/// A key within a JSON object or an index within a JSON array.
+/// A sequence of chunks represents a path within a JSON value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathChunk {
+    /// Property name within a JSON object.
+    Property(String),
+    /// Index within a JSON array.
+    Index(usize),
+}
+
+impl From<String> for PathChunk {
+    #[inline]
+    fn from(value: String) -> Self {
+        PathChunk::Property(value)
+    }
+}
+impl From<&str> for PathChunk {
+    #[inline]
+    fn from(value: &str) -> Self {
+        PathChunk::Property(value.to_string())
+    }
+}
+impl From<usize> for PathChunk {
+    #[inline]
+    fn from(value: usize) -> Self {
+        PathChunk::Index(value)
+    }
+}
+
+/// A path to the currently validated part of the instance.
+///
+/// It is a persistent linked list, therefore pushing a new chunk doesn't copy the path built so far
+/// and the path could be cheaply moved into lazily evaluated error iterators.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct InstancePath(Option<Arc<InstancePathNode>>);
+
+/// A path to the currently compiled part of the schema.
+/// It is converted to `JSONPointer` only when the location is needed, e.g. for validation errors.
+pub(crate) type SchemaPath = InstancePath;
+
+#[derive(Debug)]
+pub(crate) struct InstancePathNode {
+    chunk: PathChunk,
+    parent: InstancePath,
+}
+
+impl InstancePath {
+    pub(crate) const fn new() -> Self {
+        InstancePath(None)
+    }
+
+    #[inline]
+    pub(crate) fn push(&self, chunk: impl Into<PathChunk>) -> Self {
+        InstancePath(Some(Arc::new(InstancePathNode {
+            chunk: chunk.into(),
+            parent: self.clone(),
+        })))
+    }
+
+    /// The path without its last chunk.
+    #[inline]
+    pub(crate) fn parent(&self) -> Self {
+        match &self.0 {
+            Some(node) => node.parent.clone(),
+            None => InstancePath::new(),
+        }
+    }
+
+    pub(crate) fn to_vec(&self) -> Vec<PathChunk> {
+        let mut result = Vec::new();
+        let mut current = &self.0;
+        while let Some(node) = current {
+            result.push(node.chunk.clone());
+            current = &node.parent.0;
+        }
+        result.reverse();
+        result
+    }
+}
+
+impl From<&InstancePath> for JSONPointer {
+    #[inline]
+    fn from(path: &InstancePath) -> Self {
+        JSONPointer(path.to_vec())
+    }
+}
+
+impl From<InstancePath> for JSONPointer {
+    #[inline]
+    fn from(path: InstancePath) -> Self {
+        JSONPointer(path.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InstancePath, JSONPointer, PathChunk};
+
+    #[test]
+    fn instance_path_push() {
+        let root = InstancePath::new();
+        let first = root.push("foo");
+        let second = first.push(1);
+        let sibling = first.push("bar");
+        assert!(JSONPointer::from(&root).is_empty());
+        assert_eq!(
+            JSONPointer::from(&second.parent()),
+            JSONPointer::from(&first)
+        );
+        assert!(JSONPointer::from(&root.parent()).is_empty());
+        assert_eq!(
+            JSONPointer::from(&second).into_vec(),
+            vec!["foo".to_string(), "1".to_string()]
+        );
+        assert_eq!(
+            JSONPointer::from(&sibling),
+            JSONPointer::from(vec![
+                PathChunk::Property("foo".to_string()),
+                PathChunk::Property("bar".to_string())
+            ])
+        );
+    }
+
+    #[test]
+    fn display_escapes_special_characters() {
+        let pointer = JSONPointer::from(&["a/b", "c~d"][..]).join(0);
+        assert_eq!(pointer.to_string(), "/a~1b/c~0d/0");
+    }
+}
@@ -2,14 +2,21 @@ use std::{convert::TryFrom, fmt, ops::BitOrAssign};
 
 /// For faster error handling in "type" keyword validator we have this enum, to match
 /// with it instead of a string.
-#[derive(Debug, Clone, Copy)]
-pub(crate) enum PrimitiveType {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrimitiveType {
+    /// JSON array.
     Array,
+    /// JSON boolean.
     Boolean,
+    /// JSON number without a fractional part.
     Integer,
+    /// JSON null.
     Null,
+    /// JSON number.
     Number,
+    /// JSON object.
     Object,
+    /// JSON string.
     String,
 }
 
@@ -72,8 +79,9 @@ fn bit_map_representation_primitive_type(bit_representation: u8) -> PrimitiveTyp
     }
 }
 
+/// A compact set of primitive types.
 #[derive(Clone, Copy, Debug)]
-pub(crate) struct PrimitiveTypesBitMap {
+pub struct PrimitiveTypesBitMap {
     inner: u8,
 }
 impl PrimitiveTypesBitMap {
@@ -87,8 +95,10 @@ impl PrimitiveTypesBitMap {
         self
     }
 
+    /// Whether the given type is in the set.
     #[inline(always)]
-    pub(crate) fn contains_type(self, primitive_type: PrimitiveType) -> bool {
+    #[must_use]
+    pub fn contains_type(self, primitive_type: PrimitiveType) -> bool {
         primitive_type_to_bit_map_representation(primitive_type) & self.inner != 0
     }
 }
@@ -119,7 +129,9 @@ impl From<Vec<PrimitiveType>> for PrimitiveTypesBitMap {
     }
 }
 
-pub(crate) struct PrimitiveTypesBitMapIterator {
+/// An iterator over types stored in `PrimitiveTypesBitMap`.
+#[derive(Debug)]
+pub struct PrimitiveTypesBitMapIterator {
     range: std::ops::Range<u8>,
     bit_map: PrimitiveTypesBitMap,
 }
@@ -0,0 +1,393 @@
+//! Per-keyword profiling of validation.
+//!
+//! If `CompilationOptions::with_profiling` is set, then every compiled keyword is wrapped into `ProfileValidator`
+//! that accumulates the number of `is_valid` calls and the time spent in them.
+use crate::{
+    compilation::JSONSchema,
+    error::ErrorIterator,
+    keywords::{BoxedValidator, Subschemas},
+    paths::{InstancePath, JSONPointer},
+    validator::Validate,
+};
+use parking_lot::Mutex;
+use serde_json::{Map, Value};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    convert::TryFrom,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Accumulated statistics for a single keyword.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileEntry {
+    /// Keyword name.
+    pub keyword: String,
+    /// Location of the keyword within the schema.
+    pub location: JSONPointer,
+    /// The number of `is_valid` calls.
+    pub calls: u64,
+    /// Time spent in the keyword, including its subschemas.
+    pub total: Duration,
+    /// Time spent in the keyword itself, excluding other profiled keywords inside it.
+    pub own: Duration,
+}
+
+/// Validation statistics collected since the schema compilation or since the last `JSONSchema::reset_profile` call.
+///
+/// Entries are sorted by their own time, the slowest keywords go first.
+/// The `Display` implementation renders the profile as a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    /// Statistics for all keywords that were called at least once.
+    pub entries: Vec<ProfileEntry>,
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>12} {:>12} {:>10}  location",
+            "own (µs)", "total (µs)", "calls"
+        )?;
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "{:>12} {:>12} {:>10}  #{}",
+                entry.own.as_micros(),
+                entry.total.as_micros(),
+                entry.calls,
+                entry.location
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct KeywordStats {
+    keyword: String,
+    location: JSONPointer,
+    calls: AtomicU64,
+    total: AtomicU64,
+    own: AtomicU64,
+}
+
+/// Statistics of all profiled keywords of a single compiled schema.
+#[derive(Debug, Default)]
+pub(crate) struct Profiler {
+    stats: Mutex<Vec<Arc<KeywordStats>>>,
+}
+
+impl Profiler {
+    fn register(&self, keyword: &str, location: &JSONPointer) -> Arc<KeywordStats> {
+        let stats = Arc::new(KeywordStats {
+            keyword: keyword.to_string(),
+            location: location.clone(),
+            calls: AtomicU64::new(0),
+            total: AtomicU64::new(0),
+            own: AtomicU64::new(0),
+        });
+        self.stats.lock().push(Arc::clone(&stats));
+        stats
+    }
+
+    fn report(&self) -> Profile {
+        // The same location could be compiled more than once, e.g. via `JSONSchema::subschema`
+        let mut entries: HashMap<(&JSONPointer, &str), ProfileEntry> = HashMap::new();
+        let stats = self.stats.lock();
+        for item in stats.iter() {
+            let calls = item.calls.load(Ordering::Relaxed);
+            if calls == 0 {
+                continue;
+            }
+            let entry = entries
+                .entry((&item.location, &item.keyword))
+                .or_insert_with(|| ProfileEntry {
+                    keyword: item.keyword.clone(),
+                    location: item.location.clone(),
+                    calls: 0,
+                    total: Duration::default(),
+                    own: Duration::default(),
+                });
+            entry.calls += calls;
+            entry.total += Duration::from_nanos(item.total.load(Ordering::Relaxed));
+            entry.own += Duration::from_nanos(item.own.load(Ordering::Relaxed));
+        }
+        let mut entries: Vec<_> = entries.into_values().collect();
+        entries.sort_by(|left, right| {
+            right
+                .own
+                .cmp(&left.own)
+                .then_with(|| left.location.to_string().cmp(&right.location.to_string()))
+        });
+        Profile { entries }
+    }
+
+    fn reset(&self) {
+        for item in self.stats.lock().iter() {
+            item.calls.store(0, Ordering::Relaxed);
+            item.total.store(0, Ordering::Relaxed);
+            item.own.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+thread_local! {
+    // Time spent in nested profiled keywords for every unfinished call on the current thread
+    static NESTED: RefCell<Vec<Duration>> = const { RefCell::new(Vec::new()) };
+}
+
+fn as_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// Measures `is_valid` calls of the wrapped keyword.
+///
+/// Arrays could be validated in parallel, then the time spent on other threads is not subtracted
+/// from the own time of the parent keyword.
+pub(crate) struct ProfileValidator {
+    inner: BoxedValidator,
+    stats: Arc<KeywordStats>,
+}
+
+impl ProfileValidator {
+    #[inline]
+    fn measure(&self, is_valid: impl FnOnce() -> bool) -> bool {
+        NESTED.with(|nested| nested.borrow_mut().push(Duration::default()));
+        let start = Instant::now();
+        let result = is_valid();
+        let elapsed = start.elapsed();
+        let nested = NESTED.with(|nested| {
+            let mut nested = nested.borrow_mut();
+            let own_nested = nested.pop().unwrap_or_default();
+            if let Some(parent) = nested.last_mut() {
+                *parent += elapsed;
+            }
+            own_nested
+        });
+        self.stats.calls.fetch_add(1, Ordering::Relaxed);
+        self.stats
+            .total
+            .fetch_add(as_nanos(elapsed), Ordering::Relaxed);
+        self.stats.own.fetch_add(
+            as_nanos(elapsed.checked_sub(nested).unwrap_or_default()),
+            Ordering::Relaxed,
+        );
+        result
+    }
+}
+
+macro_rules! profile_impl {
+    ($method_suffix:tt, $instance_type: ty, $validate_type: ty) => {
+        paste::item! {
+            #[inline]
+            fn [<is_valid_ $method_suffix>](
+                &self,
+                schema: &JSONSchema,
+                instance: &Value,
+                instance_value: $instance_type,
+            ) -> bool {
+                self.measure(|| self.inner.[<is_valid_ $method_suffix>](schema, instance, instance_value))
+            }
+
+            #[inline]
+            fn [<validate_ $method_suffix>]<'a>(
+                &'a self,
+                schema: &'a JSONSchema,
+                instance: &'a Value,
+                instance_value: $validate_type,
+                instance_path: &InstancePath,
+            ) -> ErrorIterator<'a> {
+                self.inner.[<validate_ $method_suffix>](schema, instance, instance_value, instance_path)
+            }
+        }
+    };
+}
+
+impl Validate for ProfileValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        self.inner.subschemas()
+    }
+
+    profile_impl!(array, &[Value], &'a [Value]);
+    profile_impl!(boolean, bool, bool);
+    profile_impl!(null, (), ());
+    profile_impl!(number, f64, f64);
+    profile_impl!(object, &Map<String, Value>, &'a Map<String, Value>);
+    profile_impl!(signed_integer, i64, i64);
+    profile_impl!(string, &str, &'a str);
+    profile_impl!(unsigned_integer, u64, u64);
+
+    #[inline]
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        self.measure(|| self.inner.is_valid(schema, instance))
+    }
+
+    #[inline]
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        self.inner.validate(schema, instance, instance_path)
+    }
+}
+
+impl ToString for ProfileValidator {
+    fn to_string(&self) -> String {
+        self.inner.to_string()
+    }
+}
+
+#[inline]
+pub(crate) fn wrap(
+    inner: BoxedValidator,
+    keyword: &str,
+    location: &JSONPointer,
+    profiler: &Profiler,
+) -> BoxedValidator {
+    Box::new(ProfileValidator {
+        inner,
+        stats: profiler.register(keyword, location),
+    })
+}
+
+impl<'a> JSONSchema<'a> {
+    /// Get time and call counts of `is_valid` calls for every keyword.
+    ///
+    /// The schema should be compiled with `CompilationOptions::with_profiling`, otherwise the profile is empty.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({"items": {"pattern": "^[a-z]+$"}, "uniqueItems": true});
+    /// let compiled = JSONSchema::options()
+    ///     .with_profiling()
+    ///     .compile(&schema)
+    ///     .expect("A valid schema");
+    /// for _ in 0..100 {
+    ///     compiled.is_valid(&json!(["foo", "bar"]));
+    /// }
+    /// let profile = compiled.profile();
+    /// let pattern = profile
+    ///     .entries
+    ///     .iter()
+    ///     .find(|entry| entry.keyword == "pattern")
+    ///     .expect("Pattern is called");
+    /// assert_eq!(pattern.calls, 200);
+    /// println!("{}", profile);
+    /// ```
+    #[must_use]
+    pub fn profile(&self) -> Profile {
+        match self.context.config.profiler() {
+            Some(profiler) => profiler.report(),
+            None => Profile {
+                entries: Vec::new(),
+            },
+        }
+    }
+
+    /// Reset statistics collected for `JSONSchema::profile`.
+    pub fn reset_profile(&self) {
+        if let Some(profiler) = self.context.config.profiler() {
+            profiler.reset()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compilation::JSONSchema;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn profiled(schema: &Value) -> JSONSchema<'_> {
+        JSONSchema::options()
+            .with_profiling()
+            .compile(schema)
+            .unwrap()
+    }
+
+    #[test_case(json!({"type": "string", "minLength": 1}), json!("a"), &[("/minLength", 3), ("/type", 3)])]
+    #[test_case(json!({"properties": {"a": {"type": "string"}}}), json!({"a": "b"}), &[("/properties", 3), ("/properties/a/type", 3)])]
+    #[test_case(json!({"items": {"$ref": "#/definitions/a"}, "definitions": {"a": {"enum": [1, 2]}}}), json!([1, 2]), &[("/items", 3), ("/items/$ref", 6), ("/items/$ref/enum", 6)])]
+    // Keywords that are not called are not reported
+    #[test_case(json!({"anyOf": [{"const": 1}, {"const": 2}]}), json!(1), &[("/anyOf", 3), ("/anyOf/0/const", 3)])]
+    fn calls(schema: Value, instance: Value, expected: &[(&str, u64)]) {
+        let compiled = profiled(&schema);
+        for _ in 0..3 {
+            assert!(compiled.is_valid(&instance));
+        }
+        let mut actual: Vec<_> = compiled
+            .profile()
+            .entries
+            .into_iter()
+            .map(|entry| (entry.location.to_string(), entry.calls))
+            .collect();
+        actual.sort();
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|(location, calls)| ((*location).to_string(), *calls))
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn own_time() {
+        let schema = json!({"properties": {"a": {"pattern": "^(a|b)+$"}}});
+        let compiled = profiled(&schema);
+        assert!(compiled.is_valid(&json!({"a": "ab".repeat(1000)})));
+        let profile = compiled.profile();
+        let properties = &profile
+            .entries
+            .iter()
+            .find(|entry| entry.keyword == "properties")
+            .unwrap();
+        let pattern = &profile
+            .entries
+            .iter()
+            .find(|entry| entry.keyword == "pattern")
+            .unwrap();
+        assert!(properties.total >= pattern.total);
+        assert!(properties.own <= properties.total);
+        assert_eq!(pattern.own, pattern.total);
+    }
+
+    #[test]
+    fn reset() {
+        let schema = json!({"type": "string"});
+        let compiled = profiled(&schema);
+        assert!(compiled.is_valid(&json!("a")));
+        assert_eq!(compiled.profile().entries.len(), 1);
+        compiled.reset_profile();
+        assert!(compiled.profile().entries.is_empty());
+        assert!(compiled.profile().to_string().starts_with("    own (µs)"));
+    }
+
+    #[test]
+    fn tree_does_not_register_keywords() {
+        let schema = json!({"properties": {"a": {"type": "string"}}});
+        let compiled = profiled(&schema);
+        let profiler = compiled.context.config.profiler().unwrap();
+        let registered = profiler.stats.lock().len();
+        for _ in 0..3 {
+            assert_eq!(compiled.tree().iter().count(), 2);
+        }
+        assert_eq!(profiler.stats.lock().len(), registered);
+    }
+
+    #[test]
+    fn without_profiling() {
+        let schema = json!({"type": "string"});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        assert!(compiled.is_valid(&json!("a")));
+        assert!(compiled.profile().entries.is_empty());
+    }
+}
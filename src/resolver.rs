@@ -10,37 +10,63 @@ use std::{borrow::Cow, collections::HashMap};
 use url::Url;
 
 #[derive(Debug)]
-pub(crate) struct Resolver<'a> {
-    // canonical_id: sub-schema mapping to resolve documents by their ID
+pub(crate) struct Resolver {
+    // canonical_id: JSON pointer mapping to resolve documents by their ID
     // canonical_id is composed with the root document id
     // (if not specified, then `DEFAULT_ROOT_URL` is used for this purpose)
-    schemas: HashMap<String, &'a Value>,
+    // Pointers are stored instead of references, so the resolver doesn't borrow the root document
+    schemas: HashMap<String, String>,
 }
 
-impl<'a> Resolver<'a> {
+impl Resolver {
     pub(crate) fn new(
         draft: Draft,
         scope: &Url,
-        schema: &'a Value,
-    ) -> Result<Resolver<'a>, CompilationError> {
+        schema: &Value,
+    ) -> Result<Resolver, CompilationError> {
         let mut schemas = HashMap::new();
         // traverse the schema and store all named ones under their canonical ids
-        find_schemas(draft, schema, scope, &mut |id, schema| {
-            schemas.insert(id, schema);
-            None
-        })?;
+        find_schema_pointers(draft, schema, scope, &mut String::new(), &mut schemas)?;
         Ok(Resolver { schemas })
     }
 
+    /// Build a resolver from previously collected JSON pointers to named subschemas.
+    pub(crate) const fn from_pointers(schemas: HashMap<String, String>) -> Resolver {
+        Resolver { schemas }
+    }
+
+    /// JSON pointers to named subschemas by their canonical ids.
+    pub(crate) const fn pointers(&self) -> &HashMap<String, String> {
+        &self.schemas
+    }
+
+    /// Whether the given `url` points to the root document or to one of its named subschemas.
+    pub(crate) fn is_local(&self, url: &Url) -> bool {
+        if self.schemas.contains_key(url.as_str()) {
+            return true;
+        }
+        let mut resource = url.clone();
+        resource.set_fragment(None);
+        resource.as_str() == DEFAULT_ROOT_URL || self.schemas.contains_key(resource.as_str())
+    }
+
     /// Load a document for the given `url`.
     /// It may be:
     ///   - the root document (`DEFAULT_ROOT_URL`) case;
     ///   - named subschema that is stored in `self.schemas`;
     ///   - document from a remote location;
-    fn resolve_url(&self, url: &Url, schema: &'a Value) -> Result<Cow<'a, Value>, ValidationError> {
+    fn resolve_url<'a>(
+        &self,
+        url: &Url,
+        schema: &'a Value,
+    ) -> Result<Cow<'a, Value>, ValidationError<'a>> {
         match url.as_str() {
             DEFAULT_ROOT_URL => Ok(Cow::Borrowed(schema)),
-            url_str => match self.schemas.get(url_str) {
+            url_str => match self
+                .schemas
+                .get(url_str)
+                .and_then(|ptr| schema.pointer(ptr))
+            {
                 Some(value) => Ok(Cow::Borrowed(value)),
                 None => match url.scheme() {
                     "http" | "https" => {
@@ -60,12 +86,12 @@ impl<'a> Resolver<'a> {
             },
         }
     }
-    pub(crate) fn resolve_fragment(
+    pub(crate) fn resolve_fragment<'a>(
         &self,
         draft: Draft,
         url: &Url,
         schema: &'a Value,
-    ) -> Result<(Url, Cow<'a, Value>), ValidationError> {
+    ) -> Result<(Url, Cow<'a, Value>), ValidationError<'a>> {
         let mut resource = url.clone();
         resource.set_fragment(None);
         let fragment =
@@ -157,6 +183,51 @@ where
     Ok(None)
 }
 
+/// Find all named sub-schemas in the document and store JSON pointers to them under their canonical ids.
+fn find_schema_pointers(
+    draft: Draft,
+    schema: &Value,
+    base_url: &Url,
+    pointer: &mut String,
+    schemas: &mut HashMap<String, String>,
+) -> Result<(), url::ParseError> {
+    match schema {
+        Value::Object(item) => {
+            let new_url;
+            let base_url = if let Some(url) = id_of(draft, schema) {
+                new_url = base_url.join(url)?;
+                // An empty fragment, e.g. in `http://json-schema.org/draft-07/schema#`, identifies the same document
+                let mut id = new_url.clone();
+                if id.fragment() == Some("") {
+                    id.set_fragment(None);
+                }
+                schemas.insert(id.to_string(), pointer.clone());
+                &new_url
+            } else {
+                base_url
+            };
+            for (key, subschema) in item {
+                let length = pointer.len();
+                pointer.push('/');
+                pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                find_schema_pointers(draft, subschema, base_url, pointer, schemas)?;
+                pointer.truncate(length);
+            }
+        }
+        Value::Array(items) => {
+            for (idx, item) in items.iter().enumerate() {
+                let length = pointer.len();
+                pointer.push('/');
+                pointer.push_str(&idx.to_string());
+                find_schema_pointers(draft, item, base_url, pointer, schemas)?;
+                pointer.truncate(length);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
 /// Based on `serde_json`, but tracks folders in the traversed documents.
 pub(crate) fn pointer<'a>(
     draft: Draft,
@@ -229,6 +300,25 @@ mod tests {
         assert_eq!(resolver.schemas.len(), 0);
     }
 
+    #[test]
+    fn empty_fragment() {
+        // When the root identifier ends with an empty fragment
+        let schema = json!({
+            "$id": "http://example.com/schema#",
+            "definitions": {"A": {"type": "integer"}},
+            "items": {"$ref": "#/definitions/A"}
+        });
+        let resolver = make_resolver(&schema);
+        // Then the fragment is not a part of the stored id
+        assert!(resolver.schemas.contains_key("http://example.com/schema"));
+        // And references are resolved without fetching the document
+        let url = Url::parse("http://example.com/schema#/definitions/A").unwrap();
+        let (_, resolved) = resolver
+            .resolve_fragment(Draft::Draft7, &url, &schema)
+            .unwrap();
+        assert_eq!(resolved, Cow::Borrowed(&json!({"type": "integer"})));
+    }
+
     #[test]
     fn sub_schema_in_object() {
         // When only one sub-schema is specified inside an object
@@ -242,8 +332,11 @@ mod tests {
         // Then in the resolver schema there should be only this schema
         assert_eq!(resolver.schemas.len(), 1);
         assert_eq!(
-            resolver.schemas.get("json-schema:///#foo"),
-            schema.pointer("/definitions/A").as_ref()
+            resolver
+                .schemas
+                .get("json-schema:///#foo")
+                .and_then(|ptr| schema.pointer(ptr)),
+            schema.pointer("/definitions/A")
         );
     }
 
@@ -262,12 +355,18 @@ mod tests {
         // Then in the resolver schema there should be only these schemas
         assert_eq!(resolver.schemas.len(), 2);
         assert_eq!(
-            resolver.schemas.get("json-schema:///#foo"),
-            schema.pointer("/definitions/A/0").as_ref()
+            resolver
+                .schemas
+                .get("json-schema:///#foo")
+                .and_then(|ptr| schema.pointer(ptr)),
+            schema.pointer("/definitions/A/0")
         );
         assert_eq!(
-            resolver.schemas.get("json-schema:///#bar"),
-            schema.pointer("/definitions/A/1").as_ref()
+            resolver
+                .schemas
+                .get("json-schema:///#bar")
+                .and_then(|ptr| schema.pointer(ptr)),
+            schema.pointer("/definitions/A/1")
         );
     }
 
@@ -303,12 +402,18 @@ mod tests {
         // Then in the resolver schema there should be root & sub-schema
         assert_eq!(resolver.schemas.len(), 2);
         assert_eq!(
-            resolver.schemas.get("http://localhost:1234/tree"),
-            schema.pointer("").as_ref()
+            resolver
+                .schemas
+                .get("http://localhost:1234/tree")
+                .and_then(|ptr| schema.pointer(ptr)),
+            schema.pointer("")
         );
         assert_eq!(
-            resolver.schemas.get("http://localhost:1234/node"),
-            schema.pointer("/definitions/node").as_ref()
+            resolver
+                .schemas
+                .get("http://localhost:1234/node")
+                .and_then(|ptr| schema.pointer(ptr)),
+            schema.pointer("/definitions/node")
         );
     }
 
@@ -323,8 +428,11 @@ mod tests {
         let resolver = make_resolver(&schema);
         assert_eq!(resolver.schemas.len(), 1);
         assert_eq!(
-            resolver.schemas.get("http://localhost:1234/bar#foo"),
-            schema.pointer("/definitions/A").as_ref()
+            resolver
+                .schemas
+                .get("http://localhost:1234/bar#foo")
+                .and_then(|ptr| schema.pointer(ptr)),
+            schema.pointer("/definitions/A")
         );
     }
 
@@ -348,18 +456,25 @@ mod tests {
         let resolver = make_resolver(&schema);
         assert_eq!(resolver.schemas.len(), 3);
         assert_eq!(
-            resolver.schemas.get("http://localhost:1234/root"),
-            schema.pointer("").as_ref()
+            resolver
+                .schemas
+                .get("http://localhost:1234/root")
+                .and_then(|ptr| schema.pointer(ptr)),
+            schema.pointer("")
         );
         assert_eq!(
-            resolver.schemas.get("http://localhost:1234/nested.json"),
-            schema.pointer("/definitions/A").as_ref()
+            resolver
+                .schemas
+                .get("http://localhost:1234/nested.json")
+                .and_then(|ptr| schema.pointer(ptr)),
+            schema.pointer("/definitions/A")
         );
         assert_eq!(
             resolver
                 .schemas
-                .get("http://localhost:1234/nested.json#foo"),
-            schema.pointer("/definitions/A/definitions/B").as_ref()
+                .get("http://localhost:1234/nested.json#foo")
+                .and_then(|ptr| schema.pointer(ptr)),
+            schema.pointer("/definitions/A/definitions/B")
         );
     }
 
@@ -375,12 +490,18 @@ mod tests {
         let resolver = make_resolver(&schema);
         assert_eq!(resolver.schemas.len(), 2);
         assert_eq!(
-            resolver.schemas.get("http://localhost:1234/"),
-            schema.pointer("").as_ref()
+            resolver
+                .schemas
+                .get("http://localhost:1234/")
+                .and_then(|ptr| schema.pointer(ptr)),
+            schema.pointer("")
         );
         assert_eq!(
-            resolver.schemas.get("http://localhost:1234/folder/"),
-            schema.pointer("/items").as_ref()
+            resolver
+                .schemas
+                .get("http://localhost:1234/folder/")
+                .and_then(|ptr| schema.pointer(ptr)),
+            schema.pointer("/items")
         );
     }
 
@@ -405,12 +526,16 @@ mod tests {
         assert_eq!(
             resolver
                 .schemas
-                .get("http://localhost:1234/scope_change_defs1.json"),
-            schema.pointer("").as_ref()
+                .get("http://localhost:1234/scope_change_defs1.json")
+                .and_then(|ptr| schema.pointer(ptr)),
+            schema.pointer("")
         );
         assert_eq!(
-            resolver.schemas.get("http://localhost:1234/folder/"),
-            schema.pointer("/definitions/baz").as_ref()
+            resolver
+                .schemas
+                .get("http://localhost:1234/folder/")
+                .and_then(|ptr| schema.pointer(ptr)),
+            schema.pointer("/definitions/baz")
         );
     }
 
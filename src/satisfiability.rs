@@ -0,0 +1,1024 @@
+//! Static satisfiability analysis.
+//!
+//! Every schema object is flattened into a conjunction of parts - `allOf` members and `$ref` targets are
+//! inlined. Then constraints of every instance type are checked for contradictions, e.g. `minLength` above
+//! `maxLength`, and finite sets of `const` / `enum` values are validated against the other parts.
+//!
+//! The analysis is conservative - keywords it doesn't understand are assumed to accept something, so only
+//! schemas that reject every instance are reported.
+use crate::{
+    compilation::JSONSchema,
+    keywords::pattern::convert_regex,
+    normalizer::{COMBINATORS, SUBSCHEMA_KEYWORDS, SUBSCHEMA_MAP_KEYWORDS},
+    paths::JSONPointer,
+    resolver::Resolver,
+    schemas::{id_of, Draft},
+};
+use regex::Regex;
+use serde_json::Value;
+use std::{borrow::Cow, collections::BTreeSet, convert::TryFrom, fmt};
+use url::Url;
+
+/// How many references could be resolved in a row before giving up.
+const MAX_REFERENCES: usize = 64;
+/// How deep nested subschemas are analyzed, e.g. schemas of required properties.
+const MAX_DEPTH: usize = 16;
+/// Instance types distinguished by the analysis.
+const TYPES: &[&str] = &[
+    "null", "boolean", "object", "array", "string", "number", "integer",
+];
+
+/// Why a subschema is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsatisfiableKind {
+    /// No instance is valid against the subschema.
+    Schema,
+    /// An `anyOf` / `oneOf` branch never matches together with the rest of its parent schema.
+    DeadBranch,
+    /// A property declared in `properties` can't be present in any valid instance.
+    ImpossibleProperty,
+}
+
+impl fmt::Display for UnsatisfiableKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            UnsatisfiableKind::Schema => "Unsatisfiable schema",
+            UnsatisfiableKind::DeadBranch => "Dead branch",
+            UnsatisfiableKind::ImpossibleProperty => "Impossible property",
+        })
+    }
+}
+
+/// A subschema that can never match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsatisfiableSchema {
+    /// What is reported.
+    pub kind: UnsatisfiableKind,
+    /// Location of the subschema in the original document.
+    pub schema_path: JSONPointer,
+    /// Human-readable explanation.
+    pub reason: String,
+}
+
+impl fmt::Display for UnsatisfiableSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at '{}': {}",
+            self.kind, self.schema_path, self.reason
+        )
+    }
+}
+
+/// A member of a conjunction. The location is known only if the part is not behind a reference.
+#[derive(Clone)]
+struct Part<'r> {
+    schema: Cow<'r, Value>,
+    scope: Url,
+    location: Option<JSONPointer>,
+}
+
+impl<'r> Part<'r> {
+    /// A subschema under `keyword` and an optional `name` / index.
+    fn child(&self, keyword: &str, key: Option<Key<'_>>) -> Option<Cow<'r, Value>> {
+        match &self.schema {
+            Cow::Borrowed(schema) => lookup(schema, keyword, key).map(Cow::Borrowed),
+            Cow::Owned(schema) => lookup(schema, keyword, key).cloned().map(Cow::Owned),
+        }
+    }
+
+    fn get(&self, keyword: &str) -> Option<&Value> {
+        self.schema.get(keyword)
+    }
+}
+
+fn lookup<'v>(schema: &'v Value, keyword: &str, key: Option<Key<'_>>) -> Option<&'v Value> {
+    let value = schema.get(keyword)?;
+    match key {
+        Some(Key::Name(name)) => value.get(name),
+        Some(Key::Index(idx)) => value.get(idx),
+        None => Some(value),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Key<'k> {
+    Name(&'k str),
+    Index(usize),
+}
+
+struct Analyzer<'r> {
+    draft: Draft,
+    root: &'r Value,
+    resolver: &'r Resolver,
+    depth: usize,
+    findings: Vec<UnsatisfiableSchema>,
+}
+
+impl<'r> Analyzer<'r> {
+    fn report(&mut self, kind: UnsatisfiableKind, schema_path: JSONPointer, reason: String) {
+        self.findings.push(UnsatisfiableSchema {
+            kind,
+            schema_path,
+            reason,
+        });
+    }
+
+    fn is_reported(&self, path: &JSONPointer) -> bool {
+        self.findings
+            .iter()
+            .any(|finding| &finding.schema_path == path)
+    }
+
+    fn walk(&mut self, schema: &'r Value, scope: &Url, path: &JSONPointer) {
+        let object = match schema {
+            Value::Object(object) => object,
+            // Literal `false` is intentional
+            _ => return,
+        };
+        let scope = match id_of(self.draft, schema) {
+            Some(id) => match scope.join(id) {
+                Ok(scope) => scope,
+                Err(_) => return,
+            },
+            None => scope.clone(),
+        };
+        let reported = self.findings.len();
+        for (keyword, value) in object {
+            let keyword_path = path.join(keyword.as_str());
+            let keyword = keyword.as_str();
+            match value {
+                Value::Array(items) if COMBINATORS.contains(&keyword) || keyword == "items" => {
+                    for (idx, item) in items.iter().enumerate() {
+                        self.walk(item, &scope, &keyword_path.join(idx));
+                    }
+                }
+                Value::Object(entries) if SUBSCHEMA_MAP_KEYWORDS.contains(&keyword) => {
+                    for (name, value) in entries {
+                        self.walk(value, &scope, &keyword_path.join(name.as_str()));
+                    }
+                }
+                _ if SUBSCHEMA_KEYWORDS.contains(&keyword) => {
+                    self.walk(value, &scope, &keyword_path)
+                }
+                _ => {}
+            }
+        }
+        // Reference targets are analyzed at their own locations
+        if object.contains_key("$ref") {
+            return;
+        }
+        let mut parts = Vec::new();
+        let part = Part {
+            schema: Cow::Borrowed(schema),
+            scope,
+            location: Some(path.clone()),
+        };
+        if !self.flatten(part, &mut parts, &mut 0) {
+            return;
+        }
+        if let Some(reason) = self.check(&parts, true) {
+            // Report only the origin of the problem, not every schema that contains it
+            if self.findings.len() == reported || path.iter().next().is_none() {
+                self.report(UnsatisfiableKind::Schema, path.clone(), reason);
+            }
+            return;
+        }
+        self.dead_branches(&parts);
+        self.impossible_properties(&parts);
+    }
+
+    fn dead_branches(&mut self, parts: &[Part<'r>]) {
+        for part in parts {
+            let location = match &part.location {
+                Some(location) => location,
+                None => continue,
+            };
+            for keyword in &["anyOf", "oneOf"] {
+                let branches = match part.get(keyword) {
+                    Some(Value::Array(branches)) => branches.len(),
+                    _ => continue,
+                };
+                for idx in 0..branches {
+                    let branch_path = location.join(*keyword).join(idx);
+                    let branch = match part.child(keyword, Some(Key::Index(idx))) {
+                        Some(branch) => branch,
+                        None => continue,
+                    };
+                    if branch.as_ref() == &Value::Bool(false) || self.is_reported(&branch_path) {
+                        continue;
+                    }
+                    let branch = Part {
+                        schema: branch,
+                        scope: part.scope.clone(),
+                        location: None,
+                    };
+                    if let Some(reason) = self.branch_reason(parts, branch) {
+                        self.report(UnsatisfiableKind::DeadBranch, branch_path, reason);
+                    }
+                }
+            }
+        }
+    }
+
+    fn impossible_properties(&mut self, parts: &[Part<'r>]) {
+        for part in parts {
+            let (location, properties) = match (&part.location, part.get("properties")) {
+                (Some(location), Some(Value::Object(properties))) => (location, properties),
+                _ => continue,
+            };
+            for (name, schema) in properties {
+                let property_path = location.join("properties").join(name.as_str());
+                if schema == &Value::Bool(false) || self.is_reported(&property_path) {
+                    continue;
+                }
+                if let Some(reason) = self.property_reason(parts, name) {
+                    self.report(
+                        UnsatisfiableKind::ImpossibleProperty,
+                        property_path,
+                        format!("Property '{}' {}", name, reason),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Inline `$ref` targets and `allOf` members. Returns `false` if a reference can't be resolved.
+    fn flatten(
+        &self,
+        mut part: Part<'r>,
+        parts: &mut Vec<Part<'r>>,
+        references: &mut usize,
+    ) -> bool {
+        if let Some(id) = id_of(self.draft, &part.schema) {
+            match part.scope.join(id) {
+                Ok(scope) => part.scope = scope,
+                Err(_) => return false,
+            }
+        }
+        if let Some(Value::String(reference)) = part.get("$ref") {
+            *references += 1;
+            if *references > MAX_REFERENCES {
+                return false;
+            }
+            let url = match part.scope.join(reference) {
+                Ok(url) => url,
+                Err(_) => return false,
+            };
+            return match self.resolver.resolve_fragment(self.draft, &url, self.root) {
+                Ok((scope, schema)) => {
+                    let target = Part {
+                        schema,
+                        scope,
+                        location: None,
+                    };
+                    self.flatten(target, parts, references)
+                }
+                Err(_) => false,
+            };
+        }
+        if let Some(Value::Array(members)) = part.get("allOf") {
+            for idx in 0..members.len() {
+                if let Some(member) = part.child("allOf", Some(Key::Index(idx))) {
+                    let member = Part {
+                        schema: member,
+                        scope: part.scope.clone(),
+                        location: part
+                            .location
+                            .as_ref()
+                            .map(|location| location.join("allOf").join(idx)),
+                    };
+                    if !self.flatten(member, parts, references) {
+                        return false;
+                    }
+                }
+            }
+        }
+        parts.push(part);
+        true
+    }
+
+    /// The reason why no instance satisfies all `parts`, if there is one.
+    /// `anyOf` / `oneOf` are checked only with `disjunctions`, their branches are checked without them.
+    fn check(&mut self, parts: &[Part<'r>], disjunctions: bool) -> Option<String> {
+        if self.depth >= MAX_DEPTH {
+            return None;
+        }
+        self.depth += 1;
+        let reason = self.check_parts(parts, disjunctions);
+        self.depth -= 1;
+        reason
+    }
+
+    fn check_parts(&mut self, parts: &[Part<'r>], disjunctions: bool) -> Option<String> {
+        for part in parts {
+            if part.schema.as_ref() == &Value::Bool(false) {
+                return Some("`false` schema never matches".to_string());
+            }
+            if let Some(not) = part.get("not") {
+                if not == &Value::Bool(true)
+                    || not.as_object().map(|not| not.is_empty()) == Some(true)
+                {
+                    return Some("`not` of a schema that matches everything".to_string());
+                }
+            }
+        }
+        if let Some(values) = self.values(parts) {
+            // Every part is checked by the validator itself, including `anyOf` / `oneOf`
+            return self.values_reason(parts, &values);
+        }
+        let types = types(self.draft, schemas(parts));
+        if types.is_empty() {
+            return Some("`type` constraints have no type in common".to_string());
+        }
+        let mut reasons = Vec::with_capacity(types.len());
+        for name in &types {
+            match self.type_reason(name, parts) {
+                Some(reason) => reasons.push((*name, reason)),
+                None => {
+                    reasons.clear();
+                    break;
+                }
+            }
+        }
+        match reasons.len() {
+            0 => {}
+            1 => return reasons.pop().map(|(_, reason)| reason),
+            _ => {
+                let reasons: Vec<_> = reasons
+                    .iter()
+                    .map(|(name, reason)| format!("{}: {}", name, reason))
+                    .collect();
+                return Some(reasons.join("; "));
+            }
+        }
+        if disjunctions {
+            for part in parts {
+                for keyword in &["anyOf", "oneOf"] {
+                    let branches = match part.get(keyword) {
+                        Some(Value::Array(branches)) => branches.len(),
+                        _ => continue,
+                    };
+                    let mut is_dead = true;
+                    for idx in 0..branches {
+                        if let Some(branch) = part.child(keyword, Some(Key::Index(idx))) {
+                            let branch = Part {
+                                schema: branch,
+                                scope: part.scope.clone(),
+                                location: None,
+                            };
+                            if self.branch_reason(parts, branch).is_none() {
+                                is_dead = false;
+                                break;
+                            }
+                        }
+                    }
+                    if is_dead {
+                        return Some(format!("No `{}` branch can match", keyword));
+                    }
+                }
+                if let Some(reason) = self.condition_reason(parts, part) {
+                    return Some(reason);
+                }
+            }
+        }
+        None
+    }
+
+    /// The reason why neither `then` nor `else` of `part` can match together with `parts`.
+    /// `else` is never taken if `if` matches everything.
+    fn condition_reason(&mut self, parts: &[Part<'r>], part: &Part<'r>) -> Option<String> {
+        let condition = self.draft.get_validator("if").and(part.get("if"))?;
+        let is_always = condition == &Value::Bool(true)
+            || condition.as_object().map(|condition| condition.is_empty()) == Some(true);
+        let mut reasons = Vec::with_capacity(2);
+        for keyword in &["then", "else"] {
+            if *keyword == "else" && is_always {
+                continue;
+            }
+            let branch = Part {
+                schema: part.child(keyword, None)?,
+                scope: part.scope.clone(),
+                location: None,
+            };
+            reasons.push(self.branch_reason(parts, branch)?);
+        }
+        Some(if is_always {
+            format!("`if` always matches, but `then` can't: {}", reasons[0])
+        } else {
+            "Neither `then` nor `else` can match".to_string()
+        })
+    }
+
+    fn branch_reason(&mut self, parts: &[Part<'r>], branch: Part<'r>) -> Option<String> {
+        let mut combined = parts.to_vec();
+        if !self.flatten(branch, &mut combined, &mut 0) {
+            return None;
+        }
+        self.check(&combined, false)
+    }
+
+    /// Values allowed by `const` / `enum`, if any part restricts instances to a finite set.
+    fn values(&self, parts: &[Part<'r>]) -> Option<Vec<Value>> {
+        let has_const = self.draft.get_validator("const").is_some();
+        let mut smallest: Option<&[Value]> = None;
+        for part in parts {
+            let values = match (part.get("const"), part.get("enum")) {
+                (Some(value), _) if has_const => std::slice::from_ref(value),
+                (_, Some(Value::Array(values))) => values.as_slice(),
+                _ => continue,
+            };
+            match smallest {
+                Some(smallest) if smallest.len() <= values.len() => {}
+                _ => smallest = Some(values),
+            }
+        }
+        smallest.map(<[Value]>::to_vec)
+    }
+
+    fn values_reason(&self, parts: &[Part<'r>], values: &[Value]) -> Option<String> {
+        let restrictions: Vec<Value> = parts
+            .iter()
+            .filter_map(|part| {
+                let mut restriction = serde_json::Map::new();
+                for keyword in &["const", "enum"] {
+                    if let Some(value) = part.get(keyword) {
+                        restriction.insert((*keyword).to_string(), value.clone());
+                    }
+                }
+                if restriction.is_empty() {
+                    None
+                } else {
+                    Some(Value::Object(restriction))
+                }
+            })
+            .collect();
+        let allowed: Vec<&Value> = values
+            .iter()
+            .filter(|value| {
+                restrictions
+                    .iter()
+                    .all(|restriction| accepts(self.draft, restriction, value))
+            })
+            .collect();
+        if allowed.is_empty() {
+            return Some(if values.is_empty() {
+                "`enum` is empty".to_string()
+            } else {
+                "`const` / `enum` values have no value in common".to_string()
+            });
+        }
+        if allowed.iter().any(|value| {
+            parts
+                .iter()
+                .all(|part| accepts(self.draft, &part.schema, value))
+        }) {
+            None
+        } else if let [value] = allowed.as_slice() {
+            Some(format!("Value {} doesn't satisfy other constraints", value))
+        } else {
+            Some("No allowed value satisfies other constraints".to_string())
+        }
+    }
+
+    fn type_reason(&mut self, name: &str, parts: &[Part<'r>]) -> Option<String> {
+        match name {
+            "number" => self.number_reason(parts, false),
+            "integer" => self.number_reason(parts, true),
+            "string" => limits_reason(parts, "minLength", "maxLength"),
+            "array" => self.array_reason(parts),
+            "object" => self.object_reason(parts),
+            _ => None,
+        }
+    }
+
+    fn number_reason(&self, parts: &[Part<'r>], integer: bool) -> Option<String> {
+        let (lower, upper) = bounds(self.draft, schemas(parts));
+        let ((low, low_exclusive), (high, high_exclusive)) = match (lower, upper) {
+            (Some(lower), Some(upper)) => (lower, upper),
+            _ => return None,
+        };
+        if low > high || (low >= high && (low_exclusive || high_exclusive)) {
+            return Some(format!(
+                "The lower bound {} is above the upper bound {}",
+                low, high
+            ));
+        }
+        // The smallest and the largest integers within the bounds
+        let first = if low_exclusive {
+            low.floor() + 1.0
+        } else {
+            low.ceil()
+        };
+        let last = if high_exclusive {
+            high.ceil() - 1.0
+        } else {
+            high.floor()
+        };
+        if integer && first > last {
+            return Some(format!("There is no integer between {} and {}", low, high));
+        }
+        for part in parts {
+            // Only integer divisors, float multiples are not precise enough
+            let divisor = match part.get("multipleOf").and_then(Value::as_f64) {
+                Some(divisor) if divisor > 0.0 && divisor.fract() == 0.0 => divisor,
+                _ => continue,
+            };
+            let mut multiple = (first / divisor).ceil() * divisor;
+            if multiple < first {
+                multiple += divisor;
+            }
+            if multiple > last {
+                return Some(format!(
+                    "There is no multiple of {} between {} and {}",
+                    divisor, low, high
+                ));
+            }
+        }
+        None
+    }
+
+    fn array_reason(&mut self, parts: &[Part<'r>]) -> Option<String> {
+        if let Some(reason) = limits_reason(parts, "minItems", "maxItems") {
+            return Some(reason);
+        }
+        let max_items = limit(schemas(parts), "maxItems", false);
+        if self.draft != Draft::Draft4 {
+            for part in parts {
+                let contains = match part.child("contains", None) {
+                    Some(contains) => contains,
+                    None => continue,
+                };
+                if max_items == Some(0) {
+                    return Some("`contains` requires an item, but `maxItems` is 0".to_string());
+                }
+                let mut schemas = vec![Part {
+                    schema: contains,
+                    scope: part.scope.clone(),
+                    location: None,
+                }];
+                // The matching item is also validated by `items`, unless positions have their own schemas
+                if parts
+                    .iter()
+                    .all(|part| !matches!(part.get("items"), Some(Value::Array(_))))
+                {
+                    schemas.extend(parts.iter().filter_map(|part| {
+                        part.child("items", None).map(|items| Part {
+                            schema: items,
+                            scope: part.scope.clone(),
+                            location: None,
+                        })
+                    }));
+                }
+                if let Some(reason) = self.subschema_reason(schemas) {
+                    return Some(format!("`contains` never matches: {}", reason));
+                }
+            }
+        }
+        // Positions after the longest `items` array are all the same
+        let positions = parts
+            .iter()
+            .filter_map(|part| part.get("items").and_then(Value::as_array).map(Vec::len))
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let min_items = limit(schemas(parts), "minItems", true).unwrap_or(0);
+        for idx in 0..positions.min(usize::try_from(min_items).unwrap_or(usize::MAX)) {
+            let mut schemas = Vec::new();
+            for part in parts {
+                let schema = match part.get("items") {
+                    Some(Value::Array(items)) if idx < items.len() => {
+                        part.child("items", Some(Key::Index(idx)))
+                    }
+                    Some(Value::Array(_)) => part.child("additionalItems", None),
+                    Some(_) => part.child("items", None),
+                    None => None,
+                };
+                if let Some(schema) = schema {
+                    schemas.push(Part {
+                        schema,
+                        scope: part.scope.clone(),
+                        location: None,
+                    });
+                }
+            }
+            if let Some(reason) = self.subschema_reason(schemas) {
+                return Some(format!(
+                    "Item {} is required by `minItems`, but it can't be valid: {}",
+                    idx, reason
+                ));
+            }
+        }
+        None
+    }
+
+    fn object_reason(&mut self, parts: &[Part<'r>]) -> Option<String> {
+        if let Some(reason) = limits_reason(parts, "minProperties", "maxProperties") {
+            return Some(reason);
+        }
+        let required: BTreeSet<&str> = parts
+            .iter()
+            .filter_map(|part| part.get("required").and_then(Value::as_array))
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect();
+        if let Some(max_properties) = limit(schemas(parts), "maxProperties", false) {
+            if required.len() as u64 > max_properties {
+                return Some(format!(
+                    "{} properties are required, but `maxProperties` is {}",
+                    required.len(),
+                    max_properties
+                ));
+            }
+        }
+        for name in required {
+            if let Some(reason) = self.property_reason(parts, name) {
+                return Some(format!("Required property '{}' {}", name, reason));
+            }
+        }
+        None
+    }
+
+    /// The reason why property `name` can't be present in instances valid against all `parts`.
+    fn property_reason(&mut self, parts: &[Part<'r>], name: &str) -> Option<String> {
+        let mut schemas = Vec::new();
+        for part in parts {
+            let object = match part.schema.as_ref() {
+                Value::Object(object) => object,
+                _ => continue,
+            };
+            let mut found = Vec::new();
+            let mut is_declared = false;
+            if let Some(schema) = part.child("properties", Some(Key::Name(name))) {
+                is_declared = true;
+                found.push(schema);
+            }
+            if let Some(Value::Object(patterns)) = object.get("patternProperties") {
+                for pattern in patterns.keys() {
+                    match Regex::new(&convert_regex(pattern)) {
+                        Ok(regex) if !regex.is_match(name) => {}
+                        Ok(_) => {
+                            is_declared = true;
+                            found.extend(part.child("patternProperties", Some(Key::Name(pattern))));
+                        }
+                        // An unsupported pattern might match
+                        Err(_) => is_declared = true,
+                    }
+                }
+            }
+            if !is_declared {
+                match part.child("additionalProperties", None) {
+                    Some(schema) if schema.as_ref() == &Value::Bool(false) => {
+                        return Some("is forbidden by `additionalProperties`".to_string());
+                    }
+                    schema => found.extend(schema),
+                }
+            }
+            if self.draft != Draft::Draft4 {
+                if let Some(names) = object.get("propertyNames") {
+                    if !accepts(self.draft, names, &Value::String(name.to_string())) {
+                        return Some("is rejected by `propertyNames`".to_string());
+                    }
+                }
+            }
+            schemas.extend(found.into_iter().map(|schema| Part {
+                schema,
+                scope: part.scope.clone(),
+                location: None,
+            }));
+        }
+        self.subschema_reason(schemas)
+            .map(|reason| format!("can't be valid: {}", reason))
+    }
+
+    /// The reason why no instance satisfies all `schemas`.
+    fn subschema_reason(&mut self, schemas: Vec<Part<'r>>) -> Option<String> {
+        let mut parts = Vec::new();
+        for schema in schemas {
+            if !self.flatten(schema, &mut parts, &mut 0) {
+                return None;
+            }
+        }
+        self.check(&parts, true)
+    }
+}
+
+/// Types allowed by all `schemas`, `integer` is present only if `number` is not.
+pub(crate) fn types<'v>(
+    draft: Draft,
+    schemas: impl Iterator<Item = &'v Value>,
+) -> Vec<&'static str> {
+    let mut types = TYPES.to_vec();
+    for schema in schemas {
+        let names: Vec<&str> = match schema.get("type") {
+            Some(Value::String(name)) => vec![name.as_str()],
+            Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+            _ => continue,
+        };
+        let nullable =
+            draft == Draft::OpenAPI30 && matches!(schema.get("nullable"), Some(Value::Bool(true)));
+        types.retain(|name| {
+            names.contains(name)
+                || (*name == "integer" && names.contains(&"number"))
+                || (nullable && *name == "null")
+        });
+    }
+    if types.contains(&"number") {
+        types.retain(|name| *name != "integer");
+    }
+    types
+}
+
+/// A numeric bound and whether it is exclusive.
+pub(crate) type Bound = (f64, bool);
+
+/// The tightest lower and upper bounds for numbers among all `schemas`.
+pub(crate) fn bounds<'v>(
+    draft: Draft,
+    schemas: impl Iterator<Item = &'v Value>,
+) -> (Option<Bound>, Option<Bound>) {
+    let legacy = matches!(draft, Draft::Draft4 | Draft::OpenAPI30);
+    let mut lower = None;
+    let mut upper = None;
+    for schema in schemas {
+        if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+            let exclusive =
+                legacy && matches!(schema.get("exclusiveMinimum"), Some(Value::Bool(true)));
+            tighten(&mut lower, (minimum, exclusive), true);
+        }
+        if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+            let exclusive =
+                legacy && matches!(schema.get("exclusiveMaximum"), Some(Value::Bool(true)));
+            tighten(&mut upper, (maximum, exclusive), false);
+        }
+        if !legacy {
+            if let Some(minimum) = schema.get("exclusiveMinimum").and_then(Value::as_f64) {
+                tighten(&mut lower, (minimum, true), true);
+            }
+            if let Some(maximum) = schema.get("exclusiveMaximum").and_then(Value::as_f64) {
+                tighten(&mut upper, (maximum, true), false);
+            }
+        }
+    }
+    (lower, upper)
+}
+
+fn tighten(bound: &mut Option<Bound>, new: Bound, is_lower: bool) {
+    *bound = match *bound {
+        Some(old) => {
+            let is_tighter = if is_lower {
+                new.0 > old.0
+            } else {
+                new.0 < old.0
+            };
+            if is_tighter || (new.0 == old.0 && new.1) {
+                Some(new)
+            } else {
+                Some(old)
+            }
+        }
+        None => Some(new),
+    };
+}
+
+fn schemas<'p>(parts: &'p [Part<'_>]) -> impl Iterator<Item = &'p Value> {
+    parts.iter().map(|part| part.schema.as_ref())
+}
+
+/// The tightest value of a length limit among all `schemas`.
+pub(crate) fn limit<'v>(
+    schemas: impl Iterator<Item = &'v Value>,
+    keyword: &str,
+    is_lower: bool,
+) -> Option<u64> {
+    let values = schemas.filter_map(|schema| schema.get(keyword).and_then(Value::as_u64));
+    if is_lower {
+        values.max()
+    } else {
+        values.min()
+    }
+}
+
+fn limits_reason(parts: &[Part<'_>], lower: &str, upper: &str) -> Option<String> {
+    match (
+        limit(schemas(parts), lower, true),
+        limit(schemas(parts), upper, false),
+    ) {
+        (Some(low), Some(high)) if low > high => Some(format!(
+            "`{}` {} is greater than `{}` {}",
+            lower, low, upper, high
+        )),
+        _ => None,
+    }
+}
+
+/// Whether `schema` accepts `instance`. Schemas with references are assumed to accept it.
+pub(crate) fn accepts(draft: Draft, schema: &Value, instance: &Value) -> bool {
+    if let Value::Bool(value) = schema {
+        return *value;
+    }
+    if has_reference(schema) {
+        return true;
+    }
+    JSONSchema::options()
+        .with_draft(draft)
+        .compile(schema)
+        .map_or(true, |compiled| compiled.is_valid(instance))
+}
+
+pub(crate) fn has_reference(schema: &Value) -> bool {
+    match schema {
+        Value::Object(object) => object.contains_key("$ref") || object.values().any(has_reference),
+        Value::Array(items) => items.iter().any(has_reference),
+        _ => false,
+    }
+}
+
+/// Find all subschemas of `root` that no instance can satisfy.
+pub(crate) fn analyze(
+    draft: Draft,
+    root: &Value,
+    scope: &Url,
+    resolver: &Resolver,
+) -> Vec<UnsatisfiableSchema> {
+    let mut analyzer = Analyzer {
+        draft,
+        root,
+        resolver,
+        depth: 0,
+        findings: Vec::new(),
+    };
+    analyzer.walk(root, scope, &JSONPointer::default());
+    analyzer
+        .findings
+        .sort_by_cached_key(|finding| finding.schema_path.to_string());
+    analyzer.findings
+}
+
+impl<'a> JSONSchema<'a> {
+    /// Find subschemas that no instance can satisfy, `anyOf` / `oneOf` branches that never match and
+    /// properties that can't be present, e.g. because `additionalProperties` forbids them in an `allOf`
+    /// sibling. References are followed, the findings are ordered by their locations in the original schema.
+    ///
+    /// The analysis is conservative - keywords like `pattern` or `format` are assumed to match something,
+    /// so an empty result doesn't prove that the schema is satisfiable.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({
+    ///     "properties": {
+    ///         "code": {"type": "string", "minLength": 5, "maxLength": 3},
+    ///         "kind": {"anyOf": [{"type": "integer"}, {"type": "string", "enum": [1, 2]}]}
+    ///     }
+    /// });
+    /// let compiled = JSONSchema::compile(&schema).expect("A valid schema");
+    /// let findings: Vec<_> = compiled.unsatisfiable().iter().map(ToString::to_string).collect();
+    /// assert_eq!(
+    ///     findings,
+    ///     vec![
+    ///         "Unsatisfiable schema at '/properties/code': `minLength` 5 is greater than `maxLength` 3",
+    ///         "Unsatisfiable schema at '/properties/kind/anyOf/1': No allowed value satisfies other constraints",
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn unsatisfiable(&self) -> Vec<UnsatisfiableSchema> {
+        analyze(
+            self.context.config.draft(),
+            &self.schema,
+            &self.context.scope,
+            &self.resolver,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UnsatisfiableKind;
+    use crate::{compilation::JSONSchema, schemas::Draft};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn findings(schema: &Value) -> Vec<(UnsatisfiableKind, String)> {
+        JSONSchema::compile(schema)
+            .expect("Valid schema")
+            .unsatisfiable()
+            .into_iter()
+            .map(|finding| (finding.kind, finding.schema_path.to_string()))
+            .collect()
+    }
+
+    #[test_case(json!({"type": "string", "minLength": 5, "maxLength": 3}), ""; "string length")]
+    #[test_case(json!({"allOf": [{"const": 1}, {"const": 2}]}), ""; "conflicting constants")]
+    #[test_case(json!({"allOf": [{"type": "string"}, {"type": "integer"}]}), ""; "conflicting types")]
+    #[test_case(json!({"type": "integer", "enum": [1.5, "a"]}), ""; "enum and type")]
+    #[test_case(json!({"type": "integer", "minimum": 1.2, "maximum": 1.8}), ""; "no integer")]
+    #[test_case(json!({"type": "number", "exclusiveMinimum": 3, "maximum": 3}), ""; "exclusive bounds")]
+    #[test_case(json!({"type": "integer", "minimum": 1, "maximum": 4, "multipleOf": 5}), ""; "no multiple")]
+    #[test_case(json!({"type": "string", "oneOf": [{"type": "integer"}, {"type": "null"}]}), ""; "all branches dead")]
+    #[test_case(json!({"type": "object", "required": ["a"], "additionalProperties": false}), ""; "required but forbidden")]
+    #[test_case(json!({"type": "object", "required": ["a", "b"], "maxProperties": 1}), ""; "too many required")]
+    #[test_case(json!({"type": "array", "contains": {}, "maxItems": 0}), ""; "contains without items")]
+    #[test_case(json!({"type": "array", "items": false, "minItems": 1}), ""; "required item")]
+    #[test_case(json!({"type": "object", "required": ["a"], "properties": {"a": false}}), ""; "required but false")]
+    #[test_case(json!({"type": "object", "required": ["a"], "maxProperties": 0}), ""; "required without properties")]
+    #[test_case(json!({"type": "array", "contains": {"const": 1}, "items": {"type": "string"}}), ""; "contains and items")]
+    #[test_case(json!({"if": {}, "then": false, "else": false}), ""; "no condition branch")]
+    #[test_case(json!({"if": {"type": "string"}, "then": false, "else": false}), ""; "no branch")]
+    #[test_case(json!({"type": "string", "minLength": 1, "if": true, "then": {"maxLength": 0}}), ""; "always then")]
+    #[test_case(json!({"not": {}}), ""; "not anything")]
+    #[test_case(
+        json!({"definitions": {"s": {"type": "string"}}, "allOf": [{"$ref": "#/definitions/s"}, {"type": "number"}]}), "";
+        "reference"
+    )]
+    #[test_case(json!({"properties": {"a": {"type": "string", "minLength": 2, "maxLength": 1}}}), "/properties/a"; "nested")]
+    fn unsatisfiable_schema(schema: Value, path: &str) {
+        assert_eq!(
+            findings(&schema),
+            vec![(UnsatisfiableKind::Schema, path.to_string())]
+        );
+    }
+
+    #[test_case(json!({"type": "string", "anyOf": [{"minLength": 1}, {"type": "integer"}]}), "/anyOf/1"; "any of")]
+    #[test_case(json!({"oneOf": [{"type": "string"}, {"const": "a"}]}), "/oneOf/1"; "overlapping one of")]
+    #[test_case(json!({"allOf": [{"minimum": 5}, {"anyOf": [{"type": "string"}, {"type": "integer", "maximum": 3}]}]}), "/allOf/1/anyOf/1"; "sibling constraints")]
+    fn dead_branch(schema: Value, path: &str) {
+        assert_eq!(
+            findings(&schema),
+            vec![(UnsatisfiableKind::DeadBranch, path.to_string())]
+        );
+    }
+
+    #[test_case(
+        json!({"allOf": [{"properties": {"a": {}}}, {"properties": {"b": {}}, "additionalProperties": false}]}),
+        "/allOf/0/properties/a";
+        "additional properties"
+    )]
+    #[test_case(json!({"properties": {"ab": {}}, "propertyNames": {"maxLength": 1}}), "/properties/ab"; "property names")]
+    #[test_case(
+        json!({"properties": {"a": {"type": "string"}}, "patternProperties": {"^a": {"type": "integer"}}}),
+        "/properties/a";
+        "pattern properties"
+    )]
+    fn impossible_property(schema: Value, path: &str) {
+        assert_eq!(
+            findings(&schema),
+            vec![(UnsatisfiableKind::ImpossibleProperty, path.to_string())]
+        );
+    }
+
+    #[test_case(json!({"minLength": 5, "maxLength": 3}); "other types")]
+    #[test_case(json!({"type": "integer", "enum": [1.0]}); "integer enum")]
+    #[test_case(json!({"anyOf": [{"type": "string"}, {"type": "integer"}]}); "live branches")]
+    #[test_case(json!({"properties": {"a": false}}); "false property")]
+    #[test_case(json!({"anyOf": [false, {}]}); "false branch")]
+    #[test_case(json!({"type": "number", "minimum": 0.1, "maximum": 0.3, "multipleOf": 0.1}); "float multiple")]
+    #[test_case(json!({"type": "object", "properties": {"a": {}}, "required": ["a"], "additionalProperties": false}); "declared property")]
+    #[test_case(
+        json!({"patternProperties": {"^a": {}}, "additionalProperties": false, "allOf": [{"properties": {"ab": {}}}]});
+        "matched pattern"
+    )]
+    #[test_case(json!({"properties": {"next": {"$ref": "#"}}}); "recursive reference")]
+    #[test_case(json!({"type": "string", "pattern": "^a$", "minLength": 2}); "unknown pattern")]
+    // Instances of other types are valid
+    #[test_case(json!({"required": ["a"], "properties": {"a": false}}); "untyped required")]
+    #[test_case(json!({"contains": {"const": 1}, "items": {"type": "string"}}); "untyped contains")]
+    #[test_case(json!({"if": {"type": "string"}, "then": false}); "then only")]
+    #[test_case(json!({"items": [{"type": "string"}], "contains": {"const": 1}}); "contains and positions")]
+    fn satisfiable(schema: Value) {
+        assert_eq!(findings(&schema), vec![]);
+    }
+
+    #[test]
+    fn required_property() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"a": {"type": "string", "minLength": 2, "maxLength": 1}},
+            "required": ["a"]
+        });
+        let compiled = JSONSchema::compile(&schema).expect("Valid schema");
+        let findings: Vec<_> = compiled
+            .unsatisfiable()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            findings,
+            vec![
+                "Unsatisfiable schema at '': Required property 'a' can't be valid: `minLength` 2 is greater than `maxLength` 1",
+                "Unsatisfiable schema at '/properties/a': `minLength` 2 is greater than `maxLength` 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn draft4() {
+        let schema = json!({"type": "string", "const": 1});
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::Draft4)
+            .compile(&schema)
+            .expect("Valid schema");
+        assert!(compiled.unsatisfiable().is_empty());
+    }
+}
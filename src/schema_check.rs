@@ -0,0 +1,360 @@
+//! Checks of schema documents themselves.
+//!
+//! A schema is validated against the meta-schema of its draft, then every `$ref` is resolved and
+//! the schema is compiled together with its definitions and `$ref` targets, collecting all problems
+//! with their locations. References are resolved lazily during validation, therefore a broken one
+//! otherwise goes unnoticed until an instance reaches it.
+use crate::{
+    best_match::best_match,
+    compilation::{
+        compile_validators,
+        context::{CompilationContext, Problems},
+        options::CompilationOptions,
+        reachable::{self, Subschema},
+        JSONSchema, DEFAULT_SCOPE,
+    },
+    paths::{JSONPointer, SchemaPath},
+    resolver::Resolver,
+    schemas::{id_of, Draft},
+};
+use serde_json::Value;
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt, iter,
+};
+
+lazy_static::lazy_static! {
+    static ref DRAFT4: Value = serde_json::from_str(include_str!("../meta_schemas/draft4.json"))
+        .expect("Is a valid JSON");
+    static ref DRAFT6: Value = serde_json::from_str(include_str!("../meta_schemas/draft6.json"))
+        .expect("Is a valid JSON");
+    static ref DRAFT7: Value = serde_json::from_str(include_str!("../meta_schemas/draft7.json"))
+        .expect("Is a valid JSON");
+    static ref DRAFT4_VALIDATOR: JSONSchema<'static> = compile_meta_schema(Draft::Draft4, &DRAFT4);
+    static ref DRAFT6_VALIDATOR: JSONSchema<'static> = compile_meta_schema(Draft::Draft6, &DRAFT6);
+    static ref DRAFT7_VALIDATOR: JSONSchema<'static> = compile_meta_schema(Draft::Draft7, &DRAFT7);
+}
+
+fn compile_meta_schema(draft: Draft, schema: &'static Value) -> JSONSchema<'static> {
+    JSONSchema::options()
+        .with_draft(draft)
+        .compile(schema)
+        .expect("Is a valid schema")
+}
+
+/// Compiled meta-schema of the given draft. `OpenAPI` 3.0 has no official meta-schema.
+fn meta_schema(draft: Draft) -> Option<&'static JSONSchema<'static>> {
+    match draft {
+        Draft::Draft4 => Some(&DRAFT4_VALIDATOR),
+        Draft::Draft6 => Some(&DRAFT6_VALIDATOR),
+        Draft::Draft7 => Some(&DRAFT7_VALIDATOR),
+        Draft::OpenAPI30 => None,
+    }
+}
+
+/// What is wrong with a schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaProblemKind {
+    /// The schema is not valid against the meta-schema of its draft.
+    MetaSchema,
+    /// A `$ref` can't be resolved.
+    Reference,
+    /// A subschema can't be compiled, e.g. its `pattern` is not a supported regular expression.
+    Compilation,
+}
+
+impl fmt::Display for SchemaProblemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SchemaProblemKind::MetaSchema => "Invalid schema",
+            SchemaProblemKind::Reference => "Unresolvable reference",
+            SchemaProblemKind::Compilation => "Compilation error",
+        })
+    }
+}
+
+/// A problem found by `CompilationOptions::check_schema`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaProblem {
+    /// What is reported.
+    pub kind: SchemaProblemKind,
+    /// Location of the problem within the schema.
+    pub schema_path: JSONPointer,
+    /// Human-readable explanation.
+    pub message: String,
+}
+
+impl fmt::Display for SchemaProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at '{}': {}",
+            self.kind, self.schema_path, self.message
+        )
+    }
+}
+
+/// Violations of the draft's meta-schema.
+fn meta_schema_problems(draft: Draft, schema: &Value) -> Vec<SchemaProblem> {
+    let mut problems = Vec::new();
+    if let Some(meta_schema) = meta_schema(draft) {
+        if let Err(errors) = meta_schema.validate(schema) {
+            // Errors of `anyOf` / `oneOf` in meta-schemas are replaced by more precise errors of their branches
+            for error in errors.filter_map(|error| best_match(iter::once(error))) {
+                problems.push(SchemaProblem {
+                    kind: SchemaProblemKind::MetaSchema,
+                    schema_path: error.instance_path.clone(),
+                    message: error.to_string(),
+                })
+            }
+        }
+    }
+    problems
+}
+
+impl CompilationOptions {
+    /// Check the schema itself and report every problem with its location: violations of the draft's
+    /// meta-schema, `$ref`s that can't be resolved, and subschemas that can't be compiled with these
+    /// options, e.g. because of unknown keywords in strict mode. Definitions and `$ref` targets are checked too.
+    ///
+    /// ```rust
+    /// # use jsonschema::{JSONSchema, SchemaProblemKind};
+    /// # use serde_json::json;
+    /// let schema = json!({
+    ///     "properties": {
+    ///         "id": {"type": "integer", "minimum": "1"},
+    ///         "tags": {"$ref": "#/definitions/tags"}
+    ///     }
+    /// });
+    /// let problems = JSONSchema::options().check_schema(&schema);
+    /// assert_eq!(problems.len(), 2);
+    /// assert_eq!(problems[0].kind, SchemaProblemKind::MetaSchema);
+    /// assert_eq!(problems[0].schema_path.to_string(), "/properties/id/minimum");
+    /// assert_eq!(problems[1].kind, SchemaProblemKind::Reference);
+    /// assert_eq!(problems[1].schema_path.to_string(), "/properties/tags/$ref");
+    /// ```
+    #[must_use]
+    pub fn check_schema(&self, schema: &Value) -> Vec<SchemaProblem> {
+        let draft = self.detect_draft(schema);
+        let mut options = self.clone();
+        options.with_draft(draft);
+        let mut problems = meta_schema_problems(draft, schema);
+        let id_keyword = if draft == Draft::Draft4 { "id" } else { "$id" };
+        if let Some(Err(error)) = id_of(draft, schema).map(|id| DEFAULT_SCOPE.join(id)) {
+            problems.push(SchemaProblem {
+                kind: SchemaProblemKind::Compilation,
+                schema_path: JSONPointer::default().join(id_keyword),
+                message: format!("Invalid root identifier: {}", error),
+            })
+        }
+        let resolver = Resolver::new(draft, &DEFAULT_SCOPE, schema).unwrap_or_else(|_| {
+            problems.push(SchemaProblem {
+                kind: SchemaProblemKind::Reference,
+                schema_path: JSONPointer::default(),
+                message: format!(
+                    "Some `{}` is not a valid URI, references to identifiers can't be resolved",
+                    id_keyword
+                ),
+            });
+            Resolver::from_pointers(HashMap::new())
+        });
+        let reachable = reachable::find(draft, schema, &DEFAULT_SCOPE, &resolver, &options);
+        problems.extend(
+            reachable
+                .unresolved
+                .into_iter()
+                .map(|(schema_path, message)| SchemaProblem {
+                    kind: SchemaProblemKind::Reference,
+                    schema_path,
+                    message,
+                }),
+        );
+        // Every subschema is compiled once, and problems are collected instead of stopping at the first one
+        let collected = Problems::default();
+        let subschemas = iter::once(Subschema {
+            schema: Cow::Borrowed(schema),
+            scope: DEFAULT_SCOPE.clone(),
+            schema_path: SchemaPath::new(),
+        })
+        .chain(reachable.subschemas);
+        for subschema in subschemas {
+            let location = JSONPointer::from(&subschema.schema_path);
+            let context = CompilationContext::new(subschema.scope, Cow::Borrowed(&options))
+                .with_schema_path(subschema.schema_path)
+                .with_problems(&collected);
+            if let Err(error) = compile_validators(&subschema.schema, &context) {
+                collected.lock().push((location, error.to_string()))
+            }
+        }
+        // Invalid values and identifiers are already reported
+        let reported: HashSet<_> = problems
+            .iter()
+            .map(|problem| problem.schema_path.clone())
+            .collect();
+        problems.extend(
+            collected
+                .into_inner()
+                .into_iter()
+                .filter(|(schema_path, _)| !reported.contains(schema_path))
+                .map(|(schema_path, message)| SchemaProblem {
+                    kind: SchemaProblemKind::Compilation,
+                    schema_path,
+                    message,
+                }),
+        );
+        problems.sort_by_key(|problem| problem.schema_path.to_string());
+        // Some keywords compile the same subschemas twice, e.g. `oneOf` with `discriminator`
+        problems.dedup();
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SchemaProblemKind;
+    use crate::{compilation::JSONSchema, schemas::Draft};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn problems(schema: &Value) -> Vec<(SchemaProblemKind, String)> {
+        JSONSchema::options()
+            .check_schema(schema)
+            .into_iter()
+            .map(|problem| (problem.kind, problem.schema_path.to_string()))
+            .collect()
+    }
+
+    #[test_case(&json!({"type": "strng"}), "/type"; "unknown type")]
+    #[test_case(&json!({"properties": {"a": {"minLength": -1}}}), "/properties/a/minLength"; "negative length")]
+    #[test_case(&json!({"required": "a"}), "/required"; "required is not an array")]
+    #[test_case(&json!({"items": [{"type": 1}]}), "/items/0/type"; "nested in items")]
+    #[test_case(&json!({"definitions": {"a": {"minItems": "1"}}}), "/definitions/a/minItems"; "unused definition")]
+    fn meta_schema(schema: &Value, expected: &str) {
+        assert_eq!(
+            problems(schema),
+            vec![(SchemaProblemKind::MetaSchema, expected.to_string())]
+        );
+    }
+
+    #[test_case(&json!({"$ref": "#/definitions/missing"}), "/$ref"; "missing definition")]
+    #[test_case(&json!({"properties": {"a": {"items": {"$ref": "#/properties/b"}}}}), "/properties/a/items/$ref"; "nested")]
+    #[test_case(&json!({"definitions": {"a": {"$ref": "#/definitions/b"}}}), "/definitions/a/$ref"; "unused definition")]
+    #[test_case(&json!({"$ref": "other.json"}), "/$ref"; "unknown scheme")]
+    fn reference(schema: &Value, expected: &str) {
+        assert_eq!(
+            problems(schema),
+            vec![(SchemaProblemKind::Reference, expected.to_string())]
+        );
+    }
+
+    #[test_case(&json!({"definitions": {"a": {"type": "string"}}, "$ref": "#/definitions/a"}); "definition")]
+    #[test_case(&json!({"$id": "http://example.com/root.json", "definitions": {"a": {"$id": "item.json"}}, "items": {"$ref": "item.json"}}); "identifier")]
+    #[test_case(&json!({"properties": {"a": {"$ref": "#"}}}); "recursive")]
+    #[test_case(&json!({"$id": "root.json", "items": {"$ref": "root.json#/definitions/a"}, "definitions": {"a": {}}}); "relative identifier")]
+    #[test_case(&json!({"type": "object", "properties": {"a": {"type": "string", "pattern": "^a+$"}}}); "valid")]
+    fn no_problems(schema: &Value) {
+        assert_eq!(problems(schema), vec![]);
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        // The meta-schema already reports the pattern, it is not reported twice
+        let schema = json!({"properties": {"a": {"pattern": "(?<=a)b"}}});
+        assert_eq!(
+            problems(&schema),
+            vec![(
+                SchemaProblemKind::MetaSchema,
+                "/properties/a/pattern".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn unknown_format() {
+        let schema = json!({"items": {"format": "custom"}});
+        let problems = JSONSchema::options()
+            .with_strict_mode()
+            .check_schema(&schema);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].kind, SchemaProblemKind::Compilation);
+        assert_eq!(problems[0].schema_path.to_string(), "/items/format");
+        assert_eq!(problems[0].message, "Unknown format `custom`");
+    }
+
+    #[test]
+    fn strict_mode() {
+        let schema = json!({"properties": {"a": {"maxLenght": 1}}});
+        assert_eq!(problems(&schema), vec![]);
+        let problems = JSONSchema::options()
+            .with_strict_mode()
+            .check_schema(&schema);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].kind, SchemaProblemKind::Compilation);
+        assert_eq!(
+            problems[0].schema_path.to_string(),
+            "/properties/a/maxLenght"
+        );
+        assert_eq!(problems[0].message, "Unknown keyword `maxLenght`");
+    }
+
+    #[test]
+    fn strict_mode_reachable() {
+        let schema = json!({
+            "definitions": {"a": {"maxLenght": 1}},
+            "properties": {
+                "b": {"formt": "date"},
+                "c": {"items": {"$ref": "#/default"}}
+            },
+            "default": {"tpye": "string"}
+        });
+        let problems: Vec<_> = JSONSchema::options()
+            .with_strict_mode()
+            .check_schema(&schema)
+            .into_iter()
+            .map(|problem| (problem.kind, problem.schema_path.to_string()))
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                (
+                    SchemaProblemKind::Compilation,
+                    "/definitions/a/maxLenght".to_string()
+                ),
+                (
+                    SchemaProblemKind::Compilation,
+                    "/properties/b/formt".to_string()
+                ),
+                (
+                    SchemaProblemKind::Compilation,
+                    "/properties/c/items/$ref/tpye".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_identifiers() {
+        // Identifiers are collected from the whole document, not only from subschemas
+        let schema = json!({
+            "examples": [{"$id": "http://[invalid"}],
+            "items": {"$ref": "#/definitions/a"},
+            "definitions": {"a": {"type": "string"}}
+        });
+        assert_eq!(
+            problems(&schema),
+            vec![
+                (SchemaProblemKind::Reference, "".to_string()),
+                (SchemaProblemKind::Reference, "/items/$ref".to_string()),
+            ]
+        );
+    }
+
+    #[test_case(Draft::Draft4, &json!({"minimum": 1, "exclusiveMinimum": 1}); "draft 4")]
+    #[test_case(Draft::Draft6, &json!({"exclusiveMinimum": true}); "draft 6")]
+    fn draft(draft: Draft, schema: &Value) {
+        let problems = JSONSchema::options().with_draft(draft).check_schema(schema);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].kind, SchemaProblemKind::MetaSchema);
+    }
+}
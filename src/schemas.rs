@@ -10,6 +10,9 @@ pub enum Draft {
     Draft6,
     /// JSON Schema Draft 7
     Draft7,
+    /// `OpenAPI` 3.0 Schema Object. It is an extended subset of JSON Schema Draft 4 with support for
+    /// `nullable`, `discriminator`, `readOnly` / `writeOnly` and extra formats.
+    OpenAPI30,
 }
 
 impl Default for Draft {
@@ -18,57 +21,84 @@ impl Default for Draft {
     }
 }
 
+/// Which side of an API call is validated.
+/// It defines how `readOnly` and `writeOnly` keywords are applied in the `Draft::OpenAPI30` mode.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum OpenAPIDirection {
+    /// Request payloads can't contain `readOnly` properties.
+    Request,
+    /// Response payloads can't contain `writeOnly` properties.
+    Response,
+}
+
 type CompileFunc =
     fn(&Map<String, Value>, &Value, &CompilationContext) -> Option<keywords::CompilationResult>;
 
 impl Draft {
     pub(crate) fn get_validator(self, keyword: &str) -> Option<CompileFunc> {
         match keyword {
-            "additionalItems" => Some(keywords::additional_items::compile),
+            "additionalItems" => match self {
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => {
+                    Some(keywords::additional_items::compile)
+                }
+                Draft::OpenAPI30 => None,
+            },
             "additionalProperties" => Some(keywords::additional_properties::compile),
             "allOf" => Some(keywords::all_of::compile),
-            "anyOf" => Some(keywords::any_of::compile),
+            "anyOf" => match self {
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => Some(keywords::any_of::compile),
+                Draft::OpenAPI30 => Some(keywords::openapi::discriminator::compile_any_of),
+            },
             "const" => match self {
-                Draft::Draft4 => None,
+                Draft::Draft4 | Draft::OpenAPI30 => None,
                 Draft::Draft6 | Draft::Draft7 => Some(keywords::const_::compile),
             },
             "contains" => match self {
-                Draft::Draft4 => None,
+                Draft::Draft4 | Draft::OpenAPI30 => None,
                 Draft::Draft6 | Draft::Draft7 => Some(keywords::contains::compile),
             },
             "contentMediaType" => match self {
                 Draft::Draft7 | Draft::Draft6 => Some(keywords::content::compile_media_type),
-                Draft::Draft4 => None,
+                Draft::Draft4 | Draft::OpenAPI30 => None,
             },
             "contentEncoding" => match self {
                 Draft::Draft7 | Draft::Draft6 => Some(keywords::content::compile_content_encoding),
-                Draft::Draft4 => None,
+                Draft::Draft4 | Draft::OpenAPI30 => None,
+            },
+            "dependencies" => match self {
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => {
+                    Some(keywords::dependencies::compile)
+                }
+                Draft::OpenAPI30 => None,
             },
-            "dependencies" => Some(keywords::dependencies::compile),
             "enum" => Some(keywords::enum_::compile),
             "exclusiveMaximum" => match self {
                 Draft::Draft7 | Draft::Draft6 => Some(keywords::exclusive_maximum::compile),
-                Draft::Draft4 => None,
+                Draft::Draft4 | Draft::OpenAPI30 => None,
             },
             "exclusiveMinimum" => match self {
                 Draft::Draft7 | Draft::Draft6 => Some(keywords::exclusive_minimum::compile),
-                Draft::Draft4 => None,
+                Draft::Draft4 | Draft::OpenAPI30 => None,
             },
             "format" => Some(keywords::format::compile),
             "if" => match self {
                 Draft::Draft7 => Some(keywords::if_::compile),
-                Draft::Draft6 | Draft::Draft4 => None,
+                Draft::Draft6 | Draft::Draft4 | Draft::OpenAPI30 => None,
             },
             "items" => Some(keywords::items::compile),
             "maximum" => match self {
-                Draft::Draft4 => Some(keywords::legacy::maximum_draft_4::compile),
+                Draft::Draft4 | Draft::OpenAPI30 => {
+                    Some(keywords::legacy::maximum_draft_4::compile)
+                }
                 Draft::Draft6 | Draft::Draft7 => Some(keywords::maximum::compile),
             },
             "maxItems" => Some(keywords::max_items::compile),
             "maxLength" => Some(keywords::max_length::compile),
             "maxProperties" => Some(keywords::max_properties::compile),
             "minimum" => match self {
-                Draft::Draft4 => Some(keywords::legacy::minimum_draft_4::compile),
+                Draft::Draft4 | Draft::OpenAPI30 => {
+                    Some(keywords::legacy::minimum_draft_4::compile)
+                }
                 Draft::Draft6 | Draft::Draft7 => Some(keywords::minimum::compile),
             },
             "minItems" => Some(keywords::min_items::compile),
@@ -76,23 +106,66 @@ impl Draft {
             "minProperties" => Some(keywords::min_properties::compile),
             "multipleOf" => Some(keywords::multiple_of::compile),
             "not" => Some(keywords::not::compile),
-            "oneOf" => Some(keywords::one_of::compile),
+            "oneOf" => match self {
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => Some(keywords::one_of::compile),
+                Draft::OpenAPI30 => Some(keywords::openapi::discriminator::compile_one_of),
+            },
             "pattern" => Some(keywords::pattern::compile),
-            "patternProperties" => Some(keywords::pattern_properties::compile),
+            "patternProperties" => match self {
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => {
+                    Some(keywords::pattern_properties::compile)
+                }
+                Draft::OpenAPI30 => None,
+            },
             "properties" => Some(keywords::properties::compile),
             "propertyNames" => match self {
-                Draft::Draft4 => None,
+                Draft::Draft4 | Draft::OpenAPI30 => None,
                 Draft::Draft6 | Draft::Draft7 => Some(keywords::property_names::compile),
             },
-            "required" => Some(keywords::required::compile),
+            "readOnly" => match self {
+                Draft::OpenAPI30 => Some(keywords::openapi::read_write_only::compile_read_only),
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => None,
+            },
+            "required" => match self {
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => Some(keywords::required::compile),
+                Draft::OpenAPI30 => Some(keywords::openapi::read_write_only::compile_required),
+            },
             "type" => match self {
                 Draft::Draft4 => Some(keywords::legacy::type_draft_4::compile),
                 Draft::Draft6 | Draft::Draft7 => Some(keywords::type_::compile),
+                Draft::OpenAPI30 => Some(keywords::openapi::type_::compile),
             },
             "uniqueItems" => Some(keywords::unique_items::compile),
+            "writeOnly" => match self {
+                Draft::OpenAPI30 => Some(keywords::openapi::read_write_only::compile_write_only),
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => None,
+            },
             _ => None,
         }
     }
+
+    /// Whether the keyword is defined by the draft, either as a validation keyword or as an annotation.
+    /// Used to reject unknown keywords in strict mode.
+    pub(crate) fn is_known_keyword(self, keyword: &str) -> bool {
+        let annotation =
+            match keyword {
+                "$schema" | "title" | "description" | "default" | "definitions"
+                | "errorMessage" => true,
+                "id" => self == Draft::Draft4,
+                "$id" | "examples" => matches!(self, Draft::Draft6 | Draft::Draft7),
+                "$comment" | "then" | "else" | "readOnly" | "writeOnly" | "contentSchema" => {
+                    self == Draft::Draft7
+                }
+                // Boolean flags handled by `minimum` / `maximum`
+                "exclusiveMinimum" | "exclusiveMaximum" => {
+                    matches!(self, Draft::Draft4 | Draft::OpenAPI30)
+                }
+                "nullable" | "discriminator" | "example" | "externalDocs" | "deprecated"
+                | "xml" => self == Draft::OpenAPI30,
+                _ => false,
+            };
+        annotation || self.get_validator(keyword).is_some()
+    }
 }
 
 /// Get the `Draft` from a JSON Schema URL.
@@ -118,10 +191,11 @@ pub(crate) fn draft_from_schema(schema: &Value) -> Option<Draft> {
 #[inline]
 pub(crate) fn id_of(draft: Draft, schema: &Value) -> Option<&str> {
     if let Value::Object(object) = schema {
-        if draft == Draft::Draft4 {
-            object.get("id")
-        } else {
-            object.get("$id")
+        match draft {
+            Draft::Draft4 => object.get("id"),
+            Draft::Draft6 | Draft::Draft7 => object.get("$id"),
+            // OpenAPI Schema Objects don't have identifiers
+            Draft::OpenAPI30 => None,
         }
         .and_then(Value::as_str)
     } else {
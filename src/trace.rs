@@ -0,0 +1,413 @@
+//! Tracing of keyword evaluations during validation.
+//!
+//! If `CompilationOptions::with_tracing` is set, then every compiled keyword is wrapped into `TraceValidator`.
+//! During `JSONSchema::trace` it records keyword evaluations into a thread-local recorder, otherwise it just
+//! delegates to the wrapped validator.
+use crate::{
+    compilation::JSONSchema,
+    error::ErrorIterator,
+    keywords::{BoxedValidator, Subschemas},
+    paths::{InstancePath, JSONPointer},
+    validator::Validate,
+};
+use serde_json::{Map, Value};
+use std::{cell::RefCell, fmt};
+
+/// A keyword evaluation recorded during `JSONSchema::trace`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceNode {
+    /// Keyword name. For boolean schemas it is `true` or `false`.
+    pub keyword: String,
+    /// Location of the keyword within the schema.
+    pub schema_path: JSONPointer,
+    /// Location of the evaluated value within the instance.
+    pub instance_path: JSONPointer,
+    /// Whether the value is valid against the keyword.
+    pub valid: bool,
+    /// Keywords evaluated as a part of this one, e.g. keywords of `properties` subschemas.
+    pub children: Vec<TraceNode>,
+}
+
+/// Keyword evaluations recorded during `JSONSchema::trace`.
+///
+/// Only evaluated keywords are recorded, e.g. `then` is not present if `if` didn't match.
+/// The `Display` implementation renders the trace as an indented tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trace {
+    /// Whether the instance is valid.
+    pub valid: bool,
+    /// Evaluations of keywords in the root schema.
+    pub nodes: Vec<TraceNode>,
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_node(f: &mut fmt::Formatter<'_>, node: &TraceNode, depth: usize) -> fmt::Result {
+            writeln!(
+                f,
+                "{:indent$}#{}: {} (instance: '{}')",
+                "",
+                node.schema_path,
+                if node.valid { "valid" } else { "invalid" },
+                node.instance_path,
+                indent = depth * 2
+            )?;
+            for child in &node.children {
+                write_node(f, child, depth + 1)?;
+            }
+            Ok(())
+        }
+        for node in &self.nodes {
+            write_node(f, node, 0)?;
+        }
+        Ok(())
+    }
+}
+
+struct Recorder {
+    // Unfinished evaluations together with addresses of values and instance paths they were started with
+    stack: Vec<(TraceNode, *const Value, InstancePath)>,
+    nodes: Vec<TraceNode>,
+    root: *const Value,
+}
+
+thread_local! {
+    static RECORDER: RefCell<Option<Recorder>> = const { RefCell::new(None) };
+}
+
+/// Whether tracing is active.
+fn is_active() -> bool {
+    RECORDER.with(|recorder| recorder.borrow().is_some())
+}
+
+/// The instance path of `instance` if tracing is active and the path is known.
+///
+/// Keywords like `contains` or `propertyNames` check nested values via `is_valid` without passing their paths.
+/// Such checks are not recorded - the path is known only for the value of the innermost evaluation.
+fn known_path(instance: &Value) -> Option<InstancePath> {
+    RECORDER.with(|recorder| {
+        let recorder = recorder.borrow();
+        let recorder = recorder.as_ref()?;
+        let address: *const Value = instance;
+        match recorder.stack.last() {
+            Some((_, current, path)) if *current == address => Some(path.clone()),
+            None if recorder.root == address => Some(InstancePath::new()),
+            _ => None,
+        }
+    })
+}
+
+fn begin(keyword: &str, schema_path: &JSONPointer, instance: &Value, instance_path: &InstancePath) {
+    RECORDER.with(|recorder| {
+        if let Some(recorder) = recorder.borrow_mut().as_mut() {
+            recorder.stack.push((
+                TraceNode {
+                    keyword: keyword.to_string(),
+                    schema_path: schema_path.clone(),
+                    instance_path: instance_path.into(),
+                    valid: true,
+                    children: Vec::new(),
+                },
+                instance,
+                instance_path.clone(),
+            ))
+        }
+    })
+}
+
+fn end(valid: bool) {
+    RECORDER.with(|recorder| {
+        if let Some(recorder) = recorder.borrow_mut().as_mut() {
+            if let Some((mut node, _, _)) = recorder.stack.pop() {
+                node.valid = valid;
+                match recorder.stack.last_mut() {
+                    Some((parent, _, _)) => parent.children.push(node),
+                    None => recorder.nodes.push(node),
+                }
+            }
+        }
+    })
+}
+
+/// Records evaluations of the wrapped keyword.
+///
+/// While tracing, errors are collected eagerly, so evaluations of nested keywords are recorded as children.
+/// `is_valid` calls are evaluated via `validate` to know instance paths, therefore keywords are never evaluated
+/// in parallel and evaluations are recorded in the same order as they happen. Calls for values with unknown
+/// paths are not recorded.
+pub(crate) struct TraceValidator {
+    inner: BoxedValidator,
+    keyword: String,
+    schema_path: JSONPointer,
+}
+
+impl TraceValidator {
+    #[inline]
+    fn record<'a>(
+        &self,
+        instance: &Value,
+        instance_path: &InstancePath,
+        validate: impl FnOnce() -> ErrorIterator<'a>,
+    ) -> ErrorIterator<'a> {
+        begin(&self.keyword, &self.schema_path, instance, instance_path);
+        let errors: Vec<_> = validate().collect();
+        end(errors.is_empty());
+        Box::new(errors.into_iter())
+    }
+}
+
+macro_rules! trace_impl {
+    ($method_suffix:tt, $instance_type: ty, $validate_type: ty) => {
+        paste::item! {
+            #[inline]
+            fn [<is_valid_ $method_suffix>](
+                &self,
+                schema: &JSONSchema,
+                instance: &Value,
+                instance_value: $instance_type,
+            ) -> bool {
+                match known_path(instance) {
+                    Some(instance_path) => self
+                        .[<validate_ $method_suffix>](schema, instance, instance_value, &instance_path)
+                        .next()
+                        .is_none(),
+                    None => self.inner.[<is_valid_ $method_suffix>](schema, instance, instance_value),
+                }
+            }
+
+            #[inline]
+            fn [<validate_ $method_suffix>]<'a>(
+                &'a self,
+                schema: &'a JSONSchema,
+                instance: &'a Value,
+                instance_value: $validate_type,
+                instance_path: &InstancePath,
+            ) -> ErrorIterator<'a> {
+                if is_active() {
+                    self.record(instance, instance_path, || {
+                        self.inner
+                            .[<validate_ $method_suffix>](schema, instance, instance_value, instance_path)
+                    })
+                } else {
+                    self.inner
+                        .[<validate_ $method_suffix>](schema, instance, instance_value, instance_path)
+                }
+            }
+        }
+    };
+}
+
+impl Validate for TraceValidator {
+    fn subschemas(&self) -> Subschemas<'_> {
+        self.inner.subschemas()
+    }
+
+    trace_impl!(array, &[Value], &'a [Value]);
+    trace_impl!(boolean, bool, bool);
+    trace_impl!(null, (), ());
+    trace_impl!(number, f64, f64);
+    trace_impl!(object, &Map<String, Value>, &'a Map<String, Value>);
+    trace_impl!(signed_integer, i64, i64);
+    trace_impl!(string, &str, &'a str);
+    trace_impl!(unsigned_integer, u64, u64);
+
+    #[inline]
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        match known_path(instance) {
+            Some(instance_path) => self
+                .validate(schema, instance, &instance_path)
+                .next()
+                .is_none(),
+            None => self.inner.is_valid(schema, instance),
+        }
+    }
+
+    #[inline]
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        if is_active() {
+            self.record(instance, instance_path, || {
+                self.inner.validate(schema, instance, instance_path)
+            })
+        } else {
+            self.inner.validate(schema, instance, instance_path)
+        }
+    }
+}
+
+impl ToString for TraceValidator {
+    fn to_string(&self) -> String {
+        self.inner.to_string()
+    }
+}
+
+#[inline]
+pub(crate) fn wrap(
+    inner: BoxedValidator,
+    keyword: &str,
+    schema_path: &JSONPointer,
+) -> BoxedValidator {
+    Box::new(TraceValidator {
+        inner,
+        keyword: keyword.to_string(),
+        schema_path: schema_path.clone(),
+    })
+}
+
+impl<'a> JSONSchema<'a> {
+    /// Validate `instance` and record every evaluated keyword together with its result.
+    ///
+    /// The schema should be compiled with `CompilationOptions::with_tracing`, otherwise no keywords are recorded.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({"properties": {"age": {"type": "integer", "minimum": 18}}});
+    /// let compiled = JSONSchema::options()
+    ///     .with_tracing()
+    ///     .compile(&schema)
+    ///     .expect("A valid schema");
+    /// let trace = compiled.trace(&json!({"age": 15}));
+    /// assert!(!trace.valid);
+    /// assert_eq!(
+    ///     trace.to_string(),
+    ///     "#/properties: invalid (instance: '')\n  \
+    ///        #/properties/age/minimum: invalid (instance: '/age')\n  \
+    ///        #/properties/age/type: valid (instance: '/age')\n"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn trace(&self, instance: &Value) -> Trace {
+        RECORDER.with(|recorder| {
+            *recorder.borrow_mut() = Some(Recorder {
+                stack: Vec::new(),
+                nodes: Vec::new(),
+                root: instance,
+            })
+        });
+        let instance_path = InstancePath::new();
+        // Evaluate all keywords in the root schema, even if some of them already failed
+        let mut valid = true;
+        for validator in &self.validators {
+            valid &= validator
+                .validate(self, instance, &instance_path)
+                .next()
+                .is_none();
+        }
+        let recorder = RECORDER.with(|recorder| recorder.borrow_mut().take());
+        Trace {
+            valid,
+            nodes: recorder.map(|recorder| recorder.nodes).unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Trace, TraceNode};
+    use crate::compilation::JSONSchema;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn trace(schema: &Value, instance: &Value) -> Trace {
+        let compiled = JSONSchema::options()
+            .with_tracing()
+            .compile(schema)
+            .unwrap();
+        // Tracing doesn't change regular validation
+        let trace = compiled.trace(instance);
+        assert_eq!(compiled.is_valid(instance), trace.valid);
+        assert_eq!(compiled.validate(instance).is_ok(), trace.valid);
+        trace
+    }
+
+    fn flatten(nodes: &[TraceNode], output: &mut Vec<String>) {
+        for node in nodes {
+            output.push(format!(
+                "{} {} {}",
+                node.schema_path, node.instance_path, node.valid
+            ));
+            flatten(&node.children, output);
+        }
+    }
+
+    #[test_case(
+        json!({"if": {"minimum": 10}, "then": {"multipleOf": 2}, "else": {"maximum": 0}}),
+        json!(5),
+        &["/if  false", "/if/minimum  false", "/else/maximum  false"];
+        "if else"
+    )]
+    #[test_case(
+        json!({"oneOf": [{"type": "string"}, {"type": "integer"}]}),
+        json!(1),
+        &["/oneOf  true", "/oneOf/0/type  false", "/oneOf/1/type  true"];
+        "one of"
+    )]
+    #[test_case(
+        json!({"items": {"minimum": 2}}),
+        json!([1, 3]),
+        &["/items  false", "/items/minimum /0 false", "/items/minimum /1 true"];
+        "items"
+    )]
+    #[test_case(
+        json!({"items": {"$ref": "#/definitions/a"}, "definitions": {"a": {"type": "string"}}}),
+        json!(["a"]),
+        &["/items  true", "/items/$ref /0 true", "/items/$ref/type /0 true"];
+        "reference"
+    )]
+    #[test_case(
+        json!({"not": false}),
+        json!(1),
+        &["/not  true", "/not  false"];
+        "boolean schema"
+    )]
+    #[test_case(
+        json!({"items": {"anyOf": [{"type": "string"}]}}),
+        json!(["a"]),
+        &["/items  true", "/items/anyOf /0 true", "/items/anyOf/0/type /0 true"];
+        "nested"
+    )]
+    // Items are checked via `is_valid` without their paths, therefore they are not recorded
+    #[test_case(
+        json!({"contains": {"type": "string"}}),
+        json!([1, 2, "a"]),
+        &["/contains  true"];
+        "contains"
+    )]
+    // Property names are reported at the location of the object
+    #[test_case(
+        json!({"propertyNames": {"maxLength": 1}}),
+        json!({"a": 1, "bc": 2}),
+        &["/propertyNames  false", "/propertyNames/maxLength  true", "/propertyNames/maxLength  false"];
+        "property names"
+    )]
+    fn evaluations(schema: Value, instance: Value, expected: &[&str]) {
+        let trace = trace(&schema, &instance);
+        let mut actual = vec![];
+        flatten(&trace.nodes, &mut actual);
+        assert_eq!(actual, expected)
+    }
+
+    #[test]
+    fn without_tracing() {
+        let schema = json!({"type": "string"});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let trace = compiled.trace(&json!(1));
+        assert!(!trace.valid);
+        assert!(trace.nodes.is_empty());
+    }
+
+    #[test]
+    fn many_items() {
+        // Parallel validation of arrays is not used while tracing
+        let schema = json!({"items": {"type": "integer"}});
+        let instance = json!([1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        let trace = trace(&schema, &instance);
+        assert!(trace.valid);
+        assert_eq!(trace.nodes[0].children.len(), 10);
+    }
+}
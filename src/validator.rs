@@ -1,14 +1,25 @@
 use crate::{
     compilation::JSONSchema,
     error::{error, no_error, ErrorIterator, ValidationError},
+    keywords::Subschemas,
+    paths::InstancePath,
 };
 use serde_json::{Map, Value};
 use std::fmt;
 
 pub(crate) trait Validate: Send + Sync + ToString {
     #[inline]
-    fn build_validation_error<'a>(&self, instance: &'a Value) -> ValidationError<'a> {
-        ValidationError::unexpected(instance, &self.to_string())
+    fn build_validation_error<'a>(
+        &self,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ValidationError<'a> {
+        ValidationError::unexpected(instance_path.into(), instance, &self.to_string())
+    }
+
+    /// Subschemas compiled as a part of this keyword. They are used to build `JSONSchema::tree`.
+    fn subschemas(&self) -> Subschemas<'_> {
+        Vec::new()
     }
 
     #[inline]
@@ -112,121 +123,146 @@ pub(crate) trait Validate: Send + Sync + ToString {
 
     #[inline]
     fn validate_array<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         instance_value: &'a [Value],
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
         if self.is_valid_array(schema, instance, instance_value) {
             no_error()
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
     #[inline]
     fn validate_boolean<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         instance_value: bool,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
         if self.is_valid_boolean(schema, instance, instance_value) {
             no_error()
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
     #[inline]
     fn validate_object<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         instance_value: &'a Map<String, Value>,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
         if self.is_valid_object(schema, instance, instance_value) {
             no_error()
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
     #[inline]
     fn validate_null<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         _: (),
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
         if self.is_valid_null(schema, instance, ()) {
             no_error()
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
     #[inline]
     fn validate_number<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         instance_value: f64,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
         if self.is_valid_number(schema, instance, instance_value) {
             no_error()
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
     #[inline]
     fn validate_signed_integer<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         instance_value: i64,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
         if self.is_valid_signed_integer(schema, instance, instance_value) {
             no_error()
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
     #[inline]
     fn validate_string<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         instance_value: &'a str,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
         if self.is_valid_string(schema, instance, instance_value) {
             no_error()
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
     #[inline]
     fn validate_unsigned_integer<'a>(
-        &self,
+        &'a self,
         schema: &'a JSONSchema,
         instance: &'a Value,
         instance_value: u64,
+        instance_path: &InstancePath,
     ) -> ErrorIterator<'a> {
         if self.is_valid_unsigned_integer(schema, instance, instance_value) {
             no_error()
         } else {
-            error(self.build_validation_error(instance))
+            error(self.build_validation_error(instance, instance_path))
         }
     }
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         match instance {
-            Value::Array(instance_array) => self.validate_array(schema, instance, instance_array),
+            Value::Array(instance_array) => {
+                self.validate_array(schema, instance, instance_array, instance_path)
+            }
             Value::Bool(instance_boolean) => {
-                self.validate_boolean(schema, instance, *instance_boolean)
+                self.validate_boolean(schema, instance, *instance_boolean, instance_path)
             }
-            Value::Null => self.validate_null(schema, instance, ()),
+            Value::Null => self.validate_null(schema, instance, (), instance_path),
             Value::Number(instance_number) => {
                 if let Some(instance_unsigned_integer) = instance_number.as_u64() {
-                    self.validate_unsigned_integer(schema, instance, instance_unsigned_integer)
+                    self.validate_unsigned_integer(
+                        schema,
+                        instance,
+                        instance_unsigned_integer,
+                        instance_path,
+                    )
                 } else if let Some(instance_signed_integer) = instance_number.as_i64() {
-                    self.validate_signed_integer(schema, instance, instance_signed_integer)
+                    self.validate_signed_integer(
+                        schema,
+                        instance,
+                        instance_signed_integer,
+                        instance_path,
+                    )
                 } else {
                     self.validate_number(
                         schema,
@@ -234,14 +270,15 @@ pub(crate) trait Validate: Send + Sync + ToString {
                         instance_number
                             .as_f64()
                             .expect("A JSON number will always be representable as f64"),
+                        instance_path,
                     )
                 }
             }
             Value::Object(instance_object) => {
-                self.validate_object(schema, instance, instance_object)
+                self.validate_object(schema, instance, instance_object, instance_path)
             }
             Value::String(instance_string) => {
-                self.validate_string(schema, instance, instance_string)
+                self.validate_string(schema, instance, instance_string, instance_path)
             }
         }
     }